@@ -200,6 +200,7 @@ window.addEventListener('pointerdown', handlePointerDown, true);
 document.addEventListener('mousemove', handleMouseMove, true);
 document.addEventListener('keydown', handleKeyDown, true);
 document.addEventListener('input', handleInputChange, true);
+document.addEventListener('change', handleFileChange, true);
 document.addEventListener('focusin', handleFocusIn, true);
 document.addEventListener('focusout', handleFocusOut, true);
 document.addEventListener('scroll', handleScroll, true);
@@ -404,6 +405,10 @@ function isInputElement(el: Element): el is HTMLInputElement | HTMLTextAreaEleme
   return el.tagName === 'INPUT' || el.tagName === 'TEXTAREA';
 }
 
+function isFileInput(el: Element): el is HTMLInputElement {
+  return el.tagName === 'INPUT' && (el as HTMLInputElement).type === 'file';
+}
+
 function handleFocusIn(e: FocusEvent): void {
   const target = e.target as Element;
   if (isInputElement(target)) {
@@ -422,7 +427,7 @@ function handleFocusOut(e: FocusEvent): void {
 function handleInputChange(e: Event): void {
   if (!isTopFrame) return;
   const target = e.target as HTMLInputElement | HTMLTextAreaElement;
-  if (!isInputElement(target)) return;
+  if (!isInputElement(target) || isFileInput(target)) return;
 
   const pending = inputState.pending.get(target);
   if (pending?.timer) clearTimeout(pending.timer);
@@ -472,6 +477,28 @@ function flushInputEvent(element: Element): void {
   });
 }
 
+function handleFileChange(e: Event): void {
+  if (!isTopFrame) return;
+  const target = e.target as Element;
+  if (!isFileInput(target) || !target.files?.length) return;
+
+  const info = getElementInfo(target);
+  const targetInfo = buildTargetInfo(target, info);
+
+  sendToCli({
+    upload: {
+      aria: targetInfo.aria,
+      css: targetInfo.css,
+      xpath: targetInfo.xpath,
+      testid: targetInfo.testid,
+      rect: targetInfo.rect,
+      files: Array.from(target.files, (file) => file.name),
+      url: window.location.href,
+      ts: Date.now(),
+    },
+  });
+}
+
 function handleScroll(): void {
   if (!isTopFrame) return;
 
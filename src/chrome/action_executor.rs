@@ -22,6 +22,18 @@ impl Default for ActionConfig {
     }
 }
 
+/// Upper bound for throttling-scaled timeouts, so a throttled session still fails
+/// within minutes
+pub const MAX_TIMEOUT_MULTIPLIER: f64 = 10.0;
+
+impl ActionConfig {
+    /// Page loads slow down with both the network and the CPU
+    pub fn navigation_multiplier(&self) -> f64 {
+        (self.cpu_timeout_multiplier * self.network_timeout_multiplier)
+            .clamp(1.0, MAX_TIMEOUT_MULTIPLIER)
+    }
+}
+
 pub struct ActionExecutor {
     page: Arc<Page>,
     config: ActionConfig,
@@ -40,7 +52,7 @@ impl ActionExecutor {
 
     fn navigation_timeout(&self) -> Duration {
         Duration::from_millis(
-            (ms::SELECTOR_TIMEOUT as f64 * 2.0 * self.config.network_timeout_multiplier) as u64,
+            (ms::SELECTOR_TIMEOUT as f64 * 2.0 * self.config.navigation_multiplier()) as u64,
         )
     }
 }
@@ -262,4 +274,23 @@ mod tests {
         assert_eq!((100.0 * config.cpu_timeout_multiplier) as u64, 200);
         assert_eq!((10000.0 * config.network_timeout_multiplier) as u64, 100000);
     }
+
+    #[test]
+    fn test_navigation_multiplier() {
+        let config = ActionConfig {
+            cpu_timeout_multiplier: 4.0,
+            network_timeout_multiplier: 2.5,
+            ..Default::default()
+        };
+        assert_eq!(config.navigation_multiplier(), 10.0);
+        assert_eq!(ActionConfig::default().navigation_multiplier(), 1.0);
+
+        // Slow 3G with a 4x CPU slowdown stays capped
+        let combined = ActionConfig {
+            cpu_timeout_multiplier: 4.0,
+            network_timeout_multiplier: 6.25,
+            ..Default::default()
+        };
+        assert_eq!(combined.navigation_multiplier(), MAX_TIMEOUT_MULTIPLIER);
+    }
 }
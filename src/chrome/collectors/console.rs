@@ -8,6 +8,7 @@ use chrono::{DateTime, Utc};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use tokio::sync::broadcast;
 
 use super::super::storage::SessionStorage;
 
@@ -76,22 +77,31 @@ impl EventMetadata for ConsoleMessage {
 pub struct ConsoleCollector {
     storage: Arc<SessionStorage>,
     filter_config: FilterConfig,
+    sender: broadcast::Sender<ConsoleMessage>,
 }
 
 impl ConsoleCollector {
     pub fn new(storage: Arc<SessionStorage>, filter_config: FilterConfig) -> Self {
+        let (sender, _) = broadcast::channel(256);
         Self {
             storage,
             filter_config,
+            sender,
         }
     }
 
+    /// Live feed of messages as they are stored
+    pub fn subscribe(&self) -> broadcast::Receiver<ConsoleMessage> {
+        self.sender.subscribe()
+    }
+
     pub async fn attach(&self, page: &Arc<Page>) -> Result<()> {
         page.execute(RuntimeEnableParams::default())
             .await
             .map_err(|e| ChromeError::General(format!("Failed to enable Runtime domain: {}", e)))?;
 
         let storage = self.storage.clone();
+        let sender = self.sender.clone();
         let allowed_levels = self.filter_config.console_levels.clone();
 
         let mut stream = page
@@ -152,6 +162,7 @@ impl ConsoleCollector {
                 };
 
                 storage.append("console", &message).ok();
+                let _ = sender.send(message);
             }
         });
 
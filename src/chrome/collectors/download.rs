@@ -0,0 +1,294 @@
+//! Download Collector - saves downloads into the session directory and tracks their progress.
+//!
+//! Headless Chrome drops downloads unless told where to put them, so every attached page
+//! sets `Browser.setDownloadBehavior` to the session's `downloads` directory with events
+//! enabled. Files are written under their download GUID; the suggested filename is kept
+//! alongside for `downloads save`.
+//!
+//! Each state change is stored in the `downloads` collection. Progress updates are stored
+//! at most once per second per download.
+
+use crate::chrome::event_store::EventMetadata;
+use crate::{ChromeError, Result};
+use chromiumoxide::Page;
+use chromiumoxide::cdp::browser_protocol::browser::{
+    DownloadProgressState, EventDownloadProgress, EventDownloadWillBegin,
+    SetDownloadBehaviorBehavior, SetDownloadBehaviorParams,
+};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock, broadcast};
+
+use super::super::storage::SessionStorage;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadState {
+    InProgress,
+    Completed,
+    Canceled,
+}
+
+impl std::fmt::Display for DownloadState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadState::InProgress => write!(f, "in progress"),
+            DownloadState::Completed => write!(f, "completed"),
+            DownloadState::Canceled => write!(f, "canceled"),
+        }
+    }
+}
+
+impl From<&DownloadProgressState> for DownloadState {
+    fn from(state: &DownloadProgressState) -> Self {
+        match state {
+            DownloadProgressState::InProgress => Self::InProgress,
+            DownloadProgressState::Completed => Self::Completed,
+            DownloadProgressState::Canceled => Self::Canceled,
+        }
+    }
+}
+
+/// A download as of its latest progress event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Download {
+    pub guid: String,
+    pub url: String,
+    /// Filename the page suggested
+    pub filename: String,
+    /// Where Chrome writes the file
+    pub path: String,
+    pub state: DownloadState,
+    pub received_bytes: u64,
+    pub total_bytes: u64,
+    pub started_at: DateTime<Utc>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl EventMetadata for Download {
+    fn event_type(&self) -> &'static str {
+        match self.state {
+            DownloadState::InProgress if self.received_bytes == 0 => "started",
+            DownloadState::InProgress => "progress",
+            DownloadState::Completed => "completed",
+            DownloadState::Canceled => "canceled",
+        }
+    }
+    fn timestamp_ms(&self) -> Option<u64> {
+        Some(self.timestamp.timestamp_millis() as u64)
+    }
+}
+
+pub struct DownloadCollector {
+    storage: Arc<SessionStorage>,
+    downloads: Arc<RwLock<Vec<Download>>>,
+    sender: broadcast::Sender<Download>,
+    /// Downloads already returned by `wait_finished`
+    claimed: Mutex<HashSet<String>>,
+}
+
+impl DownloadCollector {
+    const COLLECTION: &'static str = "downloads";
+
+    pub fn new(storage: Arc<SessionStorage>) -> Self {
+        let (sender, _) = broadcast::channel(64);
+        Self {
+            storage,
+            downloads: Arc::new(RwLock::new(Vec::new())),
+            sender,
+            claimed: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub async fn attach(&self, page: &Arc<Page>) -> Result<()> {
+        let dir = self.storage.downloads_dir()?;
+        let params = SetDownloadBehaviorParams::builder()
+            .behavior(SetDownloadBehaviorBehavior::AllowAndName)
+            .download_path(dir.display().to_string())
+            .events_enabled(true)
+            .build()
+            .map_err(ChromeError::General)?;
+        // Pages keep working without it; downloads then go wherever Chrome puts them
+        if let Err(e) = page.execute(params).await {
+            tracing::warn!("Failed to enable downloads: {}", e);
+        }
+
+        let mut begins = page
+            .event_listener::<EventDownloadWillBegin>()
+            .await
+            .map_err(|e| {
+                ChromeError::General(format!("Failed to attach download listener: {}", e))
+            })?;
+        let mut progress = page
+            .event_listener::<EventDownloadProgress>()
+            .await
+            .map_err(|e| {
+                ChromeError::General(format!("Failed to attach download listener: {}", e))
+            })?;
+
+        let storage = self.storage.clone();
+        let downloads = self.downloads.clone();
+        let sender = self.sender.clone();
+
+        tokio::spawn(async move {
+            let mut last_stored: HashMap<String, Instant> = HashMap::new();
+            loop {
+                // `biased` keeps a download's begin ahead of its first progress event
+                let update = tokio::select! {
+                    biased;
+                    Some(event) = begins.next() => {
+                        let mut list = downloads.write().await;
+                        // Every page with events enabled reports the same download
+                        if list.iter().any(|d| d.guid == event.guid) {
+                            continue;
+                        }
+                        let now = Utc::now();
+                        let download = Download {
+                            guid: event.guid.clone(),
+                            url: event.url.clone(),
+                            filename: event.suggested_filename.clone(),
+                            path: dir.join(&event.guid).display().to_string(),
+                            state: DownloadState::InProgress,
+                            received_bytes: 0,
+                            total_bytes: 0,
+                            started_at: now,
+                            timestamp: now,
+                        };
+                        list.push(download.clone());
+                        download
+                    }
+                    Some(event) = progress.next() => {
+                        let mut list = downloads.write().await;
+                        let Some(download) = list.iter_mut().find(|d| d.guid == event.guid) else {
+                            continue;
+                        };
+                        let state = DownloadState::from(&event.state);
+                        let received = event.received_bytes as u64;
+                        if download.state == state && download.received_bytes == received {
+                            continue;
+                        }
+                        download.state = state;
+                        download.received_bytes = received;
+                        download.total_bytes = event.total_bytes as u64;
+                        download.timestamp = Utc::now();
+                        if let Some(ref path) = event.file_path {
+                            download.path = path.clone();
+                        }
+                        download.clone()
+                    }
+                    else => break,
+                };
+
+                let throttled = update.state == DownloadState::InProgress
+                    && update.received_bytes > 0
+                    && last_stored
+                        .get(&update.guid)
+                        .is_some_and(|at| at.elapsed() < PROGRESS_INTERVAL);
+                if !throttled {
+                    last_stored.insert(update.guid.clone(), Instant::now());
+                    storage.append(Self::COLLECTION, &update).ok();
+                }
+                let _ = sender.send(update);
+            }
+        });
+
+        Ok(())
+    }
+
+    pub async fn list(&self) -> Vec<Download> {
+        self.downloads.read().await.clone()
+    }
+
+    /// Looks up a download by GUID or by its index in `list`
+    pub async fn get(&self, id: &str) -> Option<Download> {
+        let list = self.downloads.read().await;
+        match id.parse::<usize>() {
+            Ok(index) => list.get(index).cloned(),
+            Err(_) => list.iter().find(|d| d.guid == id).cloned(),
+        }
+    }
+
+    /// Waits for the first download not returned by an earlier call to finish. A download
+    /// that finished before the call still counts, so a click followed by a wait never
+    /// misses a fast download.
+    pub async fn wait_finished(&self, timeout: Duration) -> Result<Download> {
+        let mut updates = self.sender.subscribe();
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if let Some(download) = self.claim_finished().await {
+                return Ok(download);
+            }
+            match tokio::time::timeout_at(deadline, updates.recv()).await {
+                Ok(Ok(_)) | Ok(Err(broadcast::error::RecvError::Lagged(_))) => {}
+                Ok(Err(broadcast::error::RecvError::Closed)) => {
+                    return Err(ChromeError::General("Download tracking stopped".into()));
+                }
+                Err(_) => {
+                    return Err(ChromeError::General(format!(
+                        "No download finished within {}s",
+                        timeout.as_secs()
+                    )));
+                }
+            }
+        }
+    }
+
+    async fn claim_finished(&self) -> Option<Download> {
+        let list = self.downloads.read().await;
+        let mut claimed = self.claimed.lock().await;
+        let download = list
+            .iter()
+            .find(|d| d.state != DownloadState::InProgress && !claimed.contains(&d.guid))?;
+        claimed.insert(download.guid.clone());
+        Some(download.clone())
+    }
+
+    pub fn count(&self) -> usize {
+        self.storage.count(Self::COLLECTION)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn download(state: DownloadState, received_bytes: u64) -> Download {
+        Download {
+            guid: "g1".into(),
+            url: "https://example.com/report.csv".into(),
+            filename: "report.csv".into(),
+            path: "/tmp/g1".into(),
+            state,
+            received_bytes,
+            total_bytes: 10,
+            started_at: Utc::now(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_event_type() {
+        assert_eq!(
+            download(DownloadState::InProgress, 0).event_type(),
+            "started"
+        );
+        assert_eq!(
+            download(DownloadState::InProgress, 5).event_type(),
+            "progress"
+        );
+        assert_eq!(
+            download(DownloadState::Completed, 10).event_type(),
+            "completed"
+        );
+        assert_eq!(
+            download(DownloadState::Canceled, 5).event_type(),
+            "canceled"
+        );
+    }
+}
@@ -10,6 +10,7 @@ use tokio::sync::broadcast;
 pub enum ExtensionEvent {
     Click(TargetInfo),
     Input(InputData),
+    Upload(UploadData),
     Select(TargetInfo),
     Hover(TargetInfo),
     Scroll(ScrollData),
@@ -28,6 +29,7 @@ impl EventMetadata for ExtensionEvent {
         match self {
             Self::Click(_) => "click",
             Self::Input(_) => "input",
+            Self::Upload(_) => "upload",
             Self::Select(_) => "select",
             Self::Hover(_) => "hover",
             Self::Scroll(_) => "scroll",
@@ -45,6 +47,7 @@ impl EventMetadata for ExtensionEvent {
         match self {
             Self::Click(t) | Self::Select(t) | Self::Hover(t) => t.ts,
             Self::Input(d) => d.target.ts,
+            Self::Upload(d) => d.target.ts,
             Self::Scroll(d) => d.ts,
             Self::KeyPress(d) => d.ts,
             Self::Screenshot(d) => d.ts,
@@ -105,6 +108,14 @@ pub struct InputData {
     pub value: Option<String>,
 }
 
+/// Files picked in an `<input type=file>`; browsers expose only their names
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadData {
+    #[serde(flatten)]
+    pub target: TargetInfo,
+    pub files: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScrollData {
     pub x: i32,
@@ -1,5 +1,6 @@
 pub mod console;
 pub mod dialog;
+pub mod download;
 pub mod extension;
 pub mod issues;
 pub mod network;
@@ -13,9 +14,10 @@ use std::sync::Arc;
 
 pub use console::{ConsoleCollector, ConsoleLevel, ConsoleMessage};
 pub use dialog::{Dialog, DialogCollector, DialogResult, DialogType};
+pub use download::{Download, DownloadCollector, DownloadState};
 pub use extension::{ExtensionCollector, ExtensionEvent, RecordingMarker, TargetInfo};
 pub use issues::{DevToolsIssue, IssuesCollector};
-pub use network::{NetworkCollector, NetworkRequest};
+pub use network::{NetworkCollector, NetworkFilter, NetworkRequest, RequestTiming};
 pub use pageerror::{PageError, PageErrorCollector};
 pub use trace::{TraceCollector, TraceData, TraceStatus};
 
@@ -27,6 +29,7 @@ pub struct CollectorSet {
     pub pageerror: PageErrorCollector,
     pub issues: IssuesCollector,
     pub dialog: DialogCollector,
+    pub downloads: DownloadCollector,
     pub extension: ExtensionCollector,
     pub trace: TraceCollector,
 }
@@ -47,6 +50,7 @@ impl CollectorSet {
                 dialog_config.behavior,
                 dialog_config.prompt_text,
             ),
+            downloads: DownloadCollector::new(storage.clone()),
             extension: ExtensionCollector::new(storage.clone()),
             trace: TraceCollector::new(storage),
         }
@@ -58,6 +62,7 @@ impl CollectorSet {
         self.pageerror.attach(page).await?;
         self.issues.attach(page).await?;
         self.dialog.attach(page).await?;
+        self.downloads.attach(page).await?;
         Ok(())
     }
 
@@ -1,18 +1,24 @@
 use crate::chrome::event_store::EventMetadata;
 use crate::{ChromeError, Result, config::FilterConfig};
+use base64::Engine;
 use chromiumoxide::{
     Page,
     cdp::browser_protocol::network::{
-        EnableParams as NetworkEnableParams, EventRequestWillBeSent, EventResponseReceived,
-        GetResponseBodyParams,
+        EnableParams as NetworkEnableParams, EventLoadingFailed, EventLoadingFinished,
+        EventRequestServedFromCache, EventRequestWillBeSent, EventResponseReceived,
+        GetRequestPostDataParams, GetResponseBodyParams, Request, RequestId, ResourceTiming,
+        Response,
     },
 };
 use chrono::{DateTime, Utc};
 use futures::StreamExt;
+use futures::stream::{BoxStream, select_all};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::sync::{RwLock, broadcast};
 use url::Url;
 
 use super::super::storage::SessionStorage;
@@ -27,10 +33,47 @@ pub struct NetworkRequest {
     pub resource_type: Option<String>,
     pub mime_type: Option<String>,
     pub request_headers: Option<serde_json::Value>,
+    /// POST/PUT payload, decoded as UTF-8 and truncated like response bodies
+    #[serde(default)]
+    pub request_body: Option<String>,
     pub response_headers: Option<serde_json::Value>,
     pub response_body: Option<String>,
+    /// `response_body` was cut at `network_max_body_size`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub response_body_truncated: bool,
+    /// Decoded length of the full response body, when it was fetched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_body_size: Option<i64>,
     pub response_size: Option<i64>,
     pub timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub protocol: Option<String>,
+    #[serde(default)]
+    pub remote_ip: Option<String>,
+    #[serde(default)]
+    pub from_cache: bool,
+    /// URLs this request was redirected from, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redirect_chain: Vec<String>,
+    /// Set when loading failed, e.g. `net::ERR_NAME_NOT_RESOLVED`
+    #[serde(default)]
+    pub error_text: Option<String>,
+    #[serde(default)]
+    pub timing: Option<RequestTiming>,
+    /// The response has arrived but its body is still loading, e.g. a stream
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub in_flight: bool,
+}
+
+impl NetworkRequest {
+    /// Short status label for text output: the status code, `failed`, or `pending`
+    pub fn status_label(&self) -> String {
+        match (self.status, &self.error_text) {
+            (_, Some(_)) => "failed".to_string(),
+            (Some(status), None) => status.to_string(),
+            (None, None) => "pending".to_string(),
+        }
+    }
 }
 
 impl EventMetadata for NetworkRequest {
@@ -42,114 +85,386 @@ impl EventMetadata for NetworkRequest {
     }
 }
 
+/// Phase durations in milliseconds. A phase is `None` when it did not happen
+/// (e.g. no DNS lookup on a reused connection) or was not reported.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestTiming {
+    pub dns_ms: Option<f64>,
+    pub connect_ms: Option<f64>,
+    pub tls_ms: Option<f64>,
+    pub send_ms: Option<f64>,
+    pub ttfb_ms: Option<f64>,
+    pub download_ms: Option<f64>,
+    pub total_ms: Option<f64>,
+}
+
+impl RequestTiming {
+    fn from_resource_timing(t: &ResourceTiming) -> Self {
+        Self {
+            dns_ms: phase(t.dns_start, t.dns_end),
+            connect_ms: phase(t.connect_start, t.connect_end),
+            tls_ms: phase(t.ssl_start, t.ssl_end),
+            send_ms: phase(t.send_start, t.send_end),
+            ttfb_ms: phase(t.send_end, t.receive_headers_end),
+            download_ms: None,
+            total_ms: None,
+        }
+    }
+}
+
+/// CDP reports unset phase boundaries as -1
+fn phase(start: f64, end: f64) -> Option<f64> {
+    (start >= 0.0 && end >= start).then(|| round_ms(end - start))
+}
+
+fn round_ms(ms: f64) -> f64 {
+    (ms * 1000.0).round() / 1000.0
+}
+
 #[derive(Debug, Clone)]
 struct PendingRequest {
-    id: String,
-    url: String,
-    method: String,
-    resource_type: Option<String>,
-    request_headers: Option<serde_json::Value>,
-    timestamp: DateTime<Utc>,
+    request: NetworkRequest,
+    /// Monotonic seconds when the request was issued
+    started: f64,
+    /// Monotonic seconds when response headers were received
+    headers_received: Option<f64>,
+    responded: bool,
+    /// Monotonic finish time and encoded length from loadingFinished; it may
+    /// arrive before responseReceived has been processed
+    finished: Option<(f64, f64)>,
+    /// The request has a body that Chrome did not inline in requestWillBeSent
+    post_data_pending: bool,
+    /// Storage row once the response has been recorded
+    row: Option<i64>,
+    /// Last event for this request, for evicting requests that never finish
+    seen: Instant,
+}
+
+impl PendingRequest {
+    fn apply_response(&mut self, response: &Response) {
+        let req = &mut self.request;
+        req.status = Some(response.status as u16);
+        req.status_text = Some(response.status_text.clone());
+        req.mime_type = Some(response.mime_type.clone());
+        req.response_headers = Some(response.headers.inner().clone());
+        req.response_size = Some(response.encoded_data_length as i64);
+        req.protocol = response.protocol.clone();
+        req.remote_ip = response.remote_ip_address.clone();
+        req.from_cache |= response.from_disk_cache.unwrap_or(false)
+            || response.from_prefetch_cache.unwrap_or(false)
+            || response.from_service_worker.unwrap_or(false);
+
+        if let Some(ref timing) = response.timing {
+            req.timing = Some(RequestTiming::from_resource_timing(timing));
+            if timing.receive_headers_end >= 0.0 {
+                self.headers_received =
+                    Some(timing.request_time + timing.receive_headers_end / 1000.0);
+            }
+        }
+        self.responded = true;
+    }
+
+    fn complete(&mut self, end: f64) {
+        let timing = self
+            .request
+            .timing
+            .get_or_insert_with(RequestTiming::default);
+        timing.total_ms = Some(round_ms((end - self.started).max(0.0) * 1000.0));
+        if let Some(headers_received) = self.headers_received {
+            timing.download_ms = Some(round_ms((end - headers_received).max(0.0) * 1000.0));
+        }
+    }
+}
+
+/// How long events that arrived before their requestWillBeSent are kept
+const EARLY_EVENT_TTL: Duration = Duration::from_secs(30);
+/// How long a request may go without events before it is dropped from tracking
+const PENDING_TTL: Duration = Duration::from_secs(600);
+
+enum NetworkEvent {
+    WillBeSent(Arc<EventRequestWillBeSent>),
+    Response(Arc<EventResponseReceived>),
+    Finished(Arc<EventLoadingFinished>),
+    Failed(Arc<EventLoadingFailed>),
+    FromCache(Arc<EventRequestServedFromCache>),
+}
+
+impl NetworkEvent {
+    fn request_id(&self) -> &str {
+        match self {
+            Self::WillBeSent(e) => e.request_id.inner(),
+            Self::Response(e) => e.request_id.inner(),
+            Self::Finished(e) => e.request_id.inner(),
+            Self::Failed(e) => e.request_id.inner(),
+            Self::FromCache(e) => e.request_id.inner(),
+        }
+    }
+}
+
+/// Events whose requestWillBeSent has not been handled yet. The CDP streams are merged
+/// without preserving order, so these are replayed once the request is known.
+#[derive(Default)]
+struct EarlyEvents {
+    events: HashMap<String, Vec<(Instant, NetworkEvent)>>,
+}
+
+impl EarlyEvents {
+    fn push(&mut self, event: NetworkEvent) {
+        self.events
+            .entry(event.request_id().to_string())
+            .or_default()
+            .push((Instant::now(), event));
+    }
+
+    fn take(&mut self, request_id: &str) -> Vec<NetworkEvent> {
+        self.events
+            .remove(request_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, event)| event)
+            .collect()
+    }
+
+    /// Drops events for requests that were filtered out or never announced
+    fn evict(&mut self, ttl: Duration) {
+        self.events.retain(|_, events| {
+            events.retain(|(at, _)| at.elapsed() < ttl);
+            !events.is_empty()
+        });
+    }
 }
 
 pub struct NetworkCollector {
     storage: Arc<SessionStorage>,
     pending: Arc<RwLock<HashMap<String, PendingRequest>>>,
     filter_config: FilterConfig,
+    sender: broadcast::Sender<NetworkRequest>,
 }
 
 impl NetworkCollector {
     pub fn new(storage: Arc<SessionStorage>, filter_config: FilterConfig) -> Self {
+        let (sender, _) = broadcast::channel(256);
         Self {
             storage,
             pending: Arc::new(RwLock::new(HashMap::new())),
             filter_config,
+            sender,
         }
     }
 
+    /// Live feed of requests as their responses arrive, and again as they complete, fail or
+    /// redirect
+    pub fn subscribe(&self) -> broadcast::Receiver<NetworkRequest> {
+        self.sender.subscribe()
+    }
+
     pub async fn attach(&self, page: &Arc<Page>) -> Result<()> {
         page.execute(NetworkEnableParams::default())
             .await
             .map_err(|e| ChromeError::General(format!("Failed to enable Network domain: {}", e)))?;
 
+        let listener_err =
+            |e| ChromeError::General(format!("Failed to attach network listener: {}", e));
+
+        // All lifecycle events are handled on one task so a request's state
+        // transitions are never processed concurrently
+        let streams: Vec<BoxStream<'static, NetworkEvent>> = vec![
+            page.event_listener::<EventRequestWillBeSent>()
+                .await
+                .map_err(listener_err)?
+                .map(NetworkEvent::WillBeSent)
+                .boxed(),
+            page.event_listener::<EventResponseReceived>()
+                .await
+                .map_err(listener_err)?
+                .map(NetworkEvent::Response)
+                .boxed(),
+            page.event_listener::<EventLoadingFinished>()
+                .await
+                .map_err(listener_err)?
+                .map(NetworkEvent::Finished)
+                .boxed(),
+            page.event_listener::<EventLoadingFailed>()
+                .await
+                .map_err(listener_err)?
+                .map(NetworkEvent::Failed)
+                .boxed(),
+            page.event_listener::<EventRequestServedFromCache>()
+                .await
+                .map_err(listener_err)?
+                .map(NetworkEvent::FromCache)
+                .boxed(),
+        ];
+        let mut events = select_all(streams);
+
+        let sink = RequestSink {
+            storage: self.storage.clone(),
+            sender: self.sender.clone(),
+        };
         let pending = self.pending.clone();
         let filter_config = self.filter_config.clone();
-
-        let mut request_stream = page
-            .event_listener::<EventRequestWillBeSent>()
-            .await
-            .map_err(|e| {
-                ChromeError::General(format!("Failed to attach network listener: {}", e))
-            })?;
+        let page_clone = page.clone();
 
         tokio::spawn(async move {
-            while let Some(event) = request_stream.next().await {
-                let url = &event.request.url;
-                let resource_type = event.r#type.as_ref().map(|t| format!("{:?}", t));
-
-                if !should_collect_request(url, resource_type.as_deref(), &filter_config) {
-                    continue;
+            let mut early = EarlyEvents::default();
+            let mut last_eviction = Instant::now();
+            while let Some(event) = events.next().await {
+                if last_eviction.elapsed() >= EARLY_EVENT_TTL {
+                    early.evict(EARLY_EVENT_TTL);
+                    pending
+                        .write()
+                        .await
+                        .retain(|_, req| req.seen.elapsed() < PENDING_TTL);
+                    last_eviction = Instant::now();
                 }
 
-                let pending_req = PendingRequest {
-                    id: event.request_id.inner().to_string(),
-                    url: url.clone(),
-                    method: event.request.method.clone(),
-                    resource_type,
-                    request_headers: Some(event.request.headers.inner().clone()),
-                    timestamp: Utc::now(),
-                };
-
-                pending
-                    .write()
-                    .await
-                    .insert(pending_req.id.clone(), pending_req);
-            }
-        });
+                let mut queue = VecDeque::from([event]);
+                while let Some(event) = queue.pop_front() {
+                    let request_id = event.request_id().to_string();
+                    if !matches!(event, NetworkEvent::WillBeSent(_))
+                        && !pending.read().await.contains_key(&request_id)
+                    {
+                        early.push(event);
+                        continue;
+                    }
 
-        let storage = self.storage.clone();
-        let pending = self.pending.clone();
-        let page_clone = page.clone();
-        let max_body_size = self.filter_config.network_max_body_size;
+                    match event {
+                        NetworkEvent::WillBeSent(event) => {
+                            let mut redirect_chain = Vec::new();
 
-        let mut response_stream = page
-            .event_listener::<EventResponseReceived>()
-            .await
-            .map_err(|e| {
-                ChromeError::General(format!("Failed to attach response listener: {}", e))
-            })?;
+                            // A redirect reuses the request id: the previous hop is complete
+                            if let Some(ref redirect) = event.redirect_response
+                                && let Some(mut hop) = pending.write().await.remove(&request_id)
+                            {
+                                hop.apply_response(redirect);
+                                hop.complete(*event.timestamp.inner());
+                                hop.request.in_flight = false;
+                                redirect_chain = hop.request.redirect_chain.clone();
+                                redirect_chain.push(hop.request.url.clone());
+                                sink.record(hop.row, &hop.request);
+                            }
 
-        tokio::spawn(async move {
-            while let Some(event) = response_stream.next().await {
-                let request_id = event.request_id.clone();
-                let request_id_str = request_id.inner().to_string();
-
-                if let Some(pending_req) = pending.write().await.remove(&request_id_str) {
-                    let response = &event.response;
-                    let mime_type = response.mime_type.clone();
-
-                    let response_body = if should_capture_body(&mime_type) {
-                        fetch_response_body(&page_clone, request_id.clone(), max_body_size).await
-                    } else {
-                        None
-                    };
-
-                    let request = NetworkRequest {
-                        id: pending_req.id,
-                        url: pending_req.url,
-                        method: pending_req.method,
-                        status: Some(response.status as u16),
-                        status_text: Some(response.status_text.clone()),
-                        resource_type: pending_req.resource_type,
-                        mime_type: Some(mime_type),
-                        request_headers: pending_req.request_headers,
-                        response_headers: Some(response.headers.inner().clone()),
-                        response_body,
-                        response_size: Some(response.encoded_data_length as i64),
-                        timestamp: pending_req.timestamp,
-                    };
-
-                    storage.append("network", &request).ok();
+                            let url = &event.request.url;
+                            let resource_type = event.r#type.as_ref().map(|t| format!("{:?}", t));
+
+                            if !should_collect_request(
+                                url,
+                                resource_type.as_deref(),
+                                &filter_config,
+                            ) {
+                                early.take(&request_id);
+                                continue;
+                            }
+
+                            let request_body = inline_post_data(
+                                &event.request,
+                                filter_config.network_max_body_size,
+                            );
+                            let post_data_pending =
+                                event.request.has_post_data == Some(true) && request_body.is_none();
+
+                            let pending_req = PendingRequest {
+                                request: NetworkRequest {
+                                    id: request_id.clone(),
+                                    url: url.clone(),
+                                    method: event.request.method.clone(),
+                                    status: None,
+                                    status_text: None,
+                                    resource_type,
+                                    mime_type: None,
+                                    request_headers: Some(event.request.headers.inner().clone()),
+                                    request_body,
+                                    response_headers: None,
+                                    response_body: None,
+                                    response_body_truncated: false,
+                                    response_body_size: None,
+                                    response_size: None,
+                                    timestamp: Utc::now(),
+                                    protocol: None,
+                                    remote_ip: None,
+                                    from_cache: false,
+                                    redirect_chain,
+                                    error_text: None,
+                                    timing: None,
+                                    in_flight: false,
+                                },
+                                started: *event.timestamp.inner(),
+                                headers_received: None,
+                                responded: false,
+                                finished: None,
+                                post_data_pending,
+                                row: None,
+                                seen: Instant::now(),
+                            };
+
+                            pending
+                                .write()
+                                .await
+                                .insert(request_id.clone(), pending_req);
+                            queue.extend(early.take(&request_id));
+                        }
+                        NetworkEvent::Response(event) => {
+                            let finished = {
+                                let mut pending = pending.write().await;
+                                let Some(req) = pending.get_mut(&request_id) else {
+                                    continue;
+                                };
+                                req.apply_response(&event.response);
+                                req.finished.is_some()
+                            };
+                            if finished {
+                                finalize(&pending, &sink, &page_clone, &request_id, &filter_config)
+                                    .await;
+                            } else {
+                                record_in_flight(
+                                    &pending,
+                                    &sink,
+                                    &page_clone,
+                                    &request_id,
+                                    &filter_config,
+                                )
+                                .await;
+                            }
+                        }
+                        NetworkEvent::Finished(event) => {
+                            let responded = {
+                                let mut pending = pending.write().await;
+                                let Some(req) = pending.get_mut(&request_id) else {
+                                    continue;
+                                };
+                                req.finished =
+                                    Some((*event.timestamp.inner(), event.encoded_data_length));
+                                req.seen = Instant::now();
+                                req.responded
+                            };
+                            if responded {
+                                finalize(&pending, &sink, &page_clone, &request_id, &filter_config)
+                                    .await;
+                            }
+                        }
+                        NetworkEvent::Failed(event) => {
+                            let failed = pending.write().await.remove(&request_id);
+                            if let Some(mut req) = failed {
+                                req.complete(*event.timestamp.inner());
+                                req.request.error_text = Some(failure_reason(&event));
+                                req.request.in_flight = false;
+                                if req.post_data_pending {
+                                    req.request.request_body = fetch_post_data(
+                                        &page_clone,
+                                        event.request_id.clone(),
+                                        filter_config.network_max_body_size,
+                                    )
+                                    .await;
+                                }
+                                sink.record(req.row, &req.request);
+                            }
+                        }
+                        NetworkEvent::FromCache(_) => {
+                            if let Some(req) = pending.write().await.get_mut(&request_id) {
+                                req.request.from_cache = true;
+                            }
+                        }
+                    }
                 }
             }
         });
@@ -161,21 +476,8 @@ impl NetworkCollector {
         self.storage.read_all("network")
     }
 
-    pub fn get_requests_filtered(
-        &self,
-        domain: Option<&str>,
-        status: Option<u16>,
-    ) -> Result<Vec<NetworkRequest>> {
-        let requests: Vec<NetworkRequest> = self.storage.read_all("network")?;
-
-        Ok(requests
-            .into_iter()
-            .filter(|r| {
-                let domain_match = domain.map(|d| r.url.contains(d)).unwrap_or(true);
-                let status_match = status.map(|s| r.status == Some(s)).unwrap_or(true);
-                domain_match && status_match
-            })
-            .collect())
+    pub fn get_requests_filtered(&self, filter: &NetworkFilter) -> Result<Vec<NetworkRequest>> {
+        filter.apply(self.storage.read_all("network")?)
     }
 
     pub fn count(&self) -> usize {
@@ -183,6 +485,122 @@ impl NetworkCollector {
     }
 }
 
+/// Persists requests and fans them out to live subscribers
+struct RequestSink {
+    storage: Arc<SessionStorage>,
+    sender: broadcast::Sender<NetworkRequest>,
+}
+
+impl RequestSink {
+    /// Updates the request's existing `row`, or stores a new one and returns it
+    fn record(&self, row: Option<i64>, request: &NetworkRequest) -> Option<i64> {
+        let row = match row {
+            Some(row) => {
+                self.storage.update(row, request).ok();
+                Some(row)
+            }
+            None => self.storage.insert("network", request).ok(),
+        };
+        let _ = self.sender.send(request.clone());
+        row
+    }
+}
+
+/// Stores a request whose response arrived before its body finished loading, so streams
+/// and long-running downloads show up while they are still open
+async fn record_in_flight(
+    pending: &RwLock<HashMap<String, PendingRequest>>,
+    sink: &RequestSink,
+    page: &Arc<Page>,
+    request_id: &str,
+    filter_config: &FilterConfig,
+) {
+    let needs_post_data = pending
+        .read()
+        .await
+        .get(request_id)
+        .is_some_and(|req| req.post_data_pending);
+    let request_body = match needs_post_data {
+        true => {
+            fetch_post_data(
+                page,
+                request_id.to_string().into(),
+                filter_config.network_max_body_size,
+            )
+            .await
+        }
+        false => None,
+    };
+
+    let mut pending = pending.write().await;
+    let Some(req) = pending.get_mut(request_id) else {
+        return;
+    };
+    if needs_post_data {
+        req.request.request_body = request_body;
+        req.post_data_pending = false;
+    }
+    req.request.in_flight = true;
+    req.seen = Instant::now();
+    req.row = sink.record(req.row, &req.request);
+}
+
+async fn finalize(
+    pending: &RwLock<HashMap<String, PendingRequest>>,
+    sink: &RequestSink,
+    page: &Arc<Page>,
+    request_id: &str,
+    filter_config: &FilterConfig,
+) {
+    let Some(mut req) = pending.write().await.remove(request_id) else {
+        return;
+    };
+    req.request.in_flight = false;
+
+    if let Some((end, encoded_length)) = req.finished {
+        req.complete(end);
+        req.request.response_size = Some(encoded_length as i64);
+    }
+
+    if req.post_data_pending {
+        req.request.request_body = fetch_post_data(
+            page,
+            request_id.to_string().into(),
+            filter_config.network_max_body_size,
+        )
+        .await;
+    }
+
+    if req
+        .request
+        .mime_type
+        .as_deref()
+        .is_some_and(should_capture_body)
+        && let Some(body) = fetch_response_body(
+            page,
+            request_id.to_string().into(),
+            filter_config.network_max_body_size,
+        )
+        .await
+    {
+        req.request.response_body_truncated = body.truncated;
+        req.request.response_body_size = Some(body.size);
+        req.request.response_body = Some(body.text);
+    }
+
+    sink.record(req.row, &req.request);
+}
+
+fn failure_reason(event: &EventLoadingFailed) -> String {
+    if event.canceled == Some(true) {
+        return format!("{} (canceled)", event.error_text);
+    }
+    match event.blocked_reason {
+        Some(ref reason) => format!("{} (blocked: {})", event.error_text, reason.as_ref()),
+        None => event.error_text.clone(),
+    }
+}
+
 fn should_collect_request(url: &str, resource_type: Option<&str>, config: &FilterConfig) -> bool {
     if url.starts_with("chrome-extension://") {
         return false;
@@ -218,27 +636,438 @@ fn should_capture_body(mime_type: &str) -> bool {
         || mime_type.contains("html")
 }
 
+/// A response body as stored, possibly cut at `network_max_body_size`
+struct CapturedBody {
+    text: String,
+    truncated: bool,
+    /// Decoded length of the full body
+    size: i64,
+}
+
+impl CapturedBody {
+    fn new(body: &str, base64_encoded: bool, max_body_size: usize) -> Self {
+        let size = if base64_encoded {
+            base64::engine::general_purpose::STANDARD
+                .decode(body)
+                .map_or(body.len(), |bytes| bytes.len())
+        } else {
+            body.len()
+        };
+        Self {
+            text: truncate_body(body, max_body_size),
+            truncated: body.len() > max_body_size,
+            size: size as i64,
+        }
+    }
+}
+
 async fn fetch_response_body(
     page: &Arc<Page>,
     request_id: chromiumoxide::cdp::browser_protocol::network::RequestId,
     max_body_size: usize,
-) -> Option<String> {
+) -> Option<CapturedBody> {
     let params = GetResponseBodyParams::new(request_id);
 
     match page.execute(params).await {
-        Ok(result) => {
-            let body = &result.body;
-            if body.len() > max_body_size {
-                let truncated = body
-                    .char_indices()
-                    .take_while(|(i, _)| *i < max_body_size)
-                    .map(|(_, c)| c)
-                    .collect::<String>();
-                Some(format!("{}... [truncated]", truncated))
-            } else {
-                Some(body.clone())
+        Ok(result) => Some(CapturedBody::new(
+            &result.body,
+            result.base64_encoded,
+            max_body_size,
+        )),
+        Err(_) => None,
+    }
+}
+
+/// Decodes the body Chrome inlines in requestWillBeSent as base64 entries
+fn inline_post_data(request: &Request, max_body_size: usize) -> Option<String> {
+    let entries = request.post_data_entries.as_ref()?;
+    let mut bytes = Vec::new();
+    for bytes_b64 in entries.iter().filter_map(|e| e.bytes.as_ref()) {
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(AsRef::<str>::as_ref(bytes_b64))
+            .ok()?;
+        bytes.extend(decoded);
+    }
+    if bytes.is_empty() {
+        return None;
+    }
+    Some(truncate_body(
+        &String::from_utf8_lossy(&bytes),
+        max_body_size,
+    ))
+}
+
+async fn fetch_post_data(
+    page: &Arc<Page>,
+    request_id: RequestId,
+    max_body_size: usize,
+) -> Option<String> {
+    match page
+        .execute(GetRequestPostDataParams::new(request_id))
+        .await
+    {
+        Ok(result) => Some(truncate_body(&result.post_data, max_body_size)),
+        Err(_) => None,
+    }
+}
+
+fn truncate_body(body: &str, max_body_size: usize) -> String {
+    if body.len() > max_body_size {
+        let truncated = body
+            .char_indices()
+            .take_while(|(i, _)| *i < max_body_size)
+            .map(|(_, c)| c)
+            .collect::<String>();
+        format!("{}... [truncated]", truncated)
+    } else {
+        body.to_string()
+    }
+}
+
+/// Criteria for `network` and `history network`; every field that is set must match
+#[derive(Debug, Clone, Default)]
+pub struct NetworkFilter {
+    /// Substring of the URL
+    pub domain: Option<String>,
+    /// `404`, `4xx`, `400-499`, `failed`, or `pending`
+    pub status: Option<String>,
+    pub method: Option<String>,
+    pub resource_type: Option<String>,
+    /// Substring of the response MIME type, e.g. `json`
+    pub mime_type: Option<String>,
+    /// Regular expression matched against the URL
+    pub url_pattern: Option<String>,
+    pub min_duration_ms: Option<f64>,
+    pub min_size: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StatusFilter {
+    Range(u16, u16),
+    Failed,
+    Pending,
+}
+
+impl StatusFilter {
+    fn parse(s: &str) -> Result<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        let invalid = || {
+            ChromeError::General(format!(
+                "Invalid status filter: {} (expected e.g. 404, 4xx, 400-499, failed)",
+                s
+            ))
+        };
+        match s.as_str() {
+            "failed" => return Ok(Self::Failed),
+            "pending" => return Ok(Self::Pending),
+            _ => {}
+        }
+        if let Some(class) = s.strip_suffix("xx") {
+            let class: u16 = class.parse().map_err(|_| invalid())?;
+            if !(1..=5).contains(&class) {
+                return Err(invalid());
             }
+            return Ok(Self::Range(class * 100, class * 100 + 99));
         }
-        Err(_) => None,
+        if let Some((start, end)) = s.split_once('-') {
+            let start: u16 = start.trim().parse().map_err(|_| invalid())?;
+            let end: u16 = end.trim().parse().map_err(|_| invalid())?;
+            if start > end {
+                return Err(invalid());
+            }
+            return Ok(Self::Range(start, end));
+        }
+        let code: u16 = s.parse().map_err(|_| invalid())?;
+        Ok(Self::Range(code, code))
+    }
+
+    fn matches(&self, request: &NetworkRequest) -> bool {
+        match self {
+            Self::Failed => request.error_text.is_some(),
+            Self::Pending => {
+                request.error_text.is_none() && (request.in_flight || request.status.is_none())
+            }
+            Self::Range(start, end) => request.status.is_some_and(|s| (*start..=*end).contains(&s)),
+        }
+    }
+}
+
+impl NetworkFilter {
+    pub fn apply(&self, requests: Vec<NetworkRequest>) -> Result<Vec<NetworkRequest>> {
+        let matcher = self.matcher()?;
+        Ok(requests.into_iter().filter(|r| matcher(r)).collect())
+    }
+
+    /// Validates and compiles the filter once, for matching a stream of requests
+    pub fn matcher(&self) -> Result<impl Fn(&NetworkRequest) -> bool + Send + Sync + 'static> {
+        let status = self
+            .status
+            .as_deref()
+            .map(StatusFilter::parse)
+            .transpose()?;
+        let url_regex = self
+            .url_pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| ChromeError::General(format!("Invalid URL pattern: {}", e)))?;
+        let filter = self.clone();
+
+        Ok(move |r: &NetworkRequest| filter.matches(r, status, url_regex.as_ref()))
+    }
+
+    fn matches(
+        &self,
+        r: &NetworkRequest,
+        status: Option<StatusFilter>,
+        url_regex: Option<&Regex>,
+    ) -> bool {
+        let contains_ci = |haystack: Option<&str>, needle: &str| {
+            haystack.is_some_and(|h| h.to_lowercase().contains(&needle.to_lowercase()))
+        };
+
+        self.domain
+            .as_ref()
+            .is_none_or(|d| r.url.contains(d.as_str()))
+            && status.is_none_or(|s| s.matches(r))
+            && self
+                .method
+                .as_ref()
+                .is_none_or(|m| r.method.eq_ignore_ascii_case(m))
+            && self.resource_type.as_ref().is_none_or(|t| {
+                r.resource_type
+                    .as_deref()
+                    .is_some_and(|rt| rt.eq_ignore_ascii_case(t))
+            })
+            && self
+                .mime_type
+                .as_ref()
+                .is_none_or(|m| contains_ci(r.mime_type.as_deref(), m))
+            && url_regex.is_none_or(|re| re.is_match(&r.url))
+            && self.min_duration_ms.is_none_or(|min| {
+                r.timing
+                    .as_ref()
+                    .and_then(|t| t.total_ms)
+                    .is_some_and(|total| total >= min)
+            })
+            && self
+                .min_size
+                .is_none_or(|min| r.response_size.is_some_and(|size| size >= min))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(started: f64) -> PendingRequest {
+        PendingRequest {
+            request: NetworkRequest {
+                id: "1".to_string(),
+                url: "https://example.com/".to_string(),
+                method: "GET".to_string(),
+                status: None,
+                status_text: None,
+                resource_type: None,
+                mime_type: None,
+                request_headers: None,
+                request_body: None,
+                response_headers: None,
+                response_body: None,
+                response_body_truncated: false,
+                response_body_size: None,
+                response_size: None,
+                timestamp: Utc::now(),
+                protocol: None,
+                remote_ip: None,
+                from_cache: false,
+                redirect_chain: Vec::new(),
+                error_text: None,
+                timing: None,
+                in_flight: false,
+            },
+            started,
+            headers_received: None,
+            responded: false,
+            finished: None,
+            post_data_pending: false,
+            row: None,
+            seen: Instant::now(),
+        }
+    }
+
+    fn finished_event(request_id: &str) -> NetworkEvent {
+        let event: EventLoadingFinished = serde_json::from_value(serde_json::json!({
+            "requestId": request_id,
+            "timestamp": 12.5,
+            "encodedDataLength": 2048.0
+        }))
+        .unwrap();
+        NetworkEvent::Finished(Arc::new(event))
+    }
+
+    #[test]
+    fn test_early_events_replayed_and_evicted() {
+        let mut early = EarlyEvents::default();
+        early.push(finished_event("7"));
+        early.push(finished_event("8"));
+
+        let replayed = early.take("7");
+        assert_eq!(replayed.len(), 1);
+        assert_eq!(replayed[0].request_id(), "7");
+        assert!(early.take("7").is_empty());
+
+        early.evict(Duration::ZERO);
+        assert!(early.take("8").is_empty());
+    }
+
+    #[test]
+    fn test_phase_skips_unset_boundaries() {
+        assert_eq!(phase(-1.0, -1.0), None);
+        assert_eq!(phase(2.0, 1.0), None);
+        assert_eq!(phase(1.5, 4.0), Some(2.5));
+    }
+
+    #[test]
+    fn test_complete_computes_total_and_download() {
+        let mut req = pending(10.0);
+        req.headers_received = Some(10.2);
+        req.complete(10.25);
+
+        let timing = req.request.timing.unwrap();
+        assert_eq!(timing.total_ms, Some(250.0));
+        assert_eq!(timing.download_ms, Some(50.0));
+    }
+
+    #[test]
+    fn test_status_label() {
+        let mut req = pending(0.0).request;
+        assert_eq!(req.status_label(), "pending");
+        req.status = Some(404);
+        assert_eq!(req.status_label(), "404");
+        req.error_text = Some("net::ERR_ABORTED".to_string());
+        assert_eq!(req.status_label(), "failed");
+    }
+
+    #[test]
+    fn test_status_filter_parse() {
+        assert_eq!(
+            StatusFilter::parse("404").unwrap(),
+            StatusFilter::Range(404, 404)
+        );
+        assert_eq!(
+            StatusFilter::parse("4XX").unwrap(),
+            StatusFilter::Range(400, 499)
+        );
+        assert_eq!(
+            StatusFilter::parse("200-299").unwrap(),
+            StatusFilter::Range(200, 299)
+        );
+        assert_eq!(StatusFilter::parse("failed").unwrap(), StatusFilter::Failed);
+        assert!(StatusFilter::parse("9xx").is_err());
+        assert!(StatusFilter::parse("500-400").is_err());
+        assert!(StatusFilter::parse("ok").is_err());
+        assert_eq!(
+            StatusFilter::parse("pending").unwrap(),
+            StatusFilter::Pending
+        );
+    }
+
+    #[test]
+    fn test_captured_body_records_truncation() {
+        let body = CapturedBody::new(&"x".repeat(300), false, 100);
+        assert!(body.truncated);
+        assert_eq!(body.size, 300);
+        assert!(body.text.ends_with("... [truncated]"));
+
+        let small = CapturedBody::new("aGVsbG8=", true, 100);
+        assert!(!small.truncated);
+        assert_eq!(small.size, 5);
+    }
+
+    #[test]
+    fn test_pending_filter_matches_in_flight() {
+        let mut stream = pending(0.0).request;
+        stream.status = Some(200);
+        stream.in_flight = true;
+        let mut done = pending(0.0).request;
+        done.status = Some(200);
+
+        let filter = NetworkFilter {
+            status: Some("pending".to_string()),
+            ..Default::default()
+        };
+        let matched = filter.apply(vec![stream, done]).unwrap();
+        assert_eq!(matched.len(), 1);
+        assert!(matched[0].in_flight);
+    }
+
+    #[test]
+    fn test_filter_apply() {
+        let mut api = pending(0.0).request;
+        api.url = "https://example.com/api/users".to_string();
+        api.method = "POST".to_string();
+        api.status = Some(422);
+        api.resource_type = Some("Fetch".to_string());
+        api.mime_type = Some("application/json".to_string());
+        api.response_size = Some(2048);
+        api.timing = Some(RequestTiming {
+            total_ms: Some(350.0),
+            ..Default::default()
+        });
+
+        let mut page = pending(0.0).request;
+        page.status = Some(200);
+        page.resource_type = Some("Document".to_string());
+        page.mime_type = Some("text/html".to_string());
+
+        let requests = vec![api, page];
+        let filter = NetworkFilter {
+            status: Some("4xx".to_string()),
+            method: Some("post".to_string()),
+            resource_type: Some("fetch".to_string()),
+            mime_type: Some("JSON".to_string()),
+            url_pattern: Some(r"/api/\w+$".to_string()),
+            min_duration_ms: Some(300.0),
+            min_size: Some(1024),
+            ..Default::default()
+        };
+        let matched = filter.apply(requests.clone()).unwrap();
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].method, "POST");
+
+        let slow = NetworkFilter {
+            min_duration_ms: Some(500.0),
+            ..Default::default()
+        };
+        assert!(slow.apply(requests.clone()).unwrap().is_empty());
+
+        let bad_regex = NetworkFilter {
+            url_pattern: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert!(bad_regex.apply(requests).is_err());
+    }
+
+    #[test]
+    fn test_inline_post_data() {
+        let json = serde_json::json!({
+            "url": "https://example.com/login",
+            "method": "POST",
+            "headers": {},
+            "hasPostData": true,
+            "postDataEntries": [{"bytes": "dXNlcj1h"}, {"bytes": "JnBhc3M9Yg=="}],
+            "initialPriority": "High",
+            "referrerPolicy": "no-referrer"
+        });
+        let request: Request = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            inline_post_data(&request, 1024).as_deref(),
+            Some("user=a&pass=b")
+        );
+        assert_eq!(
+            inline_post_data(&request, 4).as_deref(),
+            Some("user... [truncated]")
+        );
     }
 }
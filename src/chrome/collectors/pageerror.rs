@@ -5,6 +5,7 @@ use chrono::{DateTime, Utc};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use tokio::sync::broadcast;
 
 use super::super::storage::SessionStorage;
 
@@ -29,15 +30,23 @@ impl EventMetadata for PageError {
 
 pub struct PageErrorCollector {
     storage: Arc<SessionStorage>,
+    sender: broadcast::Sender<PageError>,
 }
 
 impl PageErrorCollector {
     pub fn new(storage: Arc<SessionStorage>) -> Self {
-        Self { storage }
+        let (sender, _) = broadcast::channel(256);
+        Self { storage, sender }
+    }
+
+    /// Live feed of errors as they are stored
+    pub fn subscribe(&self) -> broadcast::Receiver<PageError> {
+        self.sender.subscribe()
     }
 
     pub async fn attach(&self, page: &Arc<Page>) -> Result<()> {
         let storage = self.storage.clone();
+        let sender = self.sender.clone();
 
         let mut stream = page
             .event_listener::<EventExceptionThrown>()
@@ -77,6 +86,7 @@ impl PageErrorCollector {
                 };
 
                 storage.append("pageerror", &error).ok();
+                let _ = sender.send(error);
             }
         });
 
@@ -108,6 +108,23 @@ impl EventStore {
         Ok(conn.last_insert_rowid())
     }
 
+    /// Replaces the event stored under `id` by `append`
+    pub fn update<T: Serialize + EventMetadata>(&self, id: i64, item: &T) -> Result<()> {
+        let data = serde_json::to_string(item)
+            .map_err(|e| ChromeError::General(format!("Serialization error: {}", e)))?;
+
+        let timestamp_ms = item.timestamp_ms().map(|t| t as i64);
+
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE events SET timestamp_ms = ?1, data = ?2 WHERE id = ?3",
+            params![timestamp_ms, data, id],
+        )
+        .map_err(|e| ChromeError::General(format!("Update error: {}", e)))?;
+
+        Ok(())
+    }
+
     pub fn append_raw<T: Serialize>(&self, collection: &str, item: &T) -> Result<i64> {
         let data = serde_json::to_string(item)
             .map_err(|e| ChromeError::General(format!("Serialization error: {}", e)))?;
@@ -336,6 +353,23 @@ mod tests {
         assert_eq!(events[0], event);
     }
 
+    #[test]
+    fn test_update() {
+        let (store, _temp) = create_test_store();
+
+        let mut event = TestEvent {
+            event_type: "click".to_string(),
+            ts: 1000,
+            data: "pending".to_string(),
+        };
+        let id = store.append("test", &event).unwrap();
+        event.data = "done".to_string();
+        store.update(id, &event).unwrap();
+
+        let events: Vec<TestEvent> = store.read_all("test").unwrap();
+        assert_eq!(events, vec![event]);
+    }
+
     #[test]
     fn test_count() {
         let (store, _temp) = create_test_store();
@@ -0,0 +1,478 @@
+//! Frames as CDP reports them: the page's frame tree plus out-of-process iframes, which
+//! Chrome runs in targets of their own. Scripts aimed at a frame run in an isolated world
+//! created in that frame, and positions measured there are shifted by the frame's offset
+//! so input events can be dispatched on the page.
+
+use crate::{ChromeError, Result, timeouts::ms};
+use chromiumoxide::cdp::browser_protocol::dom::{
+    DescribeNodeParams, GetBoxModelParams, GetFrameOwnerParams,
+};
+use chromiumoxide::cdp::browser_protocol::page::{
+    CreateIsolatedWorldParams, Frame, FrameId, FrameTree, GetFrameTreeParams,
+};
+use chromiumoxide::cdp::browser_protocol::target::{
+    AttachToTargetParams, DetachFromTargetParams, EventReceivedMessageFromTarget, GetTargetsParams,
+    SessionId, TargetId, TargetInfo,
+};
+use chromiumoxide::cdp::js_protocol::runtime::{EvaluateParams, RemoteObjectId};
+use chromiumoxide::listeners::EventStream;
+use chromiumoxide::types::{Command, Method, MethodId};
+use chromiumoxide::{Browser, Page};
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Isolated world frame scripts run in, so page scripts cannot interfere with them
+const WORLD_NAME: &str = "cdtcli";
+
+/// Resolved selectors whose frame is remembered; older entries are dropped first
+const MAX_SCOPES: usize = 256;
+
+/// Where a frame's CDP commands go
+#[derive(Clone)]
+enum Channel {
+    /// The page's own session, which serves the main frame and every in-process child frame
+    Page(Arc<Page>),
+    /// A session on an out-of-process iframe target
+    Remote(Arc<RemoteTarget>),
+}
+
+impl Channel {
+    async fn execute<C: Command>(&self, cmd: C) -> Result<C::Response> {
+        match self {
+            Self::Page(page) => page
+                .execute(cmd)
+                .await
+                .map(|response| response.result)
+                .map_err(|e| ChromeError::General(e.to_string())),
+            Self::Remote(target) => target.execute(cmd).await,
+        }
+    }
+
+    fn same(&self, other: &Channel) -> bool {
+        match (self, other) {
+            (Self::Page(a), Self::Page(b)) => a.target_id() == b.target_id(),
+            (Self::Remote(a), Self::Remote(b)) => a.target == b.target,
+            _ => false,
+        }
+    }
+}
+
+/// `Target.sendMessageToTarget`, which chromiumoxide no longer generates
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SendMessageToTarget {
+    message: String,
+    session_id: SessionId,
+}
+
+impl Method for SendMessageToTarget {
+    fn identifier(&self) -> MethodId {
+        "Target.sendMessageToTarget".into()
+    }
+}
+
+impl Command for SendMessageToTarget {
+    type Response = Value;
+}
+
+/// Browser-level session on an out-of-process iframe. chromiumoxide only drives page
+/// targets, so commands travel wrapped in `Target.sendMessageToTarget` and their replies
+/// come back as `Target.receivedMessageFromTarget` events.
+struct RemoteTarget {
+    browser: Arc<Browser>,
+    target: TargetId,
+    session: SessionId,
+    replies: tokio::sync::Mutex<EventStream<EventReceivedMessageFromTarget>>,
+    next_id: AtomicUsize,
+}
+
+impl RemoteTarget {
+    async fn attach(browser: Arc<Browser>, target: TargetId) -> Result<Self> {
+        let replies = browser
+            .event_listener::<EventReceivedMessageFromTarget>()
+            .await
+            .map_err(|e| ChromeError::General(e.to_string()))?;
+        let params = AttachToTargetParams::builder()
+            .target_id(target.clone())
+            .flatten(false)
+            .build()
+            .map_err(ChromeError::General)?;
+        let session = browser
+            .execute(params)
+            .await
+            .map_err(|e| ChromeError::General(format!("Failed to attach to frame: {}", e)))?
+            .result
+            .session_id;
+        Ok(Self {
+            browser,
+            target,
+            session,
+            replies: tokio::sync::Mutex::new(replies),
+            next_id: AtomicUsize::new(1),
+        })
+    }
+
+    async fn execute<C: Command>(&self, cmd: C) -> Result<C::Response> {
+        let method = cmd.identifier();
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let message = json!({"id": id, "method": method, "params": cmd}).to_string();
+
+        // Held until the reply arrives so concurrent commands do not take each other's replies
+        let mut replies = self.replies.lock().await;
+        self.browser
+            .execute(SendMessageToTarget {
+                message,
+                session_id: self.session.clone(),
+            })
+            .await
+            .map_err(|e| ChromeError::General(e.to_string()))?;
+
+        let reply = tokio::time::timeout(Duration::from_millis(ms::CDP_ACTION), async {
+            while let Some(event) = replies.next().await {
+                if event.session_id != self.session {
+                    continue;
+                }
+                let Ok(reply) = serde_json::from_str::<Value>(&event.message) else {
+                    continue;
+                };
+                if reply.get("id").and_then(Value::as_u64) == Some(id as u64) {
+                    return Some(reply);
+                }
+            }
+            None
+        })
+        .await
+        .ok()
+        .flatten()
+        .ok_or_else(|| ChromeError::General(format!("{} got no reply from the frame", method)))?;
+
+        if let Some(error) = reply.get("error") {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error");
+            return Err(ChromeError::General(format!("{}: {}", method, message)));
+        }
+        C::response_from_value(reply.get("result").cloned().unwrap_or_default())
+            .map_err(|e| ChromeError::General(e.to_string()))
+    }
+}
+
+impl Drop for RemoteTarget {
+    fn drop(&mut self) {
+        let browser = self.browser.clone();
+        let params = DetachFromTargetParams::builder()
+            .session_id(self.session.clone())
+            .build();
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                let _ = browser.execute(params).await;
+            });
+        }
+    }
+}
+
+/// A frame below the page's main frame
+pub struct PageFrame {
+    frame: Frame,
+    channel: Channel,
+    parent: Option<Arc<PageFrame>>,
+}
+
+impl PageFrame {
+    pub fn id(&self) -> &FrameId {
+        &self.frame.id
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.frame.name.as_deref().filter(|n| !n.is_empty())
+    }
+
+    pub fn url(&self) -> &str {
+        &self.frame.url
+    }
+
+    /// Path from the page, e.g. `checkout >> https://pay.example/widget`
+    pub fn label(&self) -> String {
+        let own = self.name().unwrap_or(self.url()).to_string();
+        match self.parent.as_ref().filter(|p| p.parent.is_some()) {
+            Some(parent) => format!("{} >> {}", parent.label(), own),
+            None => own,
+        }
+    }
+
+    pub async fn execute<C: Command>(&self, cmd: C) -> Result<C::Response> {
+        self.channel.execute(cmd).await
+    }
+
+    /// Evaluates `expression` in the frame's isolated world and returns its value
+    pub async fn evaluate(&self, expression: impl Into<String>) -> Result<Value> {
+        let outcome = self.run(expression.into(), true).await?;
+        Ok(outcome.value.unwrap_or_default())
+    }
+
+    /// Evaluates `expression` and keeps the result in the frame, for DOM commands that
+    /// take an object id. `None` when the expression yields null or undefined.
+    pub async fn evaluate_handle(
+        &self,
+        expression: impl Into<String>,
+    ) -> Result<Option<RemoteObjectId>> {
+        Ok(self.run(expression.into(), false).await?.object_id)
+    }
+
+    async fn run(
+        &self,
+        expression: String,
+        by_value: bool,
+    ) -> Result<chromiumoxide::cdp::js_protocol::runtime::RemoteObject> {
+        let context = self
+            .execute(
+                CreateIsolatedWorldParams::builder()
+                    .frame_id(self.frame.id.clone())
+                    .world_name(WORLD_NAME)
+                    .build()
+                    .map_err(ChromeError::General)?,
+            )
+            .await
+            .map_err(|e| {
+                ChromeError::General(format!("Frame '{}' is not ready: {}", self.label(), e))
+            })?
+            .execution_context_id;
+        let params = EvaluateParams::builder()
+            .expression(expression)
+            .context_id(context)
+            .return_by_value(by_value)
+            .await_promise(true)
+            .build()
+            .map_err(ChromeError::General)?;
+        let returns = self.execute(params).await?;
+        if let Some(details) = returns.exception_details {
+            let reason = details
+                .exception
+                .as_ref()
+                .and_then(|e| e.description.clone())
+                .unwrap_or(details.text);
+            return Err(ChromeError::EvaluationError(reason));
+        }
+        Ok(returns.result)
+    }
+
+    /// Top-left of the frame's viewport in the page's viewport, in CSS pixels. Box models are
+    /// reported relative to the viewport of the process that renders the owner element, so
+    /// the owner boxes of out-of-process ancestors are added up.
+    pub async fn offset(&self) -> Result<(f64, f64)> {
+        let mut x = 0.0;
+        let mut y = 0.0;
+        let mut frame = self;
+        while let Some(parent) = frame.parent.as_deref() {
+            let owner = parent
+                .execute(GetFrameOwnerParams::new(frame.frame.id.clone()))
+                .await?
+                .backend_node_id;
+            let model = parent
+                .execute(GetBoxModelParams::builder().backend_node_id(owner).build())
+                .await?
+                .model;
+            let quad = model.content.inner();
+            x += quad.first().copied().unwrap_or_default();
+            y += quad.get(1).copied().unwrap_or_default();
+
+            // Skip to the topmost ancestor sharing the parent's process
+            frame = parent;
+            while let Some(up) = frame.parent.as_deref()
+                && up.channel.same(&frame.channel)
+            {
+                frame = up;
+            }
+        }
+        Ok((x, y))
+    }
+
+    /// The child frame whose owner element is `object`, an iframe found in this frame, or
+    /// `None` while that frame has no document yet
+    pub async fn child_owned_by(
+        &self,
+        object: RemoteObjectId,
+        frames: &[Arc<PageFrame>],
+    ) -> Result<Option<Arc<PageFrame>>> {
+        let node = self
+            .execute(DescribeNodeParams::builder().object_id(object).build())
+            .await?
+            .node;
+        let id = node
+            .frame_id
+            .ok_or_else(|| ChromeError::FrameNotFound("element is not a frame".to_string()))?;
+        Ok(frames.iter().find(|f| f.frame.id == id).cloned())
+    }
+}
+
+/// The page's main frame, for starting `>>` chains from the top document
+pub async fn main_frame(page: &Arc<Page>) -> Result<Arc<PageFrame>> {
+    let channel = Channel::Page(page.clone());
+    let tree = channel
+        .execute(GetFrameTreeParams::default())
+        .await?
+        .frame_tree;
+    Ok(Arc::new(PageFrame {
+        frame: tree.frame,
+        channel,
+        parent: None,
+    }))
+}
+
+/// Every frame below the main frame, parents before children. Out-of-process iframes are
+/// attached through `browser` and listed after the in-process children of the same parent;
+/// without a browser only in-process frames are found.
+pub async fn list(page: &Arc<Page>, browser: Option<&Arc<Browser>>) -> Result<Vec<Arc<PageFrame>>> {
+    let remote: Vec<TargetInfo> = match browser {
+        Some(browser) => browser
+            .execute(GetTargetsParams::default())
+            .await
+            .map_err(|e| ChromeError::General(e.to_string()))?
+            .result
+            .target_infos
+            .into_iter()
+            .filter(|t| t.r#type == "iframe" && t.parent_frame_id.is_some())
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let channel = Channel::Page(page.clone());
+    let tree = channel
+        .execute(GetFrameTreeParams::default())
+        .await?
+        .frame_tree;
+    let mut pending = vec![(tree, channel, None)];
+    let mut out = Vec::new();
+    let mut main = true;
+
+    while let Some((tree, channel, parent)) = pending.pop() {
+        let FrameTree {
+            frame,
+            child_frames,
+            ..
+        } = tree;
+        let current = Arc::new(PageFrame {
+            frame,
+            channel: channel.clone(),
+            parent,
+        });
+        if !std::mem::take(&mut main) {
+            out.push(current.clone());
+        }
+
+        let mut children: Vec<(FrameTree, Channel, Option<Arc<PageFrame>>)> = child_frames
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| (child, channel.clone(), Some(current.clone())))
+            .collect();
+        if let Some(browser) = browser {
+            for info in remote
+                .iter()
+                .filter(|t| t.parent_frame_id.as_ref() == Some(&current.frame.id))
+            {
+                let target =
+                    match RemoteTarget::attach(browser.clone(), info.target_id.clone()).await {
+                        Ok(target) => Channel::Remote(Arc::new(target)),
+                        Err(e) => {
+                            tracing::debug!("Skipping frame {}: {}", info.url, e);
+                            continue;
+                        }
+                    };
+                match target.execute(GetFrameTreeParams::default()).await {
+                    Ok(returns) => {
+                        children.push((returns.frame_tree, target, Some(current.clone())))
+                    }
+                    Err(e) => tracing::debug!("Skipping frame {}: {}", info.url, e),
+                }
+            }
+        }
+        pending.extend(children.into_iter().rev());
+    }
+    Ok(out)
+}
+
+/// Picks a frame by index in [`list`] order, exact name, or URL substring
+pub fn find(frames: &[Arc<PageFrame>], spec: &str) -> Result<Arc<PageFrame>> {
+    match spec.parse::<usize>() {
+        Ok(index) => frames.get(index),
+        Err(_) => frames
+            .iter()
+            .find(|f| f.name() == Some(spec))
+            .or_else(|| frames.iter().find(|f| f.url().contains(spec))),
+    }
+    .cloned()
+    .ok_or_else(|| ChromeError::FrameNotFound(spec.to_string()))
+}
+
+type ScopeKey = (String, String);
+
+#[derive(Default)]
+struct Scopes {
+    order: VecDeque<ScopeKey>,
+    frames: HashMap<ScopeKey, Arc<PageFrame>>,
+}
+
+/// Frames that resolved selectors point into, per page
+static SCOPES: Lazy<Mutex<Scopes>> = Lazy::new(Default::default);
+
+fn scope_key(page: &Page, selector: &str) -> ScopeKey {
+    (page.target_id().inner().clone(), selector.to_string())
+}
+
+/// Records that `selector` on `page` addresses an element inside `frame`, or in the top
+/// document when `frame` is `None`
+pub fn remember(page: &Page, selector: &str, frame: Option<Arc<PageFrame>>) {
+    let key = scope_key(page, selector);
+    let mut scopes = SCOPES.lock().unwrap_or_else(|e| e.into_inner());
+    scopes.order.retain(|k| *k != key);
+    scopes.frames.remove(&key);
+    let Some(frame) = frame else {
+        return;
+    };
+    if scopes.order.len() >= MAX_SCOPES
+        && let Some(oldest) = scopes.order.pop_front()
+    {
+        scopes.frames.remove(&oldest);
+    }
+    scopes.order.push_back(key.clone());
+    scopes.frames.insert(key, frame);
+}
+
+/// The frame `selector` was resolved in, if any
+pub fn scope(page: &Page, selector: &str) -> Option<Arc<PageFrame>> {
+    SCOPES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .frames
+        .get(&scope_key(page, selector))
+        .cloned()
+}
+
+/// Evaluates `expression` where `selector`'s element lives: the isolated world of the frame
+/// it was resolved in, or the page's main world
+pub async fn evaluate(page: &Page, selector: &str, expression: impl Into<String>) -> Result<Value> {
+    match scope(page, selector) {
+        Some(frame) => frame.evaluate(expression).await,
+        None => Ok(page
+            .evaluate(expression.into())
+            .await
+            .map_err(|e| ChromeError::EvaluationError(e.to_string()))?
+            .into_value()
+            .unwrap_or_default()),
+    }
+}
+
+/// What to add to viewport coordinates measured next to `selector`'s element
+pub async fn offset(page: &Page, selector: &str) -> Result<(f64, f64)> {
+    match scope(page, selector) {
+        Some(frame) => frame.offset().await,
+        None => Ok((0.0, 0.0)),
+    }
+}
@@ -0,0 +1,841 @@
+use crate::{ChromeError, Result};
+use chromiumoxide::{
+    Page,
+    cdp::browser_protocol::{
+        fetch::{
+            AuthChallengeResponse, AuthChallengeResponseResponse, AuthChallengeSource,
+            ContinueRequestParams, ContinueWithAuthParams, DisableParams as FetchDisableParams,
+            EnableParams as FetchEnableParams, EventAuthRequired, EventRequestPaused,
+            FailRequestParams, FulfillRequestParams, HeaderEntry, RequestId, RequestPattern,
+            RequestStage,
+        },
+        network::ErrorReason,
+    },
+    error::CdpError,
+};
+use futures::StreamExt;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use tokio::sync::RwLock;
+
+/// What to do with a request whose URL matches a route pattern.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RouteAction {
+    Fulfill {
+        status: u16,
+        headers: BTreeMap<String, String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        body_file: Option<String>,
+        /// Base64-encoded response body
+        #[serde(skip)]
+        body: Option<String>,
+    },
+    Abort {
+        reason: String,
+    },
+    Continue {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+        headers: BTreeMap<String, String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteRule {
+    pub id: String,
+    pub pattern: String,
+    #[serde(flatten)]
+    pub action: RouteAction,
+    pub hits: u64,
+}
+
+/// Username and password answered for an authentication challenge
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Credentials {
+    pub username: String,
+    #[serde(default, skip_serializing)]
+    pub password: String,
+    /// Only answered for challenges from this origin; any origin when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+}
+
+struct CompiledRoute {
+    rule: RouteRule,
+    matcher: Regex,
+}
+
+/// Per-session request interception via the Fetch domain.
+///
+/// Fetch is only enabled on attached pages while a route, HAR replay or
+/// credentials are registered, so sessions without any pay no interception
+/// overhead. Routes take precedence over replay; anything left continues to the
+/// network.
+pub struct RequestInterceptor {
+    routes: Arc<RwLock<Vec<CompiledRoute>>>,
+    replay: Arc<RwLock<Option<HarReplay>>>,
+    proxy_credentials: Arc<RwLock<Option<Credentials>>>,
+    http_credentials: Arc<RwLock<Vec<Credentials>>>,
+    pages: RwLock<Vec<Weak<Page>>>,
+    next_id: AtomicU64,
+}
+
+impl RequestInterceptor {
+    pub fn new() -> Self {
+        Self {
+            routes: Arc::new(RwLock::new(Vec::new())),
+            replay: Arc::new(RwLock::new(None)),
+            proxy_credentials: Arc::new(RwLock::new(None)),
+            http_credentials: Arc::new(RwLock::new(Vec::new())),
+            pages: RwLock::new(Vec::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    pub async fn attach(&self, page: &Arc<Page>) -> Result<()> {
+        let mut stream = page
+            .event_listener::<EventRequestPaused>()
+            .await
+            .map_err(|e| {
+                ChromeError::General(format!("Failed to attach interception listener: {}", e))
+            })?;
+
+        let routes = self.routes.clone();
+        let replay = self.replay.clone();
+        // Weak so a closed tab is dropped rather than kept alive by its listeners
+        let weak_page = Arc::downgrade(page);
+
+        tokio::spawn(async move {
+            while let Some(event) = stream.next().await {
+                let Some(page) = weak_page.upgrade() else {
+                    break;
+                };
+                let resolution = resolve(&routes, &replay, &event).await;
+                if let Err(e) = apply_resolution(&page, &event, resolution).await {
+                    tracing::debug!(
+                        "Failed to resolve paused request {}: {}",
+                        event.request.url,
+                        e
+                    );
+                }
+            }
+        });
+
+        let mut auth_stream = page
+            .event_listener::<EventAuthRequired>()
+            .await
+            .map_err(|e| ChromeError::General(format!("Failed to attach auth listener: {}", e)))?;
+        let proxy_credentials = self.proxy_credentials.clone();
+        let http_credentials = self.http_credentials.clone();
+        let weak_page = Arc::downgrade(page);
+
+        tokio::spawn(async move {
+            let mut attempts = AuthAttempts::default();
+            while let Some(event) = auth_stream.next().await {
+                let Some(page) = weak_page.upgrade() else {
+                    break;
+                };
+                let retry = !attempts.first_attempt(event.request_id.inner());
+                let credentials = match event.auth_challenge.source {
+                    Some(AuthChallengeSource::Proxy) => proxy_credentials.read().await.clone(),
+                    _ => credentials_for(
+                        &http_credentials.read().await,
+                        &event.auth_challenge.origin,
+                    ),
+                };
+                if let Err(e) = answer_auth(&page, &event, credentials, retry).await {
+                    tracing::debug!(
+                        "Failed to answer auth challenge from {}: {}",
+                        event.auth_challenge.origin,
+                        e
+                    );
+                }
+            }
+        });
+
+        self.pages.write().await.push(Arc::downgrade(page));
+        self.sync_page(page).await
+    }
+
+    /// Credentials answered when the proxy asks for authentication
+    pub async fn set_proxy_credentials(&self, credentials: Option<Credentials>) {
+        *self.proxy_credentials.write().await = credentials;
+        self.sync_all().await;
+    }
+
+    /// Credentials answered when a server asks for HTTP authentication, replacing any
+    /// registered for the same origin
+    pub async fn set_http_credentials(&self, credentials: Credentials) {
+        {
+            let mut list = self.http_credentials.write().await;
+            list.retain(|c| c.origin != credentials.origin);
+            list.push(credentials);
+        }
+        self.sync_all().await;
+    }
+
+    pub async fn clear_http_credentials(&self) -> usize {
+        let count = std::mem::take(&mut *self.http_credentials.write().await).len();
+        self.sync_all().await;
+        count
+    }
+
+    pub async fn http_credentials(&self) -> Vec<Credentials> {
+        self.http_credentials.read().await.clone()
+    }
+
+    pub async fn add(&self, pattern: &str, action: RouteAction) -> Result<RouteRule> {
+        let matcher = glob_to_regex(pattern)?;
+        if let RouteAction::Abort { reason } = &action {
+            parse_error_reason(reason)?;
+        }
+
+        let rule = RouteRule {
+            id: format!("r{}", self.next_id.fetch_add(1, Ordering::Relaxed)),
+            pattern: pattern.to_string(),
+            action,
+            hits: 0,
+        };
+
+        self.routes.write().await.push(CompiledRoute {
+            rule: rule.clone(),
+            matcher,
+        });
+        self.sync_all().await;
+
+        Ok(rule)
+    }
+
+    pub async fn remove(&self, id: &str) -> Option<RouteRule> {
+        let removed = {
+            let mut routes = self.routes.write().await;
+            let idx = routes.iter().position(|r| r.rule.id == id)?;
+            routes.remove(idx).rule
+        };
+        self.sync_all().await;
+        Some(removed)
+    }
+
+    pub async fn clear(&self) -> usize {
+        let count = {
+            let mut routes = self.routes.write().await;
+            let count = routes.len();
+            routes.clear();
+            count
+        };
+        self.sync_all().await;
+        count
+    }
+
+    pub async fn list(&self) -> Vec<RouteRule> {
+        self.routes
+            .read()
+            .await
+            .iter()
+            .map(|r| r.rule.clone())
+            .collect()
+    }
+
+    pub async fn start_replay(&self, replay: HarReplay) -> ReplaySummary {
+        let summary = replay.summary();
+        *self.replay.write().await = Some(replay);
+        self.sync_all().await;
+        summary
+    }
+
+    /// Stop replaying and return the final summary, if a replay was active.
+    pub async fn stop_replay(&self) -> Option<ReplaySummary> {
+        let replay = self.replay.write().await.take();
+        self.sync_all().await;
+        replay.map(|r| r.summary())
+    }
+
+    pub async fn replay_summary(&self) -> Option<ReplaySummary> {
+        self.replay.read().await.as_ref().map(HarReplay::summary)
+    }
+
+    pub async fn is_active(&self) -> bool {
+        !self.routes.read().await.is_empty() || self.replay.read().await.is_some()
+    }
+
+    async fn sync_all(&self) {
+        let pages: Vec<Arc<Page>> = {
+            let mut pages = self.pages.write().await;
+            pages.retain(|p| p.strong_count() > 0);
+            pages.iter().filter_map(Weak::upgrade).collect()
+        };
+
+        for page in pages {
+            if let Err(e) = self.sync_page(&page).await {
+                tracing::debug!("Failed to update interception state: {}", e);
+            }
+        }
+    }
+
+    async fn sync_page(&self, page: &Arc<Page>) -> Result<()> {
+        let handle_auth = self.proxy_credentials.read().await.is_some()
+            || !self.http_credentials.read().await.is_empty();
+        if handle_auth || self.is_active().await {
+            let pattern = RequestPattern::builder()
+                .url_pattern("*")
+                .request_stage(RequestStage::Request)
+                .build();
+            let params = FetchEnableParams::builder()
+                .pattern(pattern)
+                .handle_auth_requests(handle_auth)
+                .build();
+            page.execute(params).await.map_err(|e| {
+                ChromeError::General(format!("Failed to enable Fetch domain: {}", e))
+            })?;
+        } else {
+            page.execute(FetchDisableParams::default())
+                .await
+                .map_err(|e| {
+                    ChromeError::General(format!("Failed to disable Fetch domain: {}", e))
+                })?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for RequestInterceptor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+enum Resolution {
+    Route(RouteAction),
+    Replay(ReplayResponse),
+    Fail(ErrorReason),
+    Continue,
+}
+
+async fn resolve(
+    routes: &RwLock<Vec<CompiledRoute>>,
+    replay: &RwLock<Option<HarReplay>>,
+    event: &EventRequestPaused,
+) -> Resolution {
+    let url = &event.request.url;
+
+    {
+        let mut routes = routes.write().await;
+        if let Some(route) = routes.iter_mut().rev().find(|r| r.matcher.is_match(url)) {
+            route.rule.hits += 1;
+            return Resolution::Route(route.rule.action.clone());
+        }
+    }
+
+    if let Some(replay) = replay.write().await.as_mut() {
+        match replay.lookup(&event.request.method, url) {
+            Some(response) => return Resolution::Replay(response),
+            None if replay.fallback == ReplayFallback::Abort => {
+                return Resolution::Fail(ErrorReason::InternetDisconnected);
+            }
+            None => {}
+        }
+    }
+
+    Resolution::Continue
+}
+
+async fn apply_resolution(
+    page: &Page,
+    event: &EventRequestPaused,
+    resolution: Resolution,
+) -> Result<()> {
+    let request_id = event.request_id.clone();
+
+    let result = match resolution {
+        Resolution::Route(RouteAction::Fulfill {
+            status,
+            headers,
+            body,
+            ..
+        }) => fulfill(page, request_id, status, headers.into_iter(), body).await,
+        Resolution::Route(RouteAction::Abort { reason }) => {
+            let params = FailRequestParams::new(request_id, parse_error_reason(&reason)?);
+            page.execute(params).await.map(|_| ())
+        }
+        Resolution::Route(RouteAction::Continue { url, headers }) => {
+            let mut builder = ContinueRequestParams::builder().request_id(request_id);
+            if let Some(url) = url {
+                builder = builder.url(url);
+            }
+            if !headers.is_empty() {
+                let merged = merge_headers(event.request.headers.inner(), &headers);
+                builder = builder.headers(merged.into_iter().map(|(k, v)| HeaderEntry::new(k, v)));
+            }
+            let params = builder.build().map_err(ChromeError::General)?;
+            page.execute(params).await.map(|_| ())
+        }
+        Resolution::Replay(response) => {
+            fulfill(
+                page,
+                request_id,
+                response.status,
+                response.headers.into_iter(),
+                response.body,
+            )
+            .await
+        }
+        Resolution::Fail(reason) => page
+            .execute(FailRequestParams::new(request_id, reason))
+            .await
+            .map(|_| ()),
+        Resolution::Continue => page
+            .execute(ContinueRequestParams::new(request_id))
+            .await
+            .map(|_| ()),
+    };
+
+    result.map_err(|e| ChromeError::NetworkError(e.to_string()))
+}
+
+/// Requests already answered with credentials. A second challenge for one of them
+/// means the credentials were rejected. Requests that succeed are never challenged
+/// again, so only the most recent ids are kept.
+#[derive(Default)]
+struct AuthAttempts {
+    ids: VecDeque<String>,
+}
+
+impl AuthAttempts {
+    const CAPACITY: usize = 64;
+
+    /// Records `id`, or forgets it and returns false when it was already attempted,
+    /// as that challenge is cancelled
+    fn first_attempt(&mut self, id: &str) -> bool {
+        if let Some(idx) = self.ids.iter().position(|seen| seen == id) {
+            self.ids.remove(idx);
+            return false;
+        }
+        if self.ids.len() == Self::CAPACITY {
+            self.ids.pop_front();
+        }
+        self.ids.push_back(id.to_string());
+        true
+    }
+}
+
+/// Credentials registered for `origin`, falling back to those for any origin
+fn credentials_for(list: &[Credentials], origin: &str) -> Option<Credentials> {
+    let origin = origin.trim_end_matches('/');
+    list.iter()
+        .find(|c| c.origin.as_deref() == Some(origin))
+        .or_else(|| list.iter().find(|c| c.origin.is_none()))
+        .cloned()
+}
+
+/// Provides `credentials` for the challenge, or leaves it to the browser's default
+/// handling when there are none. A `retry` means the proxy or server rejected the
+/// credentials already sent for this request, so the challenge is cancelled and the
+/// 407/401 response reaches the page instead of looping.
+fn auth_response(credentials: Option<Credentials>, retry: bool) -> AuthChallengeResponse {
+    match credentials {
+        Some(_) if retry => AuthChallengeResponse::new(AuthChallengeResponseResponse::CancelAuth),
+        Some(credentials) => AuthChallengeResponse {
+            response: AuthChallengeResponseResponse::ProvideCredentials,
+            username: Some(credentials.username),
+            password: Some(credentials.password),
+        },
+        None => AuthChallengeResponse::new(AuthChallengeResponseResponse::Default),
+    }
+}
+
+async fn answer_auth(
+    page: &Page,
+    event: &EventAuthRequired,
+    credentials: Option<Credentials>,
+    retry: bool,
+) -> Result<()> {
+    page.execute(ContinueWithAuthParams::new(
+        event.request_id.clone(),
+        auth_response(credentials, retry),
+    ))
+    .await
+    .map_err(|e| ChromeError::NetworkError(e.to_string()))?;
+    Ok(())
+}
+
+async fn fulfill(
+    page: &Page,
+    request_id: RequestId,
+    status: u16,
+    headers: impl Iterator<Item = (String, String)>,
+    body: Option<String>,
+) -> std::result::Result<(), CdpError> {
+    let mut builder = FulfillRequestParams::builder()
+        .request_id(request_id)
+        .response_code(status as i64)
+        .response_headers(headers.map(|(k, v)| HeaderEntry::new(k, v)));
+    if let Some(body) = body {
+        builder = builder.body(body);
+    }
+    // Only fails on missing mandatory fields, both of which are set above
+    let params = builder.build().map_err(CdpError::msg)?;
+    page.execute(params).await.map(|_| ())
+}
+
+/// Overlay `overrides` onto the original request headers. Header names are
+/// compared case-insensitively; an empty override value drops the header.
+fn merge_headers(
+    original: &serde_json::Value,
+    overrides: &BTreeMap<String, String>,
+) -> Vec<(String, String)> {
+    let mut merged: Vec<(String, String)> = original
+        .as_object()
+        .map(|obj| {
+            obj.iter()
+                .filter(|(k, _)| !overrides.keys().any(|o| o.eq_ignore_ascii_case(k)))
+                .map(|(k, v)| (k.clone(), v.as_str().unwrap_or_default().to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    merged.extend(
+        overrides
+            .iter()
+            .filter(|(_, v)| !v.is_empty())
+            .map(|(k, v)| (k.clone(), v.clone())),
+    );
+    merged
+}
+
+pub fn parse_error_reason(reason: &str) -> Result<ErrorReason> {
+    reason
+        .to_ascii_lowercase()
+        .parse()
+        .map_err(|_| ChromeError::General(format!("Unknown abort reason: {}", reason)))
+}
+
+/// Convert a URL glob into an anchored regex. `**` matches any characters,
+/// `*` matches anything except `/`; everything else is literal.
+pub fn glob_to_regex(pattern: &str) -> Result<Regex> {
+    let mut re = String::from("^");
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '*' {
+            if chars.peek() == Some(&'*') {
+                chars.next();
+                re.push_str(".*");
+            } else {
+                re.push_str("[^/]*");
+            }
+        } else {
+            re.push_str(&regex::escape(&c.to_string()));
+        }
+    }
+    re.push('$');
+
+    Regex::new(&re).map_err(|e| ChromeError::General(format!("Invalid route pattern: {}", e)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplayFallback {
+    /// Fail unmatched requests as if the network were offline
+    Abort,
+    /// Let unmatched requests reach the live network
+    Live,
+}
+
+impl FromStr for ReplayFallback {
+    type Err = ChromeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "abort" => Ok(Self::Abort),
+            "live" => Ok(Self::Live),
+            _ => Err(ChromeError::General(format!(
+                "Invalid replay fallback: {}. Use abort or live",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    /// Base64-encoded response body
+    pub body: Option<String>,
+}
+
+/// Recorded responses keyed by exact method + URL. Repeated requests walk
+/// through the recorded responses in order and then keep serving the last one.
+/// Entries recorded without a usable body are left to the fallback.
+pub struct HarReplay {
+    source: String,
+    fallback: ReplayFallback,
+    responses: HashMap<(String, String), Vec<ReplayResponse>>,
+    /// Requests whose recorded body is missing or truncated
+    incomplete_entries: HashSet<(String, String)>,
+    served: HashMap<(String, String), usize>,
+    matched: u64,
+    unmatched: BTreeMap<(String, String), u64>,
+    incomplete: BTreeMap<(String, String), u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnmatchedRequest {
+    pub method: String,
+    pub url: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaySummary {
+    pub source: String,
+    pub fallback: ReplayFallback,
+    pub entries: usize,
+    pub matched: u64,
+    pub unmatched: Vec<UnmatchedRequest>,
+    /// Requests sent to the fallback because their recorded body was missing or truncated
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub incomplete: Vec<UnmatchedRequest>,
+}
+
+impl HarReplay {
+    pub fn new(
+        source: impl Into<String>,
+        fallback: ReplayFallback,
+        entries: impl IntoIterator<Item = (String, String, Option<ReplayResponse>)>,
+    ) -> Self {
+        let mut responses: HashMap<(String, String), Vec<ReplayResponse>> = HashMap::new();
+        let mut incomplete_entries = HashSet::new();
+        for (method, url, response) in entries {
+            let key = (method.to_uppercase(), url);
+            match response {
+                Some(response) => responses.entry(key).or_default().push(response),
+                None => {
+                    incomplete_entries.insert(key);
+                }
+            }
+        }
+
+        Self {
+            source: source.into(),
+            fallback,
+            responses,
+            incomplete_entries,
+            served: HashMap::new(),
+            matched: 0,
+            unmatched: BTreeMap::new(),
+            incomplete: BTreeMap::new(),
+        }
+    }
+
+    pub fn lookup(&mut self, method: &str, url: &str) -> Option<ReplayResponse> {
+        let key = (method.to_uppercase(), url.to_string());
+
+        let Some(candidates) = self.responses.get(&key) else {
+            let misses = if self.incomplete_entries.contains(&key) {
+                &mut self.incomplete
+            } else {
+                &mut self.unmatched
+            };
+            *misses.entry(key).or_default() += 1;
+            return None;
+        };
+
+        let served = self.served.entry(key).or_default();
+        let response = candidates[(*served).min(candidates.len() - 1)].clone();
+        *served += 1;
+        self.matched += 1;
+        Some(response)
+    }
+
+    pub fn summary(&self) -> ReplaySummary {
+        ReplaySummary {
+            source: self.source.clone(),
+            fallback: self.fallback,
+            entries: self.responses.values().map(Vec::len).sum(),
+            matched: self.matched,
+            unmatched: unmatched_requests(&self.unmatched),
+            incomplete: unmatched_requests(&self.incomplete),
+        }
+    }
+}
+
+fn unmatched_requests(counts: &BTreeMap<(String, String), u64>) -> Vec<UnmatchedRequest> {
+    counts
+        .iter()
+        .map(|((method, url), count)| UnmatchedRequest {
+            method: method.clone(),
+            url: url.clone(),
+            count: *count,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejected_proxy_credentials_cancel() {
+        let proxy = Credentials {
+            username: "user".to_string(),
+            password: "wrong".to_string(),
+            origin: None,
+        };
+        let first = auth_response(Some(proxy.clone()), false);
+        assert_eq!(
+            first.response,
+            AuthChallengeResponseResponse::ProvideCredentials
+        );
+        assert_eq!(first.username.as_deref(), Some("user"));
+
+        let again = auth_response(Some(proxy), true);
+        assert_eq!(again.response, AuthChallengeResponseResponse::CancelAuth);
+        assert!(again.username.is_none());
+
+        assert_eq!(
+            auth_response(None, false).response,
+            AuthChallengeResponseResponse::Default
+        );
+    }
+
+    #[test]
+    fn test_auth_attempts_bounded() {
+        let mut attempts = AuthAttempts::default();
+        assert!(attempts.first_attempt("a"));
+        assert!(!attempts.first_attempt("a"));
+        // Forgotten once cancelled
+        assert!(attempts.ids.is_empty());
+
+        for i in 0..AuthAttempts::CAPACITY + 10 {
+            assert!(attempts.first_attempt(&i.to_string()));
+        }
+        assert_eq!(attempts.ids.len(), AuthAttempts::CAPACITY);
+        assert!(!attempts.first_attempt(&(AuthAttempts::CAPACITY + 9).to_string()));
+    }
+
+    #[test]
+    fn test_credentials_for_origin() {
+        let credentials = |user: &str, origin: Option<&str>| Credentials {
+            username: user.to_string(),
+            password: "secret".to_string(),
+            origin: origin.map(str::to_string),
+        };
+        let list = vec![
+            credentials("any", None),
+            credentials("staging", Some("https://staging.example.com")),
+        ];
+        assert_eq!(
+            credentials_for(&list, "https://staging.example.com")
+                .unwrap()
+                .username,
+            "staging"
+        );
+        assert_eq!(
+            credentials_for(&list, "https://example.com")
+                .unwrap()
+                .username,
+            "any"
+        );
+        assert!(credentials_for(&list[1..], "https://example.com").is_none());
+    }
+
+    #[test]
+    fn test_glob_double_star() {
+        let re = glob_to_regex("**/api/*").unwrap();
+        assert!(re.is_match("https://example.com/api/users"));
+        assert!(!re.is_match("https://example.com/api/users/1"));
+    }
+
+    #[test]
+    fn test_glob_literal_chars() {
+        let re = glob_to_regex("https://example.com/search?q=1").unwrap();
+        assert!(re.is_match("https://example.com/search?q=1"));
+        assert!(!re.is_match("https://example.com/searchXq=1"));
+    }
+
+    #[test]
+    fn test_merge_headers_overrides_case_insensitive() {
+        let original = serde_json::json!({"User-Agent": "a", "Accept": "*/*"});
+        let mut overrides = BTreeMap::new();
+        overrides.insert("user-agent".to_string(), "b".to_string());
+        overrides.insert("Accept".to_string(), String::new());
+
+        let merged = merge_headers(&original, &overrides);
+        assert_eq!(merged, vec![("user-agent".to_string(), "b".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_error_reason() {
+        assert!(parse_error_reason("BlockedByClient").is_ok());
+        assert!(parse_error_reason("failed").is_ok());
+        assert!(parse_error_reason("nope").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_add_remove_routes() {
+        let interceptor = RequestInterceptor::new();
+        let rule = interceptor
+            .add(
+                "**/*.png",
+                RouteAction::Abort {
+                    reason: "Failed".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(rule.id, "r1");
+        assert!(interceptor.is_active().await);
+        assert!(interceptor.remove("r1").await.is_some());
+        assert!(!interceptor.is_active().await);
+    }
+
+    fn replay_response(status: u16) -> ReplayResponse {
+        ReplayResponse {
+            status,
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    #[test]
+    fn test_har_replay_lookup_sequence() {
+        let mut replay = HarReplay::new(
+            "test.har",
+            ReplayFallback::Abort,
+            vec![
+                (
+                    "GET".into(),
+                    "https://a.test/".into(),
+                    Some(replay_response(200)),
+                ),
+                (
+                    "get".into(),
+                    "https://a.test/".into(),
+                    Some(replay_response(304)),
+                ),
+                ("GET".into(), "https://a.test/logo.png".into(), None),
+            ],
+        );
+
+        assert_eq!(replay.lookup("GET", "https://a.test/").unwrap().status, 200);
+        assert_eq!(replay.lookup("GET", "https://a.test/").unwrap().status, 304);
+        assert_eq!(replay.lookup("GET", "https://a.test/").unwrap().status, 304);
+        assert!(replay.lookup("POST", "https://a.test/").is_none());
+        assert!(replay.lookup("POST", "https://a.test/").is_none());
+        assert!(replay.lookup("GET", "https://a.test/logo.png").is_none());
+
+        let summary = replay.summary();
+        assert_eq!(summary.entries, 2);
+        assert_eq!(summary.matched, 3);
+        assert_eq!(summary.unmatched.len(), 1);
+        assert_eq!(summary.unmatched[0].count, 2);
+        assert_eq!(summary.incomplete.len(), 1);
+        assert_eq!(summary.incomplete[0].url, "https://a.test/logo.png");
+    }
+}
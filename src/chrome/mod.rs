@@ -1,19 +1,25 @@
 pub mod action_executor;
 pub mod collectors;
 pub mod event_store;
+pub mod frames;
+pub mod interceptor;
 pub mod models;
 pub mod recording;
 pub mod session_manager;
 pub mod storage;
 
 use crate::Result;
-use chromiumoxide::Page;
+use chromiumoxide::{Browser, Page};
 use std::sync::Arc;
 
 pub use action_executor::{ActionConfig, ActionExecutor};
 pub use collectors::{
     CollectorSet, ConsoleLevel, ConsoleMessage, DevToolsIssue, Dialog, DialogCollector, DialogType,
-    NetworkRequest, PageError,
+    NetworkFilter, NetworkRequest, PageError,
+};
+pub use interceptor::{
+    Credentials, HarReplay, ReplayFallback, ReplayResponse, ReplaySummary, RequestInterceptor,
+    RouteAction, RouteRule,
 };
 pub use models::BrowserSession;
 pub use recording::{FrameInfo, Recording, RecordingDetail, RecordingStatus, RecordingStorage};
@@ -26,6 +32,23 @@ pub trait PageProvider: Send + Sync {
     fn storage(&self) -> &Arc<SessionStorage>;
     fn collectors(&self) -> &Arc<CollectorSet>;
 
+    /// Every open page, for settings that apply session-wide. Providers that only
+    /// track one page return the current one.
+    async fn all_pages(&self) -> Result<Vec<Arc<Page>>> {
+        Ok(vec![self.get_or_create_page().await?])
+    }
+
+    /// Browser connection, used to reach out-of-process iframes. Without it `--frame`
+    /// only finds frames rendered in the page's own process.
+    async fn browser(&self) -> Option<Arc<Browser>> {
+        None
+    }
+
+    /// Executor settings for input actions, scaled by any throttling on the session
+    async fn action_config(&self) -> ActionConfig {
+        ActionConfig::default()
+    }
+
     /// Optional method to update page info for persistence (CLI mode only)
     async fn update_active_page_info(&self) -> Result<()> {
         Ok(())
@@ -168,7 +168,7 @@ pub fn list_recordings(session_dir: &Path) -> crate::Result<Vec<Recording>> {
         }
     }
 
-    recordings.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    recordings.sort_by_key(|r| std::cmp::Reverse(r.started_at));
     Ok(recordings)
 }
 
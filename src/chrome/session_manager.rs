@@ -487,6 +487,8 @@ impl BrowserSessionManager {
             builder = builder.arg("--disable-web-security");
         }
 
+        builder = builder.args(self.config.network.browser_args()?);
+
         let config = builder
             .build()
             .map_err(|e| ChromeError::LaunchFailed(e.to_string()))?;
@@ -557,6 +559,7 @@ impl BrowserSessionManager {
             "--disable-features=ProfilePickerOnStartup",
             "--profile-directory=Default",
         ]);
+        cmd.args(self.config.network.browser_args()?);
 
         cmd.spawn()
             .map_err(|e| ChromeError::LaunchFailed(format!("Failed to spawn Chrome: {}", e)))?;
@@ -937,6 +940,10 @@ impl super::PageProvider for BrowserSessionManager {
         &self.collectors
     }
 
+    async fn browser(&self) -> Option<Arc<Browser>> {
+        self.browser.read().await.clone()
+    }
+
     async fn update_active_page_info(&self) -> Result<()> {
         BrowserSessionManager::update_active_page_info(self).await
     }
@@ -62,6 +62,16 @@ impl SessionStorage {
         Ok(())
     }
 
+    /// Appends `item`, returning the row id that `update` takes
+    pub fn insert<T: Serialize + EventMetadata>(&self, collection: &str, item: &T) -> Result<i64> {
+        self.event_store.append(collection, item)
+    }
+
+    /// Replaces a row returned by `insert`
+    pub fn update<T: Serialize + EventMetadata>(&self, row: i64, item: &T) -> Result<()> {
+        self.event_store.update(row, item)
+    }
+
     pub fn append_raw<T: Serialize>(&self, collection: &str, item: &T) -> Result<()> {
         self.event_store.append_raw(collection, item)?;
         Ok(())
@@ -103,6 +113,12 @@ impl SessionStorage {
         Ok(dir)
     }
 
+    pub fn downloads_dir(&self) -> Result<PathBuf> {
+        let dir = self.base_dir.join("downloads");
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
     pub fn extension_dir(&self) -> Result<PathBuf> {
         let dir = self.base_dir.join("extension");
         fs::create_dir_all(&dir)?;
@@ -1,17 +1,21 @@
 use super::{
     Cli,
     commands::{
-        AuthCommand, Command, ConfigCommand, CookiesCommand, HistoryCommand, ServerCommand,
-        SessionCommand, StorageCommand,
+        AssertCommand, AuthCommand, Command, ConfigCommand, CookiesCommand, DownloadsCommand,
+        EnvCommand, HeadersCommand, HistoryCommand, MouseCommand, PermissionsCommand, RouteCommand,
+        ServerCommand, SessionCommand, StorageCommand,
     },
 };
 use crate::{
     ChromeError, Result,
-    client::{DaemonClient, is_daemon_running},
-    config::Config,
+    client::{DaemonClient, TIMEOUT_MARGIN, is_daemon_running},
+    config::{Config, NetworkConfig},
     handlers, output,
     output::OutputFormatter,
-    server::{Daemon, DaemonConfig, default_socket_path},
+    server::{
+        Daemon, DaemonConfig, SessionEvent, default_socket_path,
+        protocol::{SESSION_CLOSED, SESSION_EVENT},
+    },
     timeouts::secs,
 };
 use serde_json::{Value, json};
@@ -55,6 +59,70 @@ fn print_json_or(result: &Value, json_output: bool, text: &str) -> Result<()> {
     }
 }
 
+fn print_throttle_info(session: &Value) {
+    if let Some(profile) = session
+        .get("network_throttle")
+        .cloned()
+        .and_then(|v| serde_json::from_value::<handlers::throttle::NetworkProfile>(v).ok())
+    {
+        println!("  Network: {}", profile.summary());
+    }
+    if let Some(rate) = session.get("cpu_throttle").and_then(|v| v.as_f64())
+        && rate > 1.0
+    {
+        println!("  CPU: {}x slowdown", rate);
+    }
+    if let Some(env) = session
+        .get("environment")
+        .cloned()
+        .and_then(|v| serde_json::from_value::<crate::devices::EnvironmentOverrides>(v).ok())
+        .filter(|env| !env.is_empty())
+    {
+        println!("  Environment: {}", env.summary());
+    }
+    let permissions: Vec<handlers::permissions::PermissionOverride> = session
+        .get("permissions")
+        .cloned()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    if !permissions.is_empty() {
+        let list: Vec<String> = permissions
+            .iter()
+            .map(|p| match p.origin {
+                Some(ref origin) => format!("{} {} ({})", p.name, p.setting.as_ref(), origin),
+                None => format!("{} {}", p.name, p.setting.as_ref()),
+            })
+            .collect();
+        println!("  Permissions: {}", list.join(", "));
+    }
+    if let Some(headers) = session
+        .get("extra_headers")
+        .and_then(|v| v.as_object())
+        .filter(|h| !h.is_empty())
+    {
+        let names: Vec<&str> = headers.keys().map(String::as_str).collect();
+        println!("  Extra Headers: {}", names.join(", "));
+    }
+    let http_auth: Vec<crate::chrome::Credentials> = session
+        .get("http_auth")
+        .cloned()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    if !http_auth.is_empty() {
+        let list: Vec<String> = http_auth
+            .iter()
+            .map(|c| {
+                format!(
+                    "{} ({})",
+                    c.username,
+                    c.origin.as_deref().unwrap_or("all origins")
+                )
+            })
+            .collect();
+        println!("  HTTP Auth: {}", list.join(", "));
+    }
+}
+
 fn start_daemon_background() -> Result<()> {
     let exe = std::env::current_exe()
         .map_err(|e| ChromeError::General(format!("Failed to get executable path: {}", e)))?;
@@ -95,7 +163,19 @@ pub async fn dispatch(mut cli: Cli, config: Arc<Config>) -> Result<()> {
             output,
             user_profile,
             headless,
-        } => handle_trace_command(&url, &output, user_profile, headless, &cli, &config).await,
+            cpu_throttle,
+        } => {
+            handle_trace_command(
+                &url,
+                &output,
+                user_profile,
+                headless,
+                cpu_throttle,
+                &cli,
+                &config,
+            )
+            .await
+        }
         _ => handle_browser_command(command, cli, config).await,
     }
 }
@@ -178,9 +258,24 @@ async fn handle_session_command(
     let mut client = DaemonClient::connect(&socket_path).await?;
 
     match subcommand {
-        SessionCommand::Create { headless, profile } => {
+        SessionCommand::Create {
+            headless,
+            profile,
+            proxy,
+            proxy_bypass,
+            user_agent,
+        } => {
+            let network = NetworkConfig {
+                proxy,
+                proxy_bypass,
+                user_agent,
+            };
             let session_id = client
-                .create_session_with_profile(headless, profile)
+                .create_session_with_profile(
+                    headless,
+                    profile,
+                    (!network.is_empty()).then_some(&network),
+                )
                 .await?;
             if cli.json {
                 println!(r#"{{"session_id":"{}"}}"#, session_id);
@@ -223,8 +318,13 @@ async fn handle_session_command(
         }
 
         SessionCommand::Destroy { session_id } => {
-            client.destroy_session(&session_id).await?;
-            println!("Destroyed session: {}", session_id);
+            let result = client.destroy_session(&session_id).await?;
+            if cli.json {
+                print_json(&result)?;
+            } else {
+                println!("Destroyed session: {}", session_id);
+                print_replay_summary(&result);
+            }
         }
 
         SessionCommand::Info { session_id } => {
@@ -244,6 +344,7 @@ async fn handle_session_command(
                 if let Some(headless) = result.get("headless") {
                     println!("  Headless: {}", headless);
                 }
+                print_throttle_info(&result);
             }
         }
     }
@@ -299,12 +400,18 @@ async fn handle_auth_command(subcommand: AuthCommand, cli: &Cli, config: &Config
     };
 
     match subcommand {
-        AuthCommand::Export { output } => {
+        AuthCommand::Export {
+            output,
+            permissions,
+        } => {
             let result = daemon_request(
                 &mut client,
                 "auth.export",
                 &session_id,
-                json!({ "output": output.as_ref().map(|p| p.display().to_string()) }),
+                json!({
+                    "output": output.as_ref().map(|p| p.display().to_string()),
+                    "permissions": permissions,
+                }),
             )
             .await?;
 
@@ -319,13 +426,14 @@ async fn handle_auth_command(subcommand: AuthCommand, cli: &Cli, config: &Config
                     .get("origins_count")
                     .and_then(|v| v.as_u64())
                     .unwrap_or(0);
-                println!(
-                    "{}",
-                    output::text::success(&format!(
-                        "Exported {} cookies, {} origins",
-                        cookies, origins
-                    ))
-                );
+                let mut summary = format!("Exported {} cookies, {} origins", cookies, origins);
+                match result.get("permissions_count").and_then(|v| v.as_u64()) {
+                    Some(count) if count > 0 => {
+                        summary.push_str(&format!(", {} permissions", count))
+                    }
+                    _ => {}
+                }
+                println!("{}", output::text::success(&summary));
                 if let Some(path) = output {
                     println!(
                         "{}",
@@ -355,13 +463,20 @@ async fn handle_auth_command(subcommand: AuthCommand, cli: &Cli, config: &Config
                     .get("origins_imported")
                     .and_then(|v| v.as_u64())
                     .unwrap_or(0);
-                println!(
-                    "{}",
-                    output::text::success(&format!(
-                        "Imported {} cookies, {} origins",
-                        cookies, origins
-                    ))
-                );
+                let mut summary = format!("Imported {} cookies, {} origins", cookies, origins);
+                if let Some(permissions) = result.get("permissions").and_then(|v| v.as_array()) {
+                    summary.push_str(&format!(", {} permissions", permissions.len()));
+                }
+                println!("{}", output::text::success(&summary));
+                for error in result
+                    .get("permission_errors")
+                    .and_then(|v| v.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|v| v.as_str())
+                {
+                    println!("{}", output::text::warning(error));
+                }
             }
             Ok(())
         }
@@ -420,29 +535,50 @@ async fn handle_history_command(
         HistoryCommand::Network {
             session_id,
             user_profile,
-            domain,
-            status,
+            filter,
             from,
             to,
             last,
             limit,
             offset,
+            format,
+            output,
         } => {
+            if let Some(ref fmt) = format
+                && fmt != "har"
+            {
+                return Err(ChromeError::General(format!(
+                    "Unsupported format: {}. Supported: har",
+                    fmt
+                )));
+            }
+
             let sid = resolve_session_id(session_id, user_profile).await?;
             let time_filter = handlers::sessions::TimeFilter::new(from, to, last);
             let result = handlers::sessions::handle_network(
                 &sid,
-                domain.as_deref(),
-                status,
+                &filter.to_filter(),
                 time_filter,
                 limit,
                 offset,
             )?;
-            if cli.json {
+            if format.is_some() {
+                let export = handlers::har::handle_har_export(&result.items, output.as_deref())?;
+                if cli.json {
+                    println!("{}", export.format_json(true)?);
+                } else {
+                    println!("{}", export.format_text());
+                }
+            } else if cli.json {
                 println!("{}", serde_json::to_string_pretty(&result)?);
             } else {
                 for req in &result.items {
-                    println!("{} {} - {}", req.method, req.status.unwrap_or(0), req.url);
+                    match req.error_text {
+                        Some(ref error) => {
+                            println!("{} failed - {} ({})", req.method, req.url, error)
+                        }
+                        None => println!("{} {} - {}", req.method, req.status_label(), req.url),
+                    }
                 }
             }
         }
@@ -698,6 +834,12 @@ async fn handle_via_daemon(
 
     match command {
         Command::Navigate { url, .. } => {
+            client
+                .extend_timeout_for_throttling(
+                    session_id,
+                    std::time::Duration::from_secs(secs::NAVIGATION),
+                )
+                .await?;
             let result = client
                 .request(
                     "navigate",
@@ -748,6 +890,63 @@ async fn handle_via_daemon(
         Command::Screenshot {
             output: out,
             full_page,
+            selector,
+            r#ref,
+            frame,
+            compare: Some(baseline),
+            threshold,
+            diff_out,
+            ignore,
+            update_baseline,
+            ..
+        } => {
+            let (regions, selectors): (Vec<_>, Vec<_>) = ignore
+                .iter()
+                .map(|s| handlers::visual::Region::parse(s).ok_or(s))
+                .partition(|r| r.is_ok());
+            let absolute = |p: &std::path::PathBuf| -> Result<String> {
+                Ok(std::path::absolute(p)?.display().to_string())
+            };
+
+            let result = daemon_request(
+                &mut client,
+                "screenshot.compare",
+                session_id,
+                json!({
+                    "output": absolute(out)?,
+                    "full_page": full_page,
+                    "selector": selector,
+                    "ref": r#ref,
+                    "frame": frame,
+                    "baseline": absolute(baseline)?,
+                    "threshold": threshold,
+                    "diff_out": diff_out.as_ref().map(absolute).transpose()?,
+                    "ignore_regions": regions.into_iter().flatten().collect::<Vec<_>>(),
+                    "ignore_selectors": selectors
+                        .into_iter()
+                        .filter_map(|r| r.err())
+                        .collect::<Vec<_>>(),
+                    "update_baseline": update_baseline
+                }),
+            )
+            .await?;
+            let result: handlers::visual::VisualComparison = serde_json::from_value(result)?;
+            if cli.json {
+                println!("{}", result.format_json(true)?);
+            } else {
+                println!("{}", result.format_text());
+            }
+            result.into_result()?;
+        }
+
+        Command::Screenshot {
+            output: out,
+            full_page,
+            selector,
+            r#ref,
+            frame,
+            format,
+            quality,
             ..
         } => {
             let result = client
@@ -755,7 +954,12 @@ async fn handle_via_daemon(
                     "screenshot",
                     json!({
                         "session_id": session_id,
-                        "full_page": full_page
+                        "full_page": full_page,
+                        "selector": selector,
+                        "ref": r#ref,
+                        "frame": frame,
+                        "format": format,
+                        "quality": quality
                     }),
                 )
                 .await?;
@@ -780,13 +984,27 @@ async fn handle_via_daemon(
         }
 
         Command::Click {
-            selector, r#ref, ..
+            selector,
+            r#ref,
+            frame,
+            mode,
+            button,
+            modifiers,
+            position,
         } => {
             let result = daemon_request(
                 &mut client,
                 "click",
                 session_id,
-                json!({"selector": selector, "ref": r#ref}),
+                json!({
+                    "selector": selector,
+                    "ref": r#ref,
+                    "frame": frame,
+                    "mode": mode,
+                    "button": button,
+                    "modifiers": modifiers,
+                    "position": position
+                }),
             )
             .await?;
             let target = selector
@@ -796,12 +1014,198 @@ async fn handle_via_daemon(
             print_json_or(&result, cli.json, &format!("Clicked: {}", target))?;
         }
 
-        Command::Hover { selector, r#ref } => {
+        Command::Dblclick {
+            selector,
+            r#ref,
+            frame,
+            modifiers,
+            position,
+        } => {
+            let result = daemon_request(
+                &mut client,
+                "dblclick",
+                session_id,
+                json!({
+                    "selector": selector,
+                    "ref": r#ref,
+                    "frame": frame,
+                    "modifiers": modifiers,
+                    "position": position
+                }),
+            )
+            .await?;
+            let target = selector
+                .as_deref()
+                .or(r#ref.as_deref())
+                .unwrap_or("element");
+            print_json_or(&result, cli.json, &format!("Double-clicked: {}", target))?;
+        }
+
+        Command::Drag {
+            from,
+            to,
+            frame,
+            steps,
+        } => {
+            let result = daemon_request(
+                &mut client,
+                "drag",
+                session_id,
+                json!({"from": from, "to": to, "frame": frame, "steps": steps}),
+            )
+            .await?;
+            let mode = result
+                .get("mode")
+                .and_then(|v| v.as_str())
+                .unwrap_or("pointer");
+            print_json_or(
+                &result,
+                cli.json,
+                &format!("Dragged {} onto {} ({})", from, to, mode),
+            )?;
+        }
+
+        Command::Tap {
+            selector,
+            r#ref,
+            frame,
+        } => {
+            let result = daemon_request(
+                &mut client,
+                "tap",
+                session_id,
+                json!({"selector": selector, "ref": r#ref, "frame": frame}),
+            )
+            .await?;
+            let target = selector
+                .as_deref()
+                .or(r#ref.as_deref())
+                .unwrap_or("element");
+            print_json_or(&result, cli.json, &format!("Tapped: {}", target))?;
+        }
+
+        Command::Longpress {
+            selector,
+            r#ref,
+            frame,
+            duration,
+        } => {
+            let result = daemon_request(
+                &mut client,
+                "longpress",
+                session_id,
+                json!({
+                    "selector": selector,
+                    "ref": r#ref,
+                    "frame": frame,
+                    "duration": duration
+                }),
+            )
+            .await?;
+            let target = selector
+                .as_deref()
+                .or(r#ref.as_deref())
+                .unwrap_or("element");
+            print_json_or(
+                &result,
+                cli.json,
+                &format!("Long-pressed: {} ({}ms)", target, duration),
+            )?;
+        }
+
+        Command::Swipe {
+            from,
+            to,
+            selector,
+            distance,
+            steps,
+            frame,
+        } => {
+            let result = daemon_request(
+                &mut client,
+                "swipe",
+                session_id,
+                json!({
+                    "from": from,
+                    "to": to,
+                    "selector": selector,
+                    "distance": distance,
+                    "steps": steps,
+                    "frame": frame
+                }),
+            )
+            .await?;
+            let text = match to {
+                Some(to) => format!("Swiped: {} → {}", from, to),
+                None => format!(
+                    "Swiped {} {}px on {}",
+                    from,
+                    distance,
+                    selector.as_deref().unwrap_or("viewport")
+                ),
+            };
+            print_json_or(&result, cli.json, &text)?;
+        }
+
+        Command::Pinch {
+            scale,
+            selector,
+            frame,
+        } => {
+            let result = daemon_request(
+                &mut client,
+                "pinch",
+                session_id,
+                json!({"scale": scale, "selector": selector, "frame": frame}),
+            )
+            .await?;
+            print_json_or(
+                &result,
+                cli.json,
+                &format!(
+                    "Pinched x{} on {}",
+                    scale,
+                    selector.as_deref().unwrap_or("viewport")
+                ),
+            )?;
+        }
+
+        Command::Mouse { subcommand } => {
+            let (method, args) = match subcommand {
+                MouseCommand::Move(args) => ("mouse.move", args),
+                MouseCommand::Down(args) => ("mouse.down", args),
+                MouseCommand::Up(args) => ("mouse.up", args),
+            };
+            let result = daemon_request(
+                &mut client,
+                method,
+                session_id,
+                json!({
+                    "x": args.x,
+                    "y": args.y,
+                    "button": args.button,
+                    "modifiers": args.modifiers
+                }),
+            )
+            .await?;
+            let action = method.trim_start_matches("mouse.");
+            print_json_or(
+                &result,
+                cli.json,
+                &format!("Mouse {} at ({}, {})", action, args.x, args.y),
+            )?;
+        }
+
+        Command::Hover {
+            selector,
+            r#ref,
+            frame,
+        } => {
             let result = daemon_request(
                 &mut client,
                 "hover",
                 session_id,
-                json!({"selector": selector, "ref": r#ref}),
+                json!({"selector": selector, "ref": r#ref, "frame": frame}),
             )
             .await?;
             let target = selector
@@ -815,13 +1219,14 @@ async fn handle_via_daemon(
             selector,
             text,
             r#ref,
-            ..
+            frame,
+            mode,
         } => {
             let result = daemon_request(
                 &mut client,
                 "fill",
                 session_id,
-                json!({"selector": selector, "ref": r#ref, "text": text}),
+                json!({"selector": selector, "ref": r#ref, "frame": frame, "text": text, "mode": mode}),
             )
             .await?;
             let target = selector
@@ -839,14 +1244,15 @@ async fn handle_via_daemon(
             selector,
             text,
             r#ref,
+            frame,
             delay,
-            ..
+            mode,
         } => {
             let result = daemon_request(
                 &mut client,
                 "type",
                 session_id,
-                json!({"selector": selector, "ref": r#ref, "text": text, "delay": delay.unwrap_or(50)}),
+                json!({"selector": selector, "ref": r#ref, "frame": frame, "text": text, "delay": delay.unwrap_or(50), "mode": mode}),
             )
             .await?;
             let target = selector
@@ -860,10 +1266,22 @@ async fn handle_via_daemon(
             )?;
         }
 
-        Command::Press { key } => {
+        Command::Press { keys } => {
+            let result =
+                daemon_request(&mut client, "press", session_id, json!({"keys": keys})).await?;
+            print_json_or(&result, cli.json, &format!("Pressed: {}", keys.join(" ")))?;
+        }
+
+        Command::Keydown { key } => {
+            let result =
+                daemon_request(&mut client, "keydown", session_id, json!({"key": key})).await?;
+            print_json_or(&result, cli.json, &format!("Key down: {}", key))?;
+        }
+
+        Command::Keyup { key } => {
             let result =
-                daemon_request(&mut client, "press", session_id, json!({"key": key})).await?;
-            print_json_or(&result, cli.json, &format!("Pressed: {}", key))?;
+                daemon_request(&mut client, "keyup", session_id, json!({"key": key})).await?;
+            print_json_or(&result, cli.json, &format!("Key up: {}", key))?;
         }
 
         Command::Eval { expression } => {
@@ -888,6 +1306,8 @@ async fn handle_via_daemon(
         Command::Wait {
             condition,
             selector,
+            r#ref,
+            frame,
             timeout,
         } => {
             let result = client
@@ -897,6 +1317,8 @@ async fn handle_via_daemon(
                         "session_id": session_id,
                         "condition": condition,
                         "selector": selector,
+                        "ref": r#ref,
+                        "frame": frame,
                         "timeout": timeout
                     }),
                 )
@@ -909,7 +1331,99 @@ async fn handle_via_daemon(
             }
         }
 
-        Command::Console { filter, limit } => {
+        Command::Run {
+            script,
+            vars,
+            report,
+        } => {
+            let overrides = vars
+                .iter()
+                .map(|var| {
+                    var.split_once('=')
+                        .map(|(k, v)| (k.trim().to_string(), v.to_string()))
+                        .ok_or_else(|| {
+                            ChromeError::General(format!(
+                                "Invalid --var '{}': expected KEY=VALUE",
+                                var
+                            ))
+                        })
+                })
+                .collect::<Result<_>>()?;
+
+            let result =
+                super::runner::run_script(&mut client, session_id, script, overrides).await?;
+            if let Some(path) = report {
+                std::fs::write(path, result.format_json(true)?)?;
+            }
+            if cli.json {
+                println!("{}", result.format_json(true)?);
+            } else {
+                println!("{}", result.format_text());
+            }
+            if !result.success {
+                return Err(ChromeError::General(format!(
+                    "Script failed: {} of {} steps failed",
+                    result.failed,
+                    result.steps.len()
+                )));
+            }
+        }
+
+        Command::Assert {
+            subcommand: AssertCommand::Mark { name },
+        } => {
+            let result = daemon_request(
+                &mut client,
+                "assert.mark",
+                session_id,
+                json!({"name": name}),
+            )
+            .await?;
+            print_json_or(
+                &result,
+                cli.json,
+                &output::text::success(&format!("Mark '{}' set", name)),
+            )?;
+        }
+
+        Command::Assert { subcommand } => {
+            let (r#ref, frame) = subcommand.target();
+            let result = daemon_request(
+                &mut client,
+                "assert",
+                session_id,
+                json!({"assertion": subcommand.to_assertion(), "ref": r#ref, "frame": frame}),
+            )
+            .await?;
+            let result: handlers::assert::AssertResult = serde_json::from_value(result)?;
+            if cli.json {
+                println!("{}", result.format_json(true)?);
+            } else {
+                println!("{}", result.format_text());
+            }
+            result.into_result()?;
+        }
+
+        Command::Console {
+            filter,
+            follow: true,
+            ..
+        } => {
+            // The daemon ignores levels it cannot parse, which would stream nothing
+            if let Some(level) = filter {
+                level
+                    .parse::<crate::chrome::ConsoleLevel>()
+                    .map_err(ChromeError::General)?;
+            }
+            let params = json!({
+                "session_id": session_id,
+                "filter": filter,
+                "events": ["console", "pageerror"]
+            });
+            follow_events(&mut client, params, cli.json).await?;
+        }
+
+        Command::Console { filter, limit, .. } => {
             let result = client
                 .request(
                     "console",
@@ -932,26 +1446,54 @@ async fn handle_via_daemon(
             }
         }
 
-        Command::Network { domain, status } => {
-            let result = client
-                .request(
-                    "network",
-                    json!({
-                        "session_id": session_id,
-                        "domain": domain,
-                        "status": status
-                    }),
-                )
-                .await?;
+        Command::Network {
+            filter,
+            har,
+            output,
+            follow,
+        } => {
+            let mut params = json!({
+                "session_id": session_id,
+                "domain": filter.domain,
+                "status": filter.status,
+                "method": filter.method,
+                "resource_type": filter.resource_type,
+                "mime_type": filter.mime,
+                "url_pattern": filter.url,
+                "min_duration_ms": filter.min_duration,
+                "min_size": filter.min_size
+            });
+            if *follow {
+                params["events"] = json!(["network"]);
+                return follow_events(&mut client, params, cli.json).await;
+            }
 
-            if cli.json {
+            let result = client.request("network", params).await?;
+
+            if *har {
+                let requests: Vec<crate::chrome::NetworkRequest> = result
+                    .get("requests")
+                    .cloned()
+                    .map(serde_json::from_value)
+                    .transpose()?
+                    .unwrap_or_default();
+                let export = handlers::har::handle_har_export(&requests, output.as_deref())?;
+                if cli.json {
+                    println!("{}", export.format_json(true)?);
+                } else {
+                    println!("{}", export.format_text());
+                }
+            } else if cli.json {
                 println!("{}", serde_json::to_string_pretty(&result)?);
             } else if let Some(requests) = result.get("requests").and_then(|r| r.as_array()) {
                 for req in requests {
                     let method = req.get("method").and_then(|m| m.as_str()).unwrap_or("?");
                     let status = req.get("status").and_then(|s| s.as_u64()).unwrap_or(0);
                     let url = req.get("url").and_then(|u| u.as_str()).unwrap_or("");
-                    println!("{} {} - {}", method, status, url);
+                    match req.get("error_text").and_then(|e| e.as_str()) {
+                        Some(error) => println!("{} failed - {} ({})", method, url, error),
+                        None => println!("{} {} - {}", method, status, url),
+                    }
                 }
             }
         }
@@ -1000,6 +1542,107 @@ async fn handle_via_daemon(
             }
         }
 
+        Command::Throttle {
+            profile,
+            latency,
+            download,
+            upload,
+        } => {
+            let result = daemon_request(
+                &mut client,
+                "throttle",
+                session_id,
+                json!({
+                    "profile": profile,
+                    "latency": latency,
+                    "download": download,
+                    "upload": upload
+                }),
+            )
+            .await?;
+
+            if cli.json {
+                print_json(&result)?;
+            } else {
+                let throttle: handlers::throttle::ThrottleResult = serde_json::from_value(result)?;
+                println!("{}", throttle.format_text());
+            }
+        }
+
+        Command::Env { subcommand } => {
+            handle_env_via_daemon(subcommand.clone(), &mut client, session_id, cli).await?;
+        }
+
+        Command::Headers { subcommand } => {
+            let result = match subcommand {
+                HeadersCommand::Set { headers } => {
+                    daemon_request(
+                        &mut client,
+                        "headers.set",
+                        session_id,
+                        json!({ "headers": parse_header_args(headers)? }),
+                    )
+                    .await?
+                }
+                HeadersCommand::Clear => {
+                    daemon_request(&mut client, "headers.clear", session_id, json!({})).await?
+                }
+            };
+            if cli.json {
+                print_json(&result)?;
+            } else {
+                let result = serde_json::from_value::<handlers::headers::HeadersResult>(result)?;
+                println!("{}", result.format_text());
+            }
+        }
+
+        Command::HttpAuth {
+            username,
+            password,
+            origin,
+            clear,
+        } => {
+            let result = if *clear {
+                daemon_request(&mut client, "http_auth.clear", session_id, json!({})).await?
+            } else {
+                daemon_request(
+                    &mut client,
+                    "http_auth.set",
+                    session_id,
+                    json!({ "username": username, "password": password, "origin": origin }),
+                )
+                .await?
+            };
+            if cli.json {
+                print_json(&result)?;
+            } else {
+                let result = serde_json::from_value::<handlers::headers::HttpAuthResult>(result)?;
+                println!("{}", result.format_text());
+            }
+        }
+
+        Command::Permissions { subcommand } => {
+            handle_permissions_via_daemon(subcommand.clone(), &mut client, session_id, cli).await?;
+        }
+
+        Command::CpuThrottle { rate } => {
+            let result = daemon_request(
+                &mut client,
+                "throttle.cpu",
+                session_id,
+                json!({"rate": rate}),
+            )
+            .await?;
+
+            if cli.json {
+                print_json(&result)?;
+            } else {
+                let throttle: handlers::throttle::CpuThrottleResult =
+                    serde_json::from_value(result)?;
+                println!("{}", throttle.format_text());
+            }
+        }
+
         Command::Dialog {
             accept,
             dismiss,
@@ -1058,6 +1701,12 @@ async fn handle_via_daemon(
         }
 
         Command::NewPage { url } => {
+            client
+                .extend_timeout_for_throttling(
+                    session_id,
+                    std::time::Duration::from_secs(secs::NAVIGATION),
+                )
+                .await?;
             let result = client
                 .request(
                     "page.new",
@@ -1128,6 +1777,7 @@ async fn handle_via_daemon(
                 if let Some(pages) = result.get("page_count") {
                     println!("  Pages: {}", pages);
                 }
+                print_throttle_info(&result);
             }
         }
 
@@ -1148,6 +1798,7 @@ async fn handle_via_daemon(
 
         Command::Inspect {
             selector,
+            frame,
             attributes,
             styles,
             r#box,
@@ -1160,6 +1811,7 @@ async fn handle_via_daemon(
                     json!({
                         "session_id": session_id,
                         "selector": selector,
+                        "frame": frame,
                         "attributes": *all || *attributes,
                         "styles": *all || *styles,
                         "box": *all || *r#box,
@@ -1170,13 +1822,14 @@ async fn handle_via_daemon(
             print_json(&result)?;
         }
 
-        Command::Listeners { selector } => {
+        Command::Listeners { selector, frame } => {
             let result = client
                 .request(
                     "listeners",
                     json!({
                         "session_id": session_id,
-                        "selector": selector
+                        "selector": selector,
+                        "frame": frame
                     }),
                 )
                 .await?;
@@ -1185,6 +1838,7 @@ async fn handle_via_daemon(
 
         Command::Query {
             selector,
+            frame,
             count,
             limit,
         } => {
@@ -1194,6 +1848,7 @@ async fn handle_via_daemon(
                     json!({
                         "session_id": session_id,
                         "selector": selector,
+                        "frame": frame,
                         "count": count,
                         "limit": limit
                     }),
@@ -1202,13 +1857,18 @@ async fn handle_via_daemon(
             print_json(&result)?;
         }
 
-        Command::Dom { selector, depth } => {
+        Command::Dom {
+            selector,
+            frame,
+            depth,
+        } => {
             let result = client
                 .request(
                     "dom",
                     json!({
                         "session_id": session_id,
                         "selector": selector,
+                        "frame": frame,
                         "depth": depth
                     }),
                 )
@@ -1239,6 +1899,7 @@ async fn handle_via_daemon(
 
         Command::Describe {
             selector,
+            frame,
             interactable,
             forms,
             navigation,
@@ -1252,6 +1913,7 @@ async fn handle_via_daemon(
                     json!({
                         "session_id": session_id,
                         "selector": selector,
+                        "frame": frame,
                         "interactable": interactable,
                         "forms": forms,
                         "navigation": navigation,
@@ -1281,6 +1943,7 @@ async fn handle_via_daemon(
         Command::Scroll {
             selector,
             r#ref,
+            frame,
             behavior,
             block,
         } => {
@@ -1291,6 +1954,7 @@ async fn handle_via_daemon(
                         "session_id": session_id,
                         "selector": selector,
                         "ref": r#ref,
+                        "frame": frame,
                         "behavior": behavior,
                         "block": block
                     }),
@@ -1306,6 +1970,7 @@ async fn handle_via_daemon(
         Command::Select {
             selector,
             r#ref,
+            frame,
             value,
             index,
             label,
@@ -1317,6 +1982,7 @@ async fn handle_via_daemon(
                         "session_id": session_id,
                         "selector": selector,
                         "ref": r#ref,
+                        "frame": frame,
                         "value": value,
                         "index": index,
                         "label": label
@@ -1326,6 +1992,31 @@ async fn handle_via_daemon(
             print_json(&result)?;
         }
 
+        Command::Upload {
+            files,
+            selector,
+            r#ref,
+            frame,
+        } => {
+            let files = files
+                .iter()
+                .map(|f| Ok(std::fs::canonicalize(f)?.display().to_string()))
+                .collect::<Result<Vec<_>>>()?;
+            let result = daemon_request(
+                &mut client,
+                "upload",
+                session_id,
+                json!({"files": files, "selector": selector, "ref": r#ref, "frame": frame}),
+            )
+            .await?;
+            if cli.json {
+                print_json(&result)?;
+            } else {
+                let result = serde_json::from_value::<handlers::upload::UploadResult>(result)?;
+                println!("{}", result.format_text());
+            }
+        }
+
         Command::Html { selector, inner } => {
             let result = client
                 .request(
@@ -1380,6 +2071,55 @@ async fn handle_via_daemon(
             handle_storage_via_daemon(subcommand.clone(), &mut client, session_id, cli).await?;
         }
 
+        Command::Route { subcommand } => {
+            handle_route_via_daemon(subcommand.clone(), &mut client, session_id, cli).await?;
+        }
+
+        Command::Downloads { subcommand } => {
+            handle_downloads_via_daemon(subcommand.clone(), &mut client, session_id, cli).await?;
+        }
+
+        Command::Replay {
+            har,
+            fallback,
+            status,
+            stop,
+        } => {
+            let result = if *stop {
+                daemon_request(&mut client, "replay.stop", session_id, json!({})).await?
+            } else if *status || har.is_none() {
+                daemon_request(&mut client, "replay.status", session_id, json!({})).await?
+            } else {
+                let har_path = har.as_ref().map(std::fs::canonicalize).transpose()?;
+                daemon_request(
+                    &mut client,
+                    "replay.start",
+                    session_id,
+                    json!({
+                        "har": har_path.map(|p| p.display().to_string()),
+                        "fallback": fallback
+                    }),
+                )
+                .await?
+            };
+
+            if cli.json {
+                print_json(&result)?;
+            } else {
+                let replay = handlers::har::ReplayResult {
+                    active: result
+                        .get("active")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false),
+                    summary: result
+                        .get("summary")
+                        .cloned()
+                        .and_then(|v| serde_json::from_value(v).ok()),
+                };
+                println!("{}", replay.format_text());
+            }
+        }
+
         Command::Analyze { .. }
         | Command::Trace { .. }
         | Command::Devices { .. }
@@ -1465,6 +2205,297 @@ async fn handle_cookies_via_daemon(
     Ok(())
 }
 
+/// Streams events from a `subscribe` call until Ctrl-C or the session ends.
+/// Text mode prints one line per event; `--json` prints NDJSON.
+async fn follow_events(client: &mut DaemonClient, params: Value, json_output: bool) -> Result<()> {
+    let session_id = params.get("session_id").cloned().unwrap_or(Value::Null);
+    client.request("subscribe", params).await?;
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        let notification = tokio::select! {
+            notification = client.next_notification() => notification?,
+            _ = &mut ctrl_c => break,
+        };
+        let Some(notification) = notification else {
+            break;
+        };
+
+        match notification.method.as_str() {
+            SESSION_EVENT => {
+                let Some(event) = notification.params.get("event") else {
+                    continue;
+                };
+                if json_output {
+                    println!("{}", serde_json::to_string(event)?);
+                } else if let Ok(event) = serde_json::from_value::<SessionEvent>(event.clone()) {
+                    println!("{}", format_event_line(&event));
+                }
+            }
+            SESSION_CLOSED => break,
+            _ => {}
+        }
+    }
+
+    client
+        .request("unsubscribe", json!({"session_id": session_id}))
+        .await
+        .ok();
+    Ok(())
+}
+
+fn format_event_line(event: &SessionEvent) -> String {
+    match event {
+        SessionEvent::NetworkRequest(req) => {
+            let mut line = format!("{} {} {}", req.status_label(), req.method, req.url);
+            if let Some(ref error) = req.error_text {
+                line.push_str(&format!(" ({})", error));
+            } else if let Some(total) = req.timing.as_ref().and_then(|t| t.total_ms) {
+                line.push_str(&format!(" {:.0}ms", total));
+            }
+            line
+        }
+        SessionEvent::ConsoleMessage(msg) => format!("[{}] {}", msg.level, msg.text),
+        SessionEvent::PageError(err) => format!("[pageerror] {}", err.message),
+        other => serde_json::to_string(other).unwrap_or_default(),
+    }
+}
+
+fn print_replay_summary(result: &Value) {
+    let Some(summary) = result
+        .get("replay")
+        .cloned()
+        .and_then(|v| serde_json::from_value(v).ok())
+    else {
+        return;
+    };
+    let replay = handlers::har::ReplayResult {
+        active: false,
+        summary: Some(summary),
+    };
+    println!("{}", replay.format_text());
+}
+
+fn parse_header_args(headers: &[String]) -> Result<serde_json::Map<String, Value>> {
+    headers
+        .iter()
+        .map(|h| {
+            let (name, value) = h.split_once(':').ok_or_else(|| {
+                ChromeError::General(format!("Invalid header '{}', expected 'Name: value'", h))
+            })?;
+            Ok((name.trim().to_string(), json!(value.trim())))
+        })
+        .collect()
+}
+
+async fn handle_route_via_daemon(
+    subcommand: RouteCommand,
+    client: &mut DaemonClient,
+    session_id: &str,
+    cli: &Cli,
+) -> Result<()> {
+    match subcommand {
+        RouteCommand::Add {
+            pattern,
+            fulfill,
+            status,
+            abort,
+            url,
+            headers,
+        } => {
+            let action = if abort.is_some() {
+                "abort"
+            } else if fulfill.is_some() || status.is_some() {
+                "fulfill"
+            } else {
+                "continue"
+            };
+            let body_file = fulfill
+                .map(std::fs::canonicalize)
+                .transpose()?
+                .map(|p| p.display().to_string());
+
+            let result = daemon_request(
+                client,
+                "route.add",
+                session_id,
+                json!({
+                    "pattern": pattern,
+                    "action": action,
+                    "status": status,
+                    "body_file": body_file,
+                    "reason": abort,
+                    "url": url,
+                    "headers": parse_header_args(&headers)?
+                }),
+            )
+            .await?;
+
+            if cli.json {
+                return print_json(&result);
+            }
+            let result = serde_json::from_value::<handlers::route::RouteAddResult>(result)?;
+            println!("{}", result.format_text());
+            Ok(())
+        }
+        RouteCommand::List => {
+            let result = daemon_request(client, "route.list", session_id, json!({})).await?;
+            if cli.json {
+                return print_json(&result);
+            }
+            let result = serde_json::from_value::<handlers::route::RouteList>(result)?;
+            println!("{}", result.format_text());
+            Ok(())
+        }
+        RouteCommand::Remove { id } => {
+            let result =
+                daemon_request(client, "route.remove", session_id, json!({ "id": id })).await?;
+            if cli.json {
+                return print_json(&result);
+            }
+            let result = serde_json::from_value::<handlers::route::RouteRemoveResult>(result)?;
+            println!("{}", result.format_text());
+            Ok(())
+        }
+    }
+}
+
+async fn handle_env_via_daemon(
+    subcommand: EnvCommand,
+    client: &mut DaemonClient,
+    session_id: &str,
+    cli: &Cli,
+) -> Result<()> {
+    let result = match subcommand {
+        EnvCommand::Set {
+            geolocation,
+            timezone,
+            locale,
+            color_scheme,
+            reduced_motion,
+            forced_colors,
+            media,
+        } => {
+            let env = crate::devices::EnvironmentOverrides {
+                geolocation: geolocation
+                    .as_deref()
+                    .map(crate::devices::Geolocation::parse)
+                    .transpose()?,
+                timezone,
+                locale,
+                color_scheme,
+                reduced_motion,
+                forced_colors,
+                media,
+            };
+            daemon_request(client, "env.set", session_id, serde_json::to_value(env)?).await?
+        }
+        EnvCommand::Show => daemon_request(client, "env.show", session_id, json!({})).await?,
+        EnvCommand::Reset => daemon_request(client, "env.reset", session_id, json!({})).await?,
+    };
+
+    if cli.json {
+        return print_json(&result);
+    }
+    let env = serde_json::from_value::<handlers::emulation::EnvironmentResult>(result)?;
+    println!("{}", env.format_text());
+    Ok(())
+}
+
+async fn handle_permissions_via_daemon(
+    subcommand: PermissionsCommand,
+    client: &mut DaemonClient,
+    session_id: &str,
+    cli: &Cli,
+) -> Result<()> {
+    let (setting, permissions, origin) = match subcommand {
+        PermissionsCommand::Grant {
+            permissions,
+            origin,
+        } => ("granted", permissions, origin),
+        PermissionsCommand::Deny {
+            permissions,
+            origin,
+        } => ("denied", permissions, origin),
+        PermissionsCommand::Reset {
+            permissions,
+            origin,
+        } => ("prompt", permissions, origin),
+    };
+
+    let result = if setting == "prompt" && permissions.is_empty() {
+        daemon_request(
+            client,
+            "permissions.reset",
+            session_id,
+            json!({ "origin": origin }),
+        )
+        .await?
+    } else {
+        daemon_request(
+            client,
+            "permissions.set",
+            session_id,
+            json!({ "setting": setting, "permissions": permissions, "origin": origin }),
+        )
+        .await?
+    };
+
+    if cli.json {
+        return print_json(&result);
+    }
+    let result = serde_json::from_value::<handlers::permissions::PermissionsResult>(result)?;
+    println!("{}", result.format_text());
+    Ok(())
+}
+
+async fn handle_downloads_via_daemon(
+    subcommand: DownloadsCommand,
+    client: &mut DaemonClient,
+    session_id: &str,
+    cli: &Cli,
+) -> Result<()> {
+    let text = match subcommand {
+        DownloadsCommand::List => {
+            let result = daemon_request(client, "downloads.list", session_id, json!({})).await?;
+            if cli.json {
+                return print_json(&result);
+            }
+            serde_json::from_value::<handlers::downloads::DownloadList>(result)?.format_text()
+        }
+        DownloadsCommand::Wait { timeout } => {
+            client.set_timeout(std::time::Duration::from_secs(timeout) + TIMEOUT_MARGIN);
+            let result = daemon_request(
+                client,
+                "downloads.wait",
+                session_id,
+                json!({ "timeout": timeout }),
+            )
+            .await?;
+            if cli.json {
+                return print_json(&result);
+            }
+            serde_json::from_value::<handlers::downloads::DownloadResult>(result)?.format_text()
+        }
+        DownloadsCommand::Save { id, path } => {
+            let result = daemon_request(
+                client,
+                "downloads.save",
+                session_id,
+                json!({ "id": id, "path": std::path::absolute(&path)? }),
+            )
+            .await?;
+            if cli.json {
+                return print_json(&result);
+            }
+            serde_json::from_value::<handlers::downloads::DownloadSaveResult>(result)?.format_text()
+        }
+    };
+    println!("{}", text);
+    Ok(())
+}
+
 async fn handle_storage_via_daemon(
     subcommand: StorageCommand,
     client: &mut DaemonClient,
@@ -1531,6 +2562,7 @@ async fn handle_trace_command(
     output: &std::path::Path,
     user_profile: bool,
     headless: bool,
+    cpu_throttle: Option<f64>,
     cli: &Cli,
     config: &Arc<Config>,
 ) -> Result<()> {
@@ -1564,6 +2596,15 @@ async fn handle_trace_command(
         .ok_or_else(|| ChromeError::General("Failed to get session_id".into()))?
         .to_string();
 
+    if let Some(rate) = cpu_throttle {
+        client
+            .request(
+                "throttle.cpu",
+                json!({"session_id": session_id, "rate": rate}),
+            )
+            .await?;
+    }
+
     let resp = client
         .request("trace.start", json!({"session_id": session_id}))
         .await?;
@@ -1603,6 +2644,7 @@ async fn handle_trace_command(
         "metadata": {
             "url": url,
             "timestamp": chrono::Utc::now().to_rfc3339(),
+            "cpuThrottlingRate": cpu_throttle.unwrap_or(1.0),
         }
     });
 
@@ -1,5 +1,6 @@
 pub mod commands;
 pub mod dispatch;
+pub mod runner;
 
 use clap::Parser;
 use std::path::PathBuf;
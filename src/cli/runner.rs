@@ -0,0 +1,561 @@
+use crate::{
+    ChromeError, Result,
+    client::{DaemonClient, TIMEOUT_MARGIN},
+    handlers::assert::AssertResult,
+    output,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+static VARIABLE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$\{([^}]+)\}").unwrap());
+
+/// A `run` script: `vars` are defaults that `--var` overrides
+#[derive(Debug, Clone, Deserialize)]
+pub struct Script {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub vars: BTreeMap<String, String>,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Step {
+    #[serde(flatten)]
+    pub action: StepAction,
+    /// Name under which the step output is available as `${id}`
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Per-step timeout in milliseconds, after which the daemon cancels the step
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default, alias = "continue-on-error")]
+    pub continue_on_error: bool,
+}
+
+/// Each action takes either a shorthand string or the daemon method's params
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepAction {
+    Navigate(Value),
+    Click(Value),
+    Dblclick(Value),
+    Tap(Value),
+    Drag(Value),
+    Fill(Value),
+    Upload(Value),
+    Press(Value),
+    Hover(Value),
+    Wait(Value),
+    Assert(Value),
+    Screenshot(Value),
+    Eval(Value),
+}
+
+impl StepAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Navigate(_) => "navigate",
+            Self::Click(_) => "click",
+            Self::Dblclick(_) => "dblclick",
+            Self::Tap(_) => "tap",
+            Self::Drag(_) => "drag",
+            Self::Fill(_) => "fill",
+            Self::Upload(_) => "upload",
+            Self::Press(_) => "press",
+            Self::Hover(_) => "hover",
+            Self::Wait(_) => "wait",
+            Self::Assert(_) => "assert",
+            Self::Screenshot(_) => "screenshot",
+            Self::Eval(_) => "eval",
+        }
+    }
+
+    fn args(&self) -> &Value {
+        match self {
+            Self::Navigate(v)
+            | Self::Click(v)
+            | Self::Dblclick(v)
+            | Self::Tap(v)
+            | Self::Drag(v)
+            | Self::Fill(v)
+            | Self::Upload(v)
+            | Self::Press(v)
+            | Self::Hover(v)
+            | Self::Wait(v)
+            | Self::Assert(v)
+            | Self::Screenshot(v)
+            | Self::Eval(v) => v,
+        }
+    }
+
+    /// Expands shorthand into the params the daemon method expects
+    fn params(&self, args: Value) -> Result<Value> {
+        let shorthand_key = match self {
+            Self::Navigate(_) => "url",
+            Self::Click(_) | Self::Dblclick(_) | Self::Tap(_) | Self::Hover(_) | Self::Wait(_) => {
+                "selector"
+            }
+            Self::Press(_) => "key",
+            Self::Eval(_) => "expression",
+            Self::Screenshot(_) => "output",
+            // A bare expression is a `js` assertion
+            Self::Assert(_) => {
+                let mut params = json!({});
+                let assertion = match args {
+                    Value::String(s) => json!({"kind": "js", "expression": s}),
+                    Value::Object(mut map) => {
+                        map.entry("kind").or_insert_with(|| json!("js"));
+                        // Element targeting goes next to the assertion, as from the CLI
+                        for key in ["ref", "frame"] {
+                            if let Some(value) = map.remove(key) {
+                                params[key] = value;
+                            }
+                        }
+                        Value::Object(map)
+                    }
+                    other => {
+                        return Err(ChromeError::General(format!(
+                            "Invalid assert step: {}",
+                            other
+                        )));
+                    }
+                };
+                params["assertion"] = assertion;
+                return Ok(params);
+            }
+            Self::Fill(_) => {
+                return match args {
+                    Value::Object(_) => Ok(args),
+                    _ => Err(ChromeError::General(
+                        "fill step needs an object with selector and text".to_string(),
+                    )),
+                };
+            }
+            Self::Drag(_) => {
+                return match args {
+                    Value::Object(_) => Ok(args),
+                    _ => Err(ChromeError::General(
+                        "drag step needs an object with from and to".to_string(),
+                    )),
+                };
+            }
+            Self::Upload(_) => {
+                let Value::Object(mut map) = args else {
+                    return Err(ChromeError::General(
+                        "upload step needs an object with selector and files".to_string(),
+                    ));
+                };
+                let files = match map.get("files") {
+                    Some(Value::String(file)) => vec![file.clone()],
+                    Some(Value::Array(files)) => files
+                        .iter()
+                        .filter_map(|f| f.as_str().map(String::from))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                // The daemon resolves paths against its own working directory
+                let files = files
+                    .iter()
+                    .map(|f| Ok(std::path::absolute(f)?))
+                    .collect::<Result<Vec<_>>>()?;
+                map.insert("files".to_string(), json!(files));
+                return Ok(Value::Object(map));
+            }
+        };
+
+        let mut params = match args {
+            Value::String(s) => json!({ shorthand_key: s }),
+            Value::Object(_) => args,
+            other => {
+                return Err(ChromeError::General(format!(
+                    "Invalid {} step: {}",
+                    self.name(),
+                    other
+                )));
+            }
+        };
+
+        if let Self::Wait(_) = self
+            && params.get("condition").is_none()
+        {
+            params["condition"] = json!("selector");
+        }
+        if let Self::Screenshot(_) = self
+            && let Some(path) = params.get("output").and_then(|v| v.as_str())
+        {
+            // The daemon resolves paths against its own working directory
+            params["output"] = json!(std::path::absolute(path)?);
+        }
+        Ok(params)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StepReport {
+    pub index: usize,
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub status: StepStatus,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunReport {
+    pub script: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub session_id: String,
+    pub success: bool,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub duration_ms: u64,
+    pub steps: Vec<StepReport>,
+}
+
+impl output::OutputFormatter for RunReport {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+
+        let mut output = text::section(self.name.as_deref().unwrap_or(&self.script));
+        for step in &self.steps {
+            let label = match step.id {
+                Some(ref id) => format!("{}. {} [{}]", step.index, step.action, id),
+                None => format!("{}. {}", step.index, step.action),
+            };
+            let line = match step.status {
+                StepStatus::Passed => text::success(&format!("{} ({}ms)", label, step.duration_ms)),
+                StepStatus::Failed => text::error(&format!(
+                    "{} ({}ms): {}",
+                    label,
+                    step.duration_ms,
+                    step.error.as_deref().unwrap_or("failed")
+                )),
+                StepStatus::Skipped => text::info(&format!("{} skipped", label)),
+            };
+            output.push_str(&format!("\n{}", line));
+        }
+        output.push_str(&format!(
+            "\n\n{}",
+            text::key_value(
+                "Result",
+                &format!(
+                    "{} passed, {} failed, {} skipped in {}ms",
+                    self.passed, self.failed, self.skipped, self.duration_ms
+                )
+            )
+        ));
+        output
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+pub fn load_script(path: &Path) -> Result<Script> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| ChromeError::General(format!("Failed to read {}: {}", path.display(), e)))?;
+    parse_script(&content, path)
+}
+
+fn parse_script(content: &str, path: &Path) -> Result<Script> {
+    let is_json = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    let parsed = if is_json {
+        serde_json::from_str(content).map_err(|e| e.to_string())
+    } else {
+        serde_yaml_ng::from_str(content).map_err(|e| e.to_string())
+    };
+    parsed.map_err(|e| ChromeError::General(format!("Invalid script {}: {}", path.display(), e)))
+}
+
+/// Variables visible to `${...}`: step outputs by id, then script/CLI vars,
+/// then `${env.NAME}` for the environment
+struct Scope {
+    vars: BTreeMap<String, String>,
+    outputs: HashMap<String, Value>,
+}
+
+impl Scope {
+    fn lookup(&self, name: &str) -> Option<String> {
+        if let Some(var) = name.strip_prefix("env.") {
+            return std::env::var(var).ok();
+        }
+
+        let mut path = name.split('.');
+        let head = path.next()?;
+        if let Some(output) = self.outputs.get(head) {
+            let value = path.try_fold(output, |v, key| match v {
+                Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => v.get(key),
+            })?;
+            return Some(match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            });
+        }
+
+        self.vars.get(name).cloned()
+    }
+
+    fn substitute(&self, input: &str) -> Result<String> {
+        let mut missing = None;
+        let replaced = VARIABLE.replace_all(input, |caps: &regex::Captures| {
+            let name = caps[1].trim();
+            self.lookup(name).unwrap_or_else(|| {
+                missing.get_or_insert_with(|| name.to_string());
+                String::new()
+            })
+        });
+        match missing {
+            Some(name) => Err(ChromeError::General(format!(
+                "Undefined variable: ${{{}}}",
+                name
+            ))),
+            None => Ok(replaced.into_owned()),
+        }
+    }
+
+    fn substitute_value(&self, value: &Value) -> Result<Value> {
+        Ok(match value {
+            Value::String(s) => Value::String(self.substitute(s)?),
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|v| self.substitute_value(v))
+                    .collect::<Result<_>>()?,
+            ),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| Ok((k.clone(), self.substitute_value(v)?)))
+                    .collect::<Result<_>>()?,
+            ),
+            other => other.clone(),
+        })
+    }
+}
+
+async fn run_step(
+    client: &mut DaemonClient,
+    session_id: &str,
+    step: &Step,
+    scope: &Scope,
+) -> Result<Value> {
+    let args = scope.substitute_value(step.action.args())?;
+    let mut params = step.action.params(args)?;
+    params["session_id"] = json!(session_id);
+    if let Some(timeout) = step.timeout {
+        if matches!(step.action, StepAction::Navigate(_) | StepAction::Wait(_))
+            && params.get("timeout").is_none()
+        {
+            params["timeout"] = json!(timeout);
+        }
+        // The daemon cancels the step at its deadline rather than finishing it unobserved
+        params["deadline_ms"] = json!(timeout);
+    }
+
+    let mut result = client.request(step.action.name(), params).await?;
+
+    match step.action {
+        StepAction::Eval(_) => Ok(result["result"].take()),
+        StepAction::Assert(_) => {
+            let report: AssertResult = serde_json::from_value(result)?;
+            report.into_result().map(|r| r.actual)
+        }
+        _ => {
+            // Screenshot responses carry the image as base64
+            if let Some(map) = result.as_object_mut() {
+                map.remove("data");
+            }
+            Ok(result)
+        }
+    }
+}
+
+/// Runs every step in order on one connection. A failing step stops the run
+/// unless it sets `continue_on_error`; the remaining steps are reported as skipped.
+pub async fn run_script(
+    client: &mut DaemonClient,
+    session_id: &str,
+    path: &Path,
+    overrides: BTreeMap<String, String>,
+) -> Result<RunReport> {
+    let script = load_script(path)?;
+    let mut scope = Scope {
+        vars: script.vars.clone(),
+        outputs: HashMap::new(),
+    };
+    scope.vars.extend(overrides);
+
+    let default_timeout = client.timeout();
+    let started = Instant::now();
+    let mut steps = Vec::with_capacity(script.steps.len());
+    let mut aborted = false;
+
+    for (index, step) in script.steps.iter().enumerate() {
+        let mut report = StepReport {
+            index: index + 1,
+            action: step.action.name().to_string(),
+            id: step.id.clone(),
+            status: StepStatus::Skipped,
+            duration_ms: 0,
+            output: None,
+            error: None,
+        };
+        if aborted {
+            steps.push(report);
+            continue;
+        }
+
+        client.set_timeout(
+            step.timeout
+                .map(|ms| Duration::from_millis(ms) + TIMEOUT_MARGIN)
+                .unwrap_or(default_timeout),
+        );
+        let step_started = Instant::now();
+        let result = run_step(client, session_id, step, &scope).await;
+        report.duration_ms = step_started.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(output) => {
+                if let Some(ref id) = step.id {
+                    scope.outputs.insert(id.clone(), output.clone());
+                }
+                report.status = StepStatus::Passed;
+                report.output = Some(output).filter(|o| !o.is_null());
+            }
+            Err(e) => {
+                report.status = StepStatus::Failed;
+                report.error = Some(e.to_string());
+                aborted = !step.continue_on_error;
+            }
+        }
+        steps.push(report);
+    }
+    client.set_timeout(default_timeout);
+
+    let count = |status| steps.iter().filter(|s| s.status == status).count();
+    Ok(RunReport {
+        script: path.display().to_string(),
+        name: script.name,
+        session_id: session_id.to_string(),
+        success: !aborted,
+        passed: count(StepStatus::Passed),
+        failed: count(StepStatus::Failed),
+        skipped: count(StepStatus::Skipped),
+        duration_ms: started.elapsed().as_millis() as u64,
+        steps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_yaml_script() {
+        let yaml = r##"
+name: login
+vars:
+  base: https://example.com
+steps:
+  - navigate: ${base}/login
+  - fill: { selector: "#email", text: "a@b.c" }
+    timeout: 5000
+  - click: "#submit"
+    continue-on-error: true
+  - eval: document.title
+    id: title
+"##;
+        let script = parse_script(yaml, Path::new("flow.yaml")).unwrap();
+        assert_eq!(script.steps.len(), 4);
+        assert_eq!(script.steps[1].timeout, Some(5000));
+        assert!(script.steps[2].continue_on_error);
+        assert_eq!(script.steps[3].id.as_deref(), Some("title"));
+
+        let params = script.steps[2]
+            .action
+            .params(script.steps[2].action.args().clone())
+            .unwrap();
+        assert_eq!(params["selector"], "#submit");
+    }
+
+    #[test]
+    fn test_wait_shorthand_defaults_condition() {
+        let action = StepAction::Wait(json!(".ready"));
+        let params = action.params(action.args().clone()).unwrap();
+        assert_eq!(params["condition"], "selector");
+        assert_eq!(params["selector"], ".ready");
+        assert!(StepAction::Fill(json!("x")).params(json!("x")).is_err());
+        assert!(StepAction::Drag(json!("x")).params(json!("x")).is_err());
+    }
+
+    #[test]
+    fn test_upload_step_resolves_files() {
+        let action = StepAction::Upload(json!({"selector": "#avatar", "files": "me.png"}));
+        let params = action.params(action.args().clone()).unwrap();
+        let files = params["files"].as_array().unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(Path::new(files[0].as_str().unwrap()).is_absolute());
+        assert!(StepAction::Upload(json!("x")).params(json!("x")).is_err());
+    }
+
+    #[test]
+    fn test_substitution() {
+        let mut scope = Scope {
+            vars: BTreeMap::from([("base".to_string(), "https://example.com".to_string())]),
+            outputs: HashMap::new(),
+        };
+        scope
+            .outputs
+            .insert("user".to_string(), json!({"name": "kim", "ids": [7, 8]}));
+
+        assert_eq!(
+            scope.substitute("${base}/u/${user.name}").unwrap(),
+            "https://example.com/u/kim"
+        );
+        assert_eq!(scope.substitute("${user.ids.1}").unwrap(), "8");
+        assert!(scope.substitute("${missing}").is_err());
+    }
+
+    #[test]
+    fn test_assert_step_params() {
+        let params = StepAction::Assert(json!(null))
+            .params(json!("document.title"))
+            .unwrap();
+        assert_eq!(params["assertion"]["kind"], "js");
+        assert_eq!(params["assertion"]["expression"], "document.title");
+
+        let params = StepAction::Assert(json!(null))
+            .params(json!({"kind": "count", "selector": "li", "min": 1}))
+            .unwrap();
+        assert_eq!(params["assertion"]["kind"], "count");
+
+        let params = StepAction::Assert(json!(null))
+            .params(json!({"kind": "visible", "selector": "text=Save", "frame": "checkout"}))
+            .unwrap();
+        assert_eq!(params["frame"], "checkout");
+        assert!(params["assertion"].get("frame").is_none());
+    }
+}
@@ -1,17 +1,33 @@
-use crate::server::protocol::{Request, Response};
+use crate::config::NetworkConfig;
+use crate::server::protocol::{Notification, Request, Response};
 use crate::{ChromeError, Result, timeouts::secs};
 use serde_json::Value;
+use std::collections::VecDeque;
 use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::UnixStream;
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
 
 static REQUEST_ID: AtomicU64 = AtomicU64::new(1);
 
+/// Extra wait beyond a daemon-side timeout, so the daemon's error arrives first
+pub const TIMEOUT_MARGIN: Duration = Duration::from_secs(5);
+
+/// Read timeout covering a daemon-side `base` timeout scaled by session throttling,
+/// never below the default
+pub fn throttled_timeout(base: Duration, multiplier: f64) -> Duration {
+    let scaled = base.mul_f64(multiplier.max(1.0)) + TIMEOUT_MARGIN;
+    scaled.max(Duration::from_secs(secs::REQUEST / 2))
+}
+
 pub struct DaemonClient {
-    stream: UnixStream,
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
     timeout: Duration,
+    /// Notifications that arrived while waiting for a response
+    notifications: VecDeque<Notification>,
 }
 
 impl DaemonClient {
@@ -19,38 +35,85 @@ impl DaemonClient {
         let stream = UnixStream::connect(socket_path)
             .await
             .map_err(|e| ChromeError::Connection(format!("Failed to connect to daemon: {}", e)))?;
+        let (read_half, writer) = stream.into_split();
 
         Ok(Self {
-            stream,
+            reader: BufReader::new(read_half),
+            writer,
             timeout: Duration::from_secs(secs::REQUEST / 2),
+            notifications: VecDeque::new(),
         })
     }
 
+    /// Overrides how long `request` waits for a response
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Waits long enough for a request whose daemon-side timeout of `base` is scaled
+    /// by the session's network and CPU throttling
+    pub async fn extend_timeout_for_throttling(
+        &mut self,
+        session_id: &str,
+        base: Duration,
+    ) -> Result<()> {
+        let session = self
+            .request(
+                "session.get",
+                serde_json::json!({ "session_id": session_id }),
+            )
+            .await?;
+        let multiplier = session
+            .get("navigation_multiplier")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(1.0);
+        self.timeout = self.timeout.max(throttled_timeout(base, multiplier));
+        Ok(())
+    }
+
     pub async fn request(&mut self, method: &str, params: Value) -> Result<Value> {
         let id = REQUEST_ID.fetch_add(1, Ordering::SeqCst);
         let request = Request::new(id, method, params);
 
         let json = serde_json::to_string(&request)?;
-        self.stream
+        self.writer
             .write_all(format!("{}\n", json).as_bytes())
             .await
             .map_err(|e| ChromeError::General(format!("Write error: {}", e)))?;
 
-        let (read_half, _) = self.stream.split();
-        let mut reader = BufReader::new(read_half);
-        let mut line = String::new();
-
-        match tokio::time::timeout(self.timeout, reader.read_line(&mut line)).await {
-            Ok(Ok(_)) => {}
-            Ok(Err(e)) => return Err(ChromeError::General(format!("Read error: {}", e))),
-            Err(_) => {
-                return Err(ChromeError::General(
-                    "RPC error -32001: Request timed out.".to_string(),
-                ));
+        let response = loop {
+            let mut line = String::new();
+            match tokio::time::timeout(self.timeout, self.reader.read_line(&mut line)).await {
+                Ok(Ok(0)) => {
+                    return Err(ChromeError::Connection(
+                        "Daemon closed connection".to_string(),
+                    ));
+                }
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => return Err(ChromeError::General(format!("Read error: {}", e))),
+                Err(_) => {
+                    return Err(ChromeError::General(
+                        "RPC error -32001: Request timed out.".to_string(),
+                    ));
+                }
             }
-        }
 
-        let response: Response = serde_json::from_str(&line)?;
+            let message: Value = serde_json::from_str(&line)?;
+            match message.get("id").and_then(|v| v.as_u64()) {
+                Some(response_id) if response_id == id => {
+                    break serde_json::from_value::<Response>(message)?;
+                }
+                // Late response to a request that already timed out
+                Some(_) => continue,
+                None => self
+                    .notifications
+                    .push_back(serde_json::from_value(message)?),
+            }
+        };
 
         if let Some(error) = response.error {
             return Err(ChromeError::General(format!(
@@ -71,18 +134,25 @@ impl DaemonClient {
     }
 
     pub async fn create_session(&mut self, headless: bool) -> Result<String> {
-        self.create_session_with_profile(headless, None).await
+        self.create_session_with_profile(headless, None, None).await
     }
 
+    /// `network` overrides the configured proxy and user agent for the new session
     pub async fn create_session_with_profile(
         &mut self,
         headless: bool,
         profile_directory: Option<String>,
+        network: Option<&NetworkConfig>,
     ) -> Result<String> {
         let mut params = serde_json::json!({ "headless": headless });
         if let Some(profile) = profile_directory {
             params["profile_directory"] = serde_json::Value::String(profile);
         }
+        if let Some(network) = network {
+            params["proxy"] = serde_json::json!(network.proxy);
+            params["proxy_bypass"] = serde_json::json!(network.proxy_bypass);
+            params["user_agent"] = serde_json::json!(network.user_agent);
+        }
         let result = self.request("session.create", params).await?;
 
         result
@@ -95,7 +165,7 @@ impl DaemonClient {
     /// Get or create user-profile session (auto-join/cleanup)
     pub async fn get_or_create_user_profile_session(&mut self, headless: bool) -> Result<String> {
         // Empty string triggers user-profile mode in daemon
-        self.create_session_with_profile(headless, Some(String::new()))
+        self.create_session_with_profile(headless, Some(String::new()), None)
             .await
     }
 
@@ -116,13 +186,12 @@ impl DaemonClient {
         Ok(None)
     }
 
-    pub async fn destroy_session(&mut self, session_id: &str) -> Result<()> {
+    pub async fn destroy_session(&mut self, session_id: &str) -> Result<Value> {
         self.request(
             "session.destroy",
             serde_json::json!({"session_id": session_id}),
         )
-        .await?;
-        Ok(())
+        .await
     }
 
     pub async fn navigate(&mut self, session_id: &str, url: &str) -> Result<Value> {
@@ -148,6 +217,28 @@ impl DaemonClient {
             .ok_or_else(|| ChromeError::General("Invalid screenshot response".to_string()))
     }
 
+    /// Waits for the next pushed notification; `None` once the daemon hangs up
+    pub async fn next_notification(&mut self) -> Result<Option<Notification>> {
+        if let Some(notification) = self.notifications.pop_front() {
+            return Ok(Some(notification));
+        }
+
+        loop {
+            let mut line = String::new();
+            let read = self
+                .reader
+                .read_line(&mut line)
+                .await
+                .map_err(|e| ChromeError::General(format!("Read error: {}", e)))?;
+            if read == 0 {
+                return Ok(None);
+            }
+            if let Ok(notification) = serde_json::from_str::<Notification>(&line) {
+                return Ok(Some(notification));
+            }
+        }
+    }
+
     pub async fn shutdown_daemon(&mut self) -> Result<()> {
         self.request("shutdown", Value::Null).await?;
         Ok(())
@@ -167,4 +258,17 @@ mod tests {
     fn test_is_daemon_running_false() {
         assert!(!is_daemon_running(&PathBuf::from("/tmp/nonexistent.sock")));
     }
+
+    #[test]
+    fn test_throttled_timeout() {
+        let base = Duration::from_secs(30);
+        assert_eq!(throttled_timeout(base, 1.0), Duration::from_secs(60));
+        assert_eq!(throttled_timeout(base, 0.5), Duration::from_secs(60));
+        // Slow 3G
+        assert_eq!(
+            throttled_timeout(base, 6.25),
+            Duration::from_millis(192_500)
+        );
+        assert_eq!(throttled_timeout(base, 10.0), Duration::from_secs(305));
+    }
 }
@@ -1,3 +1,3 @@
 pub mod connection;
 
-pub use connection::{DaemonClient, is_daemon_running};
+pub use connection::{DaemonClient, TIMEOUT_MARGIN, is_daemon_running};
@@ -13,6 +13,160 @@ pub struct DeviceProfile {
     pub touch: bool,
     pub mobile: bool,
     pub landscape: bool,
+    #[serde(default, skip_serializing_if = "EnvironmentOverrides::is_empty")]
+    pub env: EnvironmentOverrides,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Geolocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default = "Geolocation::default_accuracy")]
+    pub accuracy: f64,
+}
+
+impl Geolocation {
+    fn default_accuracy() -> f64 {
+        100.0
+    }
+
+    /// Parses `lat,lng` or `lat,lng,accuracy`
+    pub fn parse(value: &str) -> Result<Self> {
+        let parts: Vec<f64> = value
+            .split(',')
+            .map(|p| p.trim().parse::<f64>())
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|_| {
+                ChromeError::ConfigError(format!(
+                    "Invalid geolocation '{}': expected lat,lng[,accuracy]",
+                    value
+                ))
+            })?;
+        let geolocation = match parts.as_slice() {
+            [latitude, longitude] => Self {
+                latitude: *latitude,
+                longitude: *longitude,
+                accuracy: Self::default_accuracy(),
+            },
+            [latitude, longitude, accuracy] => Self {
+                latitude: *latitude,
+                longitude: *longitude,
+                accuracy: *accuracy,
+            },
+            _ => {
+                return Err(ChromeError::ConfigError(format!(
+                    "Invalid geolocation '{}': expected lat,lng[,accuracy]",
+                    value
+                )));
+            }
+        };
+        geolocation.validate()?;
+        Ok(geolocation)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !(-90.0..=90.0).contains(&self.latitude) || !(-180.0..=180.0).contains(&self.longitude) {
+            return Err(ChromeError::ConfigError(
+                "Latitude must be within ±90 and longitude within ±180".into(),
+            ));
+        }
+        if self.accuracy < 0.0 {
+            return Err(ChromeError::ConfigError(
+                "Geolocation accuracy cannot be negative".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Page environment emulated on top of a device: location, time zone, locale and
+/// CSS media. Unset fields leave the browser default.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geolocation: Option<Geolocation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_scheme: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reduced_motion: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forced_colors: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media: Option<String>,
+}
+
+impl EnvironmentOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Fields set in `other` replace ours
+    pub fn merge(&mut self, other: &EnvironmentOverrides) {
+        fn take<T: Clone>(ours: &mut Option<T>, theirs: &Option<T>) {
+            if theirs.is_some() {
+                ours.clone_from(theirs);
+            }
+        }
+        take(&mut self.geolocation, &other.geolocation);
+        take(&mut self.timezone, &other.timezone);
+        take(&mut self.locale, &other.locale);
+        take(&mut self.color_scheme, &other.color_scheme);
+        take(&mut self.reduced_motion, &other.reduced_motion);
+        take(&mut self.forced_colors, &other.forced_colors);
+        take(&mut self.media, &other.media);
+    }
+
+    /// One-line list of the set overrides, e.g. `timezone=Asia/Seoul, media=print`
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(geo) = self.geolocation {
+            parts.push(format!("geolocation={},{}", geo.latitude, geo.longitude));
+        }
+        for (name, value) in [
+            ("timezone", &self.timezone),
+            ("locale", &self.locale),
+            ("color-scheme", &self.color_scheme),
+            ("reduced-motion", &self.reduced_motion),
+            ("forced-colors", &self.forced_colors),
+            ("media", &self.media),
+        ] {
+            if let Some(value) = value {
+                parts.push(format!("{}={}", name, value));
+            }
+        }
+        parts.join(", ")
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        fn one_of(field: &str, value: &Option<String>, allowed: &[&str]) -> Result<()> {
+            match value {
+                Some(v) if !allowed.contains(&v.as_str()) => Err(ChromeError::ConfigError(
+                    format!("Invalid {} '{}': expected {}", field, v, allowed.join(", ")),
+                )),
+                _ => Ok(()),
+            }
+        }
+        if let Some(geolocation) = &self.geolocation {
+            geolocation.validate()?;
+        }
+        one_of(
+            "color scheme",
+            &self.color_scheme,
+            &["light", "dark", "no-preference"],
+        )?;
+        one_of(
+            "reduced motion",
+            &self.reduced_motion,
+            &["reduce", "no-preference"],
+        )?;
+        one_of("forced colors", &self.forced_colors, &["active", "none"])?;
+        one_of("media type", &self.media, &["screen", "print"])?;
+        Ok(())
+    }
 }
 
 impl DeviceProfile {
@@ -35,7 +189,7 @@ impl DeviceProfile {
             ));
         }
 
-        Ok(())
+        self.env.validate()
     }
 }
 
@@ -52,6 +206,7 @@ pub static DEVICE_PRESETS: Lazy<Vec<DeviceProfile>> = Lazy::new(|| {
             touch: false,
             mobile: false,
             landscape: true,
+            env: EnvironmentOverrides::default(),
         },
         DeviceProfile {
             name: String::from("iPhone 14"),
@@ -64,6 +219,7 @@ pub static DEVICE_PRESETS: Lazy<Vec<DeviceProfile>> = Lazy::new(|| {
             touch: true,
             mobile: true,
             landscape: false,
+            env: EnvironmentOverrides::default(),
         },
         DeviceProfile {
             name: String::from("iPad Pro"),
@@ -76,6 +232,7 @@ pub static DEVICE_PRESETS: Lazy<Vec<DeviceProfile>> = Lazy::new(|| {
             touch: true,
             mobile: true,
             landscape: false,
+            env: EnvironmentOverrides::default(),
         },
         DeviceProfile {
             name: String::from("Pixel 7"),
@@ -88,6 +245,7 @@ pub static DEVICE_PRESETS: Lazy<Vec<DeviceProfile>> = Lazy::new(|| {
             touch: true,
             mobile: true,
             landscape: false,
+            env: EnvironmentOverrides::default(),
         },
         DeviceProfile {
             name: String::from("Galaxy S23"),
@@ -100,6 +258,7 @@ pub static DEVICE_PRESETS: Lazy<Vec<DeviceProfile>> = Lazy::new(|| {
             touch: true,
             mobile: true,
             landscape: false,
+            env: EnvironmentOverrides::default(),
         },
         DeviceProfile {
             name: String::from("iPhone SE"),
@@ -112,6 +271,7 @@ pub static DEVICE_PRESETS: Lazy<Vec<DeviceProfile>> = Lazy::new(|| {
             touch: true,
             mobile: true,
             landscape: false,
+            env: EnvironmentOverrides::default(),
         },
         DeviceProfile {
             name: String::from("Tablet"),
@@ -124,6 +284,7 @@ pub static DEVICE_PRESETS: Lazy<Vec<DeviceProfile>> = Lazy::new(|| {
             touch: true,
             mobile: false,
             landscape: false,
+            env: EnvironmentOverrides::default(),
         },
         DeviceProfile {
             name: String::from("4K Display"),
@@ -136,6 +297,7 @@ pub static DEVICE_PRESETS: Lazy<Vec<DeviceProfile>> = Lazy::new(|| {
             touch: false,
             mobile: false,
             landscape: true,
+            env: EnvironmentOverrides::default(),
         },
     ]
 });
@@ -148,6 +310,16 @@ pub fn get_device_by_name(name: &str) -> Result<DeviceProfile> {
         .ok_or_else(|| ChromeError::DeviceNotFound(name.to_string()))
 }
 
+/// Looks up a preset, then the custom devices in `devices.toml`
+pub fn find_device(name: &str) -> Result<DeviceProfile> {
+    get_device_by_name(name).or_else(|err| {
+        load_custom_devices(None)?
+            .into_iter()
+            .find(|d| d.name.eq_ignore_ascii_case(name))
+            .ok_or(err)
+    })
+}
+
 pub fn load_custom_devices(path: Option<PathBuf>) -> Result<Vec<DeviceProfile>> {
     let devices_path = if let Some(p) = path {
         p
@@ -250,6 +422,7 @@ mod tests {
             touch: false,
             mobile: false,
             landscape: true,
+            env: EnvironmentOverrides::default(),
         };
         assert!(device.validate().is_ok());
     }
@@ -265,6 +438,7 @@ mod tests {
             touch: false,
             mobile: false,
             landscape: true,
+            env: EnvironmentOverrides::default(),
         };
         assert!(device.validate().is_err());
     }
@@ -280,6 +454,7 @@ mod tests {
             touch: false,
             mobile: false,
             landscape: true,
+            env: EnvironmentOverrides::default(),
         };
         assert!(device.validate().is_err());
     }
@@ -295,6 +470,7 @@ mod tests {
             touch: false,
             mobile: false,
             landscape: true,
+            env: EnvironmentOverrides::default(),
         };
         assert!(device.validate().is_err());
     }
@@ -316,6 +492,7 @@ mod tests {
             touch: false,
             mobile: false,
             landscape: true,
+            env: EnvironmentOverrides::default(),
         };
 
         let json = serde_json::to_string(&device).unwrap();
@@ -325,4 +502,66 @@ mod tests {
         assert_eq!(parsed.name, device.name);
         assert_eq!(parsed.width, device.width);
     }
+
+    #[test]
+    fn test_geolocation_parse() {
+        let geo = Geolocation::parse("37.5665, 126.978").unwrap();
+        assert_eq!(geo.latitude, 37.5665);
+        assert_eq!(geo.accuracy, 100.0);
+
+        let geo = Geolocation::parse("0,0,5").unwrap();
+        assert_eq!(geo.accuracy, 5.0);
+
+        assert!(Geolocation::parse("91,0").is_err());
+        assert!(Geolocation::parse("north").is_err());
+        assert!(Geolocation::parse("1,2,3,4").is_err());
+    }
+
+    #[test]
+    fn test_environment_merge_and_validate() {
+        let mut env = EnvironmentOverrides {
+            timezone: Some("Asia/Seoul".to_string()),
+            color_scheme: Some("light".to_string()),
+            ..Default::default()
+        };
+        env.merge(&EnvironmentOverrides {
+            color_scheme: Some("dark".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(env.timezone.as_deref(), Some("Asia/Seoul"));
+        assert_eq!(env.color_scheme.as_deref(), Some("dark"));
+        assert!(env.validate().is_ok());
+        assert_eq!(env.summary(), "timezone=Asia/Seoul, color-scheme=dark");
+
+        env.media = Some("tv".to_string());
+        assert!(env.validate().is_err());
+        assert!(EnvironmentOverrides::default().is_empty());
+    }
+
+    #[test]
+    fn test_custom_device_environment_defaults() {
+        let wrapper: DevicesWrapper = toml::from_str(
+            r#"
+            [[devices]]
+            name = "Kiosk"
+            width = 1080
+            height = 1920
+            pixel_ratio = 1.0
+            user_agent = "Mozilla/5.0"
+            touch = true
+            mobile = false
+            landscape = false
+
+            [devices.env]
+            timezone = "Europe/Berlin"
+            locale = "de-DE"
+            reduced_motion = "reduce"
+            "#,
+        )
+        .unwrap();
+        let device = &wrapper.devices[0];
+        assert!(device.validate().is_ok());
+        assert_eq!(device.env.locale.as_deref(), Some("de-DE"));
+        assert!(device.env.geolocation.is_none());
+    }
 }
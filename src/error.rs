@@ -17,6 +17,22 @@ pub enum ChromeError {
     #[error("Element not found: {selector}")]
     ElementNotFound { selector: String },
 
+    #[error("Element not actionable: {selector} failed the {check} check ({reason})")]
+    NotActionable {
+        selector: String,
+        check: String,
+        reason: String,
+    },
+
+    #[error("Stale ref '{ref_id}': {reason}")]
+    StaleRef { ref_id: String, reason: String },
+
+    #[error("Frame not found: {0}")]
+    FrameNotFound(String),
+
+    #[error("Invalid locator '{locator}': {reason}")]
+    InvalidLocator { locator: String, reason: String },
+
     #[error("Screenshot failed: {0}")]
     ScreenshotFailed(String),
 
@@ -59,6 +75,9 @@ pub enum ChromeError {
     #[error("Session not found")]
     SessionNotFound,
 
+    #[error("Assertion failed: {0}")]
+    AssertionFailed(String),
+
     #[error("General error: {0}")]
     General(String),
 }
@@ -86,6 +105,28 @@ impl ChromeError {
                 "Wait for page to fully load with --wait-for load".into(),
                 format!("Check if element '{}' exists on the page", selector),
             ],
+            Self::NotActionable { check, .. } if check == "hit" => vec![
+                "Close the overlay, dialog or banner covering the element first".into(),
+                "Pass --mode js to click, fill or type to skip actionability checks".into(),
+            ],
+            Self::NotActionable { .. } => vec![
+                "Wait for the page to settle with the wait command first".into(),
+                "Pass --mode js to click, fill or type to skip actionability checks".into(),
+            ],
+            Self::StaleRef { .. } => vec![
+                "Run describe again to get fresh refs".into(),
+                "Refs only last until the next describe or navigation".into(),
+            ],
+            Self::FrameNotFound(_) => vec![
+                "Match a frame by name, URL substring or index (0 = first child frame)".into(),
+                "Frames are numbered in document order, nested frames right after their parent"
+                    .into(),
+                "Run describe to see which frame each element lives in".into(),
+            ],
+            Self::InvalidLocator { .. } => vec![
+                "Use css=, role=, text=, label=, placeholder=, testid= or xpath=".into(),
+                "Quote values with spaces: role=button[name=\"Sign in\"]".into(),
+            ],
             Self::ScreenshotFailed(_) => vec![
                 "Ensure output directory exists and is writable".into(),
                 "Check if page is fully loaded".into(),
@@ -131,6 +172,10 @@ impl ChromeError {
                 "Start a new session by running a command without existing session".into(),
                 "Check if Chrome was closed manually".into(),
             ],
+            Self::AssertionFailed(_) => vec![
+                "Re-run with --json for the observed values".into(),
+                "Wait for the page to settle with the wait command first".into(),
+            ],
             _ => vec![
                 "Run with --verbose for more details".into(),
                 "Check the documentation for help".into(),
@@ -142,13 +187,17 @@ impl ChromeError {
         match self {
             Self::LaunchFailed(_) | Self::ConnectionLost | Self::SessionNotFound => 3,
             Self::NavigationTimeout(_) => 4,
-            Self::ElementNotFound { .. } => 5,
+            Self::ElementNotFound { .. }
+            | Self::NotActionable { .. }
+            | Self::StaleRef { .. }
+            | Self::FrameNotFound(_) => 5,
             Self::IoError(_) | Self::ScreenshotFailed(_) | Self::StorageError(_) => 6,
             Self::ConfigError(_)
             | Self::TomlDeError(_)
             | Self::TomlSerError(_)
             | Self::InvalidPort(_) => 7,
-            Self::InvalidUrl(_) => 2,
+            Self::InvalidUrl(_) | Self::InvalidLocator { .. } => 2,
+            Self::AssertionFailed(_) => 8,
             _ => 1,
         }
     }
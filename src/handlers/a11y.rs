@@ -1,4 +1,8 @@
-use crate::{ChromeError, Result, chrome::PageProvider, output};
+use crate::{
+    ChromeError, Result,
+    chrome::{PageProvider, frames},
+    output,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize)]
@@ -121,12 +125,7 @@ pub async fn handle_a11y(
     let page = provider.get_or_create_page().await?;
 
     let selector_code = selector
-        .map(|s| {
-            format!(
-                "document.querySelector('{}')",
-                crate::js_templates::escape_selector(s)
-            )
-        })
+        .map(crate::js_templates::deep_query)
         .unwrap_or_else(|| "document.body".to_string());
 
     let interactive_roles = if interactable_only {
@@ -235,12 +234,9 @@ pub async fn handle_a11y(
         depth = depth
     );
 
-    let result = page
-        .evaluate(script)
-        .await
-        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?;
+    let result = frames::evaluate(&page, selector.unwrap_or_default(), script).await?;
 
-    let js_nodes: Option<Vec<JsA11yNode>> = result.into_value().unwrap_or(None);
+    let js_nodes: Option<Vec<JsA11yNode>> = serde_json::from_value(result).unwrap_or(None);
 
     let nodes = js_nodes.ok_or_else(|| {
         ChromeError::General(
@@ -0,0 +1,421 @@
+use crate::{
+    ChromeError, Result,
+    chrome::{ConsoleLevel, ConsoleMessage, NetworkRequest, PageError, PageProvider, frames},
+    handlers::input,
+    js_templates, output,
+};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextMatch {
+    Equals(String),
+    Contains(String),
+    Matches(String),
+}
+
+impl TextMatch {
+    fn test(&self, actual: &str) -> Result<bool> {
+        Ok(match self {
+            Self::Equals(expected) => actual == expected,
+            Self::Contains(expected) => actual.contains(expected.as_str()),
+            Self::Matches(pattern) => Regex::new(pattern)
+                .map_err(|e| ChromeError::General(format!("Invalid pattern: {}", e)))?
+                .is_match(actual),
+        })
+    }
+}
+
+impl std::fmt::Display for TextMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Equals(s) => write!(f, "equals {:?}", s),
+            Self::Contains(s) => write!(f, "contains {:?}", s),
+            Self::Matches(s) => write!(f, "matches /{}/", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Assertion {
+    Exists {
+        selector: String,
+    },
+    Visible {
+        selector: String,
+    },
+    Count {
+        selector: String,
+        #[serde(default)]
+        eq: Option<usize>,
+        #[serde(default)]
+        min: Option<usize>,
+        #[serde(default)]
+        max: Option<usize>,
+    },
+    Text {
+        selector: String,
+        matcher: TextMatch,
+    },
+    Attribute {
+        selector: String,
+        name: String,
+        matcher: TextMatch,
+    },
+    Url {
+        matcher: TextMatch,
+    },
+    Title {
+        matcher: TextMatch,
+    },
+    NoConsoleErrors {
+        /// Mark name or RFC 3339 timestamp
+        #[serde(default)]
+        since: Option<String>,
+    },
+    NoFailedRequests {
+        #[serde(default)]
+        since: Option<String>,
+        /// Only count network-level failures, not 4xx/5xx responses
+        #[serde(default)]
+        ignore_http_errors: bool,
+    },
+    Js {
+        expression: String,
+    },
+}
+
+impl Assertion {
+    pub fn since(&self) -> Option<&str> {
+        match self {
+            Self::NoConsoleErrors { since } | Self::NoFailedRequests { since, .. } => {
+                since.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// The element the assertion is about, for the daemon to fill in from `--ref`
+    pub fn selector_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Self::Exists { selector }
+            | Self::Visible { selector }
+            | Self::Count { selector, .. }
+            | Self::Text { selector, .. }
+            | Self::Attribute { selector, .. } => Some(selector),
+            _ => None,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Exists { selector } => format!("'{}' exists", selector),
+            Self::Visible { selector } => format!("'{}' is visible", selector),
+            Self::Count {
+                selector,
+                eq,
+                min,
+                max,
+            } => {
+                let mut bounds = Vec::new();
+                if let Some(eq) = eq {
+                    bounds.push(format!("== {}", eq));
+                }
+                if let Some(min) = min {
+                    bounds.push(format!(">= {}", min));
+                }
+                if let Some(max) = max {
+                    bounds.push(format!("<= {}", max));
+                }
+                format!("count of '{}' {}", selector, bounds.join(" and "))
+            }
+            Self::Text { selector, matcher } => format!("text of '{}' {}", selector, matcher),
+            Self::Attribute {
+                selector,
+                name,
+                matcher,
+            } => format!("[{}] of '{}' {}", name, selector, matcher),
+            Self::Url { matcher } => format!("URL {}", matcher),
+            Self::Title { matcher } => format!("title {}", matcher),
+            Self::NoConsoleErrors { since } => match since {
+                Some(since) => format!("no console errors since {}", since),
+                None => "no console errors".to_string(),
+            },
+            Self::NoFailedRequests { since, .. } => match since {
+                Some(since) => format!("no failed requests since {}", since),
+                None => "no failed requests".to_string(),
+            },
+            Self::Js { expression } => format!("`{}` is truthy", expression),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssertResult {
+    pub passed: bool,
+    pub assertion: Assertion,
+    pub message: String,
+    /// What was observed, for diagnostics
+    pub actual: Value,
+}
+
+impl output::OutputFormatter for AssertResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        if self.passed {
+            text::success(&self.message)
+        } else {
+            let actual = serde_json::to_string_pretty(&self.actual).unwrap_or_default();
+            format!(
+                "{}\n{}",
+                text::error(&self.message),
+                text::key_value("Actual", &actual)
+            )
+        }
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+impl AssertResult {
+    /// Converts a failed assertion into the error that sets the exit code
+    pub fn into_result(self) -> Result<Self> {
+        if self.passed {
+            Ok(self)
+        } else {
+            Err(ChromeError::AssertionFailed(self.message))
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ElementState {
+    count: usize,
+    visible: bool,
+    text: Option<String>,
+    attribute: Option<String>,
+}
+
+/// Checked once, not waited for: a locator that matches nothing is a count of zero
+async fn element_state(
+    provider: &impl PageProvider,
+    selector: &str,
+    frame: Option<&str>,
+    attribute: Option<&str>,
+) -> Result<ElementState> {
+    let Some(css) = input::match_selector(provider, selector, frame).await? else {
+        return Ok(ElementState::default());
+    };
+    let page = provider.get_or_create_page().await?;
+    let state = frames::evaluate(&page, &css, js_templates::element_state(&css, attribute)).await?;
+    serde_json::from_value(state).map_err(|e| ChromeError::EvaluationError(e.to_string()))
+}
+
+async fn evaluate(provider: &impl PageProvider, expression: &str) -> Result<Value> {
+    let page = provider.get_or_create_page().await?;
+    Ok(page
+        .evaluate(expression)
+        .await
+        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?
+        .into_value()
+        .unwrap_or(Value::Null))
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(_) | Value::Object(_) => true,
+    }
+}
+
+fn is_failed_request(request: &NetworkRequest, ignore_http_errors: bool) -> bool {
+    request.error_text.is_some()
+        || (!ignore_http_errors && request.status.is_some_and(|s| s >= 400))
+}
+
+/// Evaluates `assertion`; `since` is the resolved start of the window for
+/// console and network assertions and `frame` scopes element assertions.
+/// A failed check is `passed: false`, not an error.
+pub async fn handle_assert(
+    provider: &impl PageProvider,
+    assertion: Assertion,
+    since: Option<DateTime<Utc>>,
+    frame: Option<&str>,
+) -> Result<AssertResult> {
+    let after = |ts: DateTime<Utc>| since.is_none_or(|since| ts >= since);
+
+    let (passed, actual) = match &assertion {
+        Assertion::Exists { selector } => {
+            let state = element_state(provider, selector, frame, None).await?;
+            (state.count > 0, json!({"count": state.count}))
+        }
+        Assertion::Visible { selector } => {
+            let state = element_state(provider, selector, frame, None).await?;
+            (
+                state.visible,
+                json!({"count": state.count, "visible": state.visible}),
+            )
+        }
+        Assertion::Count {
+            selector,
+            eq,
+            min,
+            max,
+        } => {
+            let count = element_state(provider, selector, frame, None).await?.count;
+            let passed = eq.is_none_or(|eq| count == eq)
+                && min.is_none_or(|min| count >= min)
+                && max.is_none_or(|max| count <= max);
+            (passed, json!({"count": count}))
+        }
+        Assertion::Text { selector, matcher } => {
+            let state = element_state(provider, selector, frame, None).await?;
+            let passed = match state.text {
+                Some(ref text) => matcher.test(text)?,
+                None => false,
+            };
+            (passed, json!({"count": state.count, "text": state.text}))
+        }
+        Assertion::Attribute {
+            selector,
+            name,
+            matcher,
+        } => {
+            let state = element_state(provider, selector, frame, Some(name)).await?;
+            let passed = match state.attribute {
+                Some(ref value) => matcher.test(value)?,
+                None => false,
+            };
+            (
+                passed,
+                json!({"count": state.count, "value": state.attribute}),
+            )
+        }
+        Assertion::Url { matcher } => {
+            let page = provider.get_or_create_page().await?;
+            let url = page.url().await.ok().flatten().unwrap_or_default();
+            (matcher.test(&url)?, json!({"url": url}))
+        }
+        Assertion::Title { matcher } => {
+            let title = evaluate(provider, "document.title").await?;
+            let title = title.as_str().unwrap_or_default();
+            (matcher.test(title)?, json!({"title": title}))
+        }
+        Assertion::NoConsoleErrors { .. } => {
+            let collectors = provider.collectors();
+            let console: Vec<ConsoleMessage> = collectors
+                .console
+                .get_messages_filtered(Some(ConsoleLevel::Error))?
+                .into_iter()
+                .filter(|m| after(m.timestamp))
+                .collect();
+            let page_errors: Vec<PageError> = collectors
+                .pageerror
+                .get_errors()?
+                .into_iter()
+                .filter(|e| after(e.timestamp))
+                .collect();
+            (
+                console.is_empty() && page_errors.is_empty(),
+                json!({"console_errors": console, "page_errors": page_errors}),
+            )
+        }
+        Assertion::NoFailedRequests {
+            ignore_http_errors, ..
+        } => {
+            let failed: Vec<NetworkRequest> = provider
+                .collectors()
+                .network
+                .get_requests()?
+                .into_iter()
+                .filter(|r| after(r.timestamp) && is_failed_request(r, *ignore_http_errors))
+                .collect();
+            (failed.is_empty(), json!({"failed_requests": failed}))
+        }
+        Assertion::Js { expression } => {
+            let value = evaluate(provider, expression).await?;
+            (is_truthy(&value), json!({"result": value}))
+        }
+    };
+
+    let description = assertion.describe();
+    let message = if passed {
+        description
+    } else {
+        format!("Expected {}", description)
+    };
+
+    Ok(AssertResult {
+        passed,
+        assertion,
+        message,
+        actual,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_match() {
+        assert!(TextMatch::Equals("a".into()).test("a").unwrap());
+        assert!(TextMatch::Contains("ell".into()).test("hello").unwrap());
+        assert!(
+            TextMatch::Matches(r"^\d+ items$".into())
+                .test("12 items")
+                .unwrap()
+        );
+        assert!(!TextMatch::Matches("^x".into()).test("abc").unwrap());
+        assert!(TextMatch::Matches("(".into()).test("abc").is_err());
+    }
+
+    #[test]
+    fn test_assertion_serde() {
+        let assertion: Assertion = serde_json::from_value(json!({
+            "kind": "attribute",
+            "selector": "a",
+            "name": "href",
+            "matcher": {"contains": "/docs"}
+        }))
+        .unwrap();
+        assert_eq!(assertion.describe(), "[href] of 'a' contains \"/docs\"");
+
+        let count: Assertion =
+            serde_json::from_value(json!({"kind": "count", "selector": "li", "min": 2})).unwrap();
+        assert_eq!(count.describe(), "count of 'li' >= 2");
+    }
+
+    #[test]
+    fn test_is_truthy() {
+        assert!(is_truthy(&json!(true)));
+        assert!(is_truthy(&json!("x")));
+        assert!(!is_truthy(&json!(0)));
+        assert!(!is_truthy(&json!("")));
+        assert!(!is_truthy(&Value::Null));
+    }
+
+    #[test]
+    fn test_failed_result_maps_to_assertion_error() {
+        let result = AssertResult {
+            passed: false,
+            assertion: Assertion::Js {
+                expression: "false".into(),
+            },
+            message: "Expected `false` is truthy".into(),
+            actual: Value::Null,
+        };
+        let err = result.into_result().unwrap_err();
+        assert!(matches!(err, ChromeError::AssertionFailed(_)));
+        assert_eq!(err.exit_code(), 8);
+    }
+}
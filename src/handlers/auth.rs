@@ -1,4 +1,5 @@
 use crate::chrome::PageProvider;
+use crate::handlers::permissions::{self, PermissionOverride};
 use crate::output::{self, OutputFormatter};
 use crate::{ChromeError, Result};
 use chromiumoxide::cdp::browser_protocol::network::{
@@ -41,6 +42,9 @@ pub struct OriginStorage {
 pub struct StorageState {
     pub cookies: Vec<PlaywrightCookie>,
     pub origins: Vec<OriginStorage>,
+    /// Not part of Playwright's format; other tools ignore it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<PermissionOverride>,
 }
 
 impl StorageState {
@@ -48,6 +52,7 @@ impl StorageState {
         Self {
             cookies: Vec::new(),
             origins: Vec::new(),
+            permissions: Vec::new(),
         }
     }
 }
@@ -63,6 +68,7 @@ pub struct AuthExportResult {
     pub output: Option<String>,
     pub cookies_count: usize,
     pub origins_count: usize,
+    pub permissions_count: usize,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub storage_state: Option<StorageState>,
 }
@@ -73,6 +79,12 @@ impl OutputFormatter for AuthExportResult {
         let mut lines = vec![text::success("Auth state exported")];
         lines.push(text::key_value("Cookies", &self.cookies_count.to_string()));
         lines.push(text::key_value("Origins", &self.origins_count.to_string()));
+        if self.permissions_count > 0 {
+            lines.push(text::key_value(
+                "Permissions",
+                &self.permissions_count.to_string(),
+            ));
+        }
         if let Some(ref path) = self.output {
             lines.push(text::key_value("Output", path));
         }
@@ -92,9 +104,11 @@ impl OutputFormatter for AuthExportResult {
     }
 }
 
+/// `permissions` are the session's overrides to include, if requested
 pub async fn handle_auth_export(
     provider: &impl PageProvider,
     output: Option<&Path>,
+    permissions: Vec<PermissionOverride>,
 ) -> Result<AuthExportResult> {
     let page = provider.get_or_create_page().await?;
 
@@ -145,10 +159,12 @@ pub async fn handle_auth_export(
     let storage_state = StorageState {
         cookies: cookies.clone(),
         origins: origins.clone(),
+        permissions,
     };
 
     let cookies_count = storage_state.cookies.len();
     let origins_count = storage_state.origins.len();
+    let permissions_count = storage_state.permissions.len();
 
     if let Some(path) = output {
         let json = serde_json::to_string_pretty(&storage_state)?;
@@ -161,6 +177,7 @@ pub async fn handle_auth_export(
             output: Some(path.display().to_string()),
             cookies_count,
             origins_count,
+            permissions_count,
             storage_state: None,
         })
     } else {
@@ -168,6 +185,7 @@ pub async fn handle_auth_export(
             output: None,
             cookies_count,
             origins_count,
+            permissions_count,
             storage_state: Some(storage_state),
         })
     }
@@ -211,15 +229,26 @@ fn extract_origin(url: &str) -> String {
 pub struct AuthImportResult {
     pub cookies_imported: usize,
     pub origins_imported: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<PermissionOverride>,
+    /// Why each permission override that could not be applied failed
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub permission_errors: Vec<String>,
 }
 
 impl OutputFormatter for AuthImportResult {
     fn format_text(&self) -> String {
         use crate::output::text;
-        text::success(&format!(
+        let mut summary = format!(
             "Imported {} cookies, {} origins",
             self.cookies_imported, self.origins_imported
-        ))
+        );
+        if !self.permissions.is_empty() {
+            summary.push_str(&format!(", {} permissions", self.permissions.len()));
+        }
+        let mut lines = vec![text::success(&summary)];
+        lines.extend(self.permission_errors.iter().map(|e| text::warning(e)));
+        lines.join("\n")
     }
 
     fn format_json(&self, pretty: bool) -> Result<String> {
@@ -285,9 +314,23 @@ pub async fn handle_auth_import(
         }
     }
 
+    let mut imported_permissions = Vec::new();
+    let mut permission_errors = Vec::new();
+    for permission in storage_state.permissions {
+        match permissions::apply_override(&page, &permission).await {
+            Ok(()) => imported_permissions.push(permission),
+            Err(e) => {
+                tracing::warn!("{}", e);
+                permission_errors.push(e.to_string());
+            }
+        }
+    }
+
     Ok(AuthImportResult {
         cookies_imported,
         origins_imported,
+        permissions: imported_permissions,
+        permission_errors,
     })
 }
 
@@ -331,11 +374,13 @@ mod tests {
                     value: "123".to_string(),
                 }],
             }],
+            permissions: Vec::new(),
         };
 
         let json = serde_json::to_string_pretty(&state).unwrap();
         assert!(json.contains("session"));
         assert!(json.contains("abc123"));
         assert!(json.contains("user_id"));
+        assert!(!json.contains("permissions"));
     }
 }
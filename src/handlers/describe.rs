@@ -1,11 +1,54 @@
-use crate::{ChromeError, Result, chrome::PageProvider, js_templates, output};
+use crate::{
+    ChromeError, Result,
+    chrome::{
+        PageProvider,
+        frames::{self, PageFrame},
+    },
+    js_templates, output,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
 
 #[derive(Debug, Serialize)]
 pub struct DescribeResult {
     pub page: PageInfo,
     pub elements: Vec<VisibleElement>,
     pub summary: Summary,
+    /// Identifies the document the refs were stamped on
+    #[serde(skip)]
+    pub ref_epoch: Option<String>,
+}
+
+/// Refs issued by the last `describe`, kept on the session to detect stale targets
+#[derive(Debug, Clone, Default)]
+pub struct RefMap {
+    pub epoch: String,
+    /// Ref id to a short description such as `<button> "Sign in"`
+    pub targets: HashMap<String, String>,
+}
+
+impl DescribeResult {
+    pub fn ref_map(&self) -> Option<RefMap> {
+        let epoch = self.ref_epoch.clone()?;
+        let targets = self
+            .elements
+            .iter()
+            .map(|el| {
+                let mut desc = format!("<{}>", el.tag);
+                if let Some(name) = el.label.as_ref().or(el.text.as_ref()) {
+                    desc.push_str(&format!(" \"{}\"", output::text::truncate(name, 40)));
+                }
+                (el.ref_id.clone(), desc)
+            })
+            .collect();
+        Some(RefMap { epoch, targets })
+    }
+}
+
+/// Selector for an element stamped by `describe`
+pub fn ref_selector(ref_id: &str) -> String {
+    format!("[{}=\"{}\"]", js_templates::REF_ATTRIBUTE, ref_id)
 }
 
 #[derive(Debug, Serialize)]
@@ -37,6 +80,9 @@ pub struct VisibleElement {
     pub state: Option<ElementState>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub selector: Option<String>,
+    /// Owning frame for elements inside iframes, e.g. `checkout >> https://pay.example/widget`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bounds: Option<Bounds>,
 }
@@ -131,6 +177,10 @@ impl output::OutputFormatter for DescribeResult {
                 desc.push_str(&format!(" → {}", output::text::truncate(selector, 50)));
             }
 
+            if let Some(ref frame) = el.frame {
+                desc.push_str(&format!(" [frame: {}]", output::text::truncate(frame, 50)));
+            }
+
             lines.push(desc);
         }
 
@@ -143,8 +193,14 @@ impl output::OutputFormatter for DescribeResult {
 }
 
 #[derive(Deserialize)]
-struct JsDescribeResult {
+struct JsPage {
+    #[serde(rename = "refEpoch")]
+    ref_epoch: Option<String>,
     page: JsPageInfo,
+}
+
+#[derive(Deserialize)]
+struct JsDescribeResult {
     elements: Vec<JsElement>,
     summary: JsSummary,
 }
@@ -225,30 +281,104 @@ impl<'a> DescribeOptions<'a> {
     }
 }
 
+/// Describes the top document and then every frame, out-of-process ones included, each in its
+/// own execution context. With a selector only the document holding that element is described.
 pub async fn handle_describe(
     provider: &impl PageProvider,
     options: DescribeOptions<'_>,
 ) -> Result<DescribeResult> {
     let page = provider.get_or_create_page().await?;
 
-    let script = js_templates::describe_visible_elements(
-        options.selector,
-        options.interactable,
-        options.forms,
-        options.navigation,
-        options.limit,
-        options.with_bounds,
-        options.with_selectors,
-    );
-
-    let result = page
-        .evaluate(script)
+    let info: JsPage = page
+        .evaluate(js_templates::PAGE_INFO)
         .await
+        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?
+        .into_value()
         .map_err(|e| ChromeError::EvaluationError(e.to_string()))?;
 
-    let js_result: Option<JsDescribeResult> = result.into_value().unwrap_or(None);
+    let documents: Vec<Option<Arc<PageFrame>>> = match options.selector {
+        Some(selector) => vec![frames::scope(&page, selector)],
+        None => {
+            let browser = provider.browser().await;
+            std::iter::once(None)
+                .chain(
+                    frames::list(&page, browser.as_ref())
+                        .await?
+                        .into_iter()
+                        .map(Some),
+                )
+                .collect()
+        }
+    };
+
+    let mut elements = Vec::new();
+    let mut summary: Option<Summary> = None;
+    for frame in documents {
+        let start = elements.len();
+        let script = js_templates::describe_visible_elements(
+            options.selector,
+            options.interactable,
+            options.forms,
+            options.navigation,
+            start..options.limit.max(start),
+            options.with_bounds,
+            options.with_selectors,
+        );
+        let value = match &frame {
+            Some(frame) => match frame.evaluate(script).await {
+                Ok(value) => value,
+                Err(e) => {
+                    tracing::debug!("Skipping frame {}: {}", frame.label(), e);
+                    continue;
+                }
+            },
+            None => page
+                .evaluate(script)
+                .await
+                .map_err(|e| ChromeError::EvaluationError(e.to_string()))?
+                .into_value()
+                .unwrap_or_default(),
+        };
+        let Some(data) = serde_json::from_value::<Option<JsDescribeResult>>(value).unwrap_or(None)
+        else {
+            continue;
+        };
+
+        let (dx, dy) = match &frame {
+            Some(frame) => frame.offset().await.unwrap_or_default(),
+            None => (0.0, 0.0),
+        };
+        for el in data.elements {
+            let mut el = convert_element(el);
+            frames::remember(&page, &ref_selector(&el.ref_id), frame.clone());
+            if let Some(frame) = &frame {
+                el.frame = Some(frame.label());
+                if let Some(bounds) = el.bounds.as_mut() {
+                    bounds.x = (bounds.x + dx).round();
+                    bounds.y = (bounds.y + dy).round();
+                    bounds.in_viewport = bounds.x >= 0.0
+                        && bounds.y >= 0.0
+                        && bounds.x + bounds.width <= info.page.viewport.width as f64
+                        && bounds.y + bounds.height <= info.page.viewport.height as f64;
+                }
+            }
+            elements.push(el);
+        }
+        let total = summary.get_or_insert(Summary {
+            total_visible: 0,
+            interactive: 0,
+            forms: 0,
+            navigation: 0,
+            truncated: false,
+        });
+        total.total_visible += data.summary.total_visible;
+        total.interactive += data.summary.interactive;
+        total.forms += data.summary.forms;
+        total.navigation += data.summary.navigation;
+        total.truncated |= data.summary.truncated;
+    }
 
-    let data = js_result.ok_or_else(|| {
+    let summary = summary.ok_or_else(|| {
         ChromeError::General(
             options
                 .selector
@@ -259,21 +389,16 @@ pub async fn handle_describe(
 
     Ok(DescribeResult {
         page: PageInfo {
-            url: data.page.url,
-            title: data.page.title,
+            url: info.page.url,
+            title: info.page.title,
             viewport: Viewport {
-                width: data.page.viewport.width,
-                height: data.page.viewport.height,
+                width: info.page.viewport.width,
+                height: info.page.viewport.height,
             },
         },
-        elements: data.elements.into_iter().map(convert_element).collect(),
-        summary: Summary {
-            total_visible: data.summary.total_visible,
-            interactive: data.summary.interactive,
-            forms: data.summary.forms,
-            navigation: data.summary.navigation,
-            truncated: data.summary.truncated,
-        },
+        elements,
+        summary,
+        ref_epoch: info.ref_epoch,
     })
 }
 
@@ -297,6 +422,7 @@ fn convert_element(el: JsElement) -> VisibleElement {
             required: s.required,
         }),
         selector: el.selector,
+        frame: None,
         bounds: el.bounds.map(|b| Bounds {
             x: b.x,
             y: b.y,
@@ -383,6 +509,7 @@ mod tests {
                 category: ElementCategory::Interactive,
                 state: None,
                 selector: Some("#submit-btn".to_string()),
+                frame: Some("checkout".to_string()),
                 bounds: None,
             }],
             summary: Summary {
@@ -392,6 +519,7 @@ mod tests {
                 navigation: 0,
                 truncated: false,
             },
+            ref_epoch: None,
         };
 
         let text = result.format_text();
@@ -399,6 +527,7 @@ mod tests {
         assert!(text.contains("<button>"));
         assert!(text.contains("Submit"));
         assert!(text.contains("i0"));
+        assert!(text.contains("[frame: checkout]"));
     }
 
     #[test]
@@ -408,6 +537,47 @@ mod tests {
         assert_eq!(generate_ref(&ElementCategory::Navigation, 12), "n12");
     }
 
+    #[test]
+    fn test_ref_map() {
+        let mut result = DescribeResult {
+            page: PageInfo {
+                url: "https://example.com".to_string(),
+                title: "Test".to_string(),
+                viewport: Viewport {
+                    width: 800,
+                    height: 600,
+                },
+            },
+            elements: vec![VisibleElement {
+                ref_id: "i0".to_string(),
+                tag: "button".to_string(),
+                role: None,
+                label: None,
+                text: Some("Sign in".to_string()),
+                category: ElementCategory::Interactive,
+                state: None,
+                selector: None,
+                frame: None,
+                bounds: None,
+            }],
+            summary: Summary {
+                total_visible: 1,
+                interactive: 1,
+                forms: 0,
+                navigation: 0,
+                truncated: false,
+            },
+            ref_epoch: None,
+        };
+        assert!(result.ref_map().is_none());
+
+        result.ref_epoch = Some("e1".to_string());
+        let refs = result.ref_map().unwrap();
+        assert_eq!(refs.epoch, "e1");
+        assert_eq!(refs.targets["i0"], "<button> \"Sign in\"");
+        assert_eq!(ref_selector("i0"), "[data-cdtcli-ref=\"i0\"]");
+    }
+
     #[test]
     fn test_describe_result_json() {
         let result = DescribeResult {
@@ -427,6 +597,7 @@ mod tests {
                 navigation: 0,
                 truncated: false,
             },
+            ref_epoch: None,
         };
 
         let json = result.format_json(false).unwrap();
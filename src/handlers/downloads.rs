@@ -0,0 +1,163 @@
+use crate::chrome::collectors::{Download, DownloadCollector, DownloadState};
+use crate::{ChromeError, Result, output};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DownloadList {
+    pub downloads: Vec<Download>,
+}
+
+impl output::OutputFormatter for DownloadList {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+
+        if self.downloads.is_empty() {
+            return text::info("No downloads");
+        }
+
+        let mut output = text::section("Downloads");
+        for (index, download) in self.downloads.iter().enumerate() {
+            output.push_str(&format!("\n  [{}] {}", index, describe_download(download)));
+        }
+        output
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+/// One-line summary, e.g. `report.csv completed (12.0 KB)`
+pub fn describe_download(download: &Download) -> String {
+    use crate::output::text;
+    let size = match download.state {
+        DownloadState::InProgress if download.total_bytes > 0 => format!(
+            "{} / {}",
+            text::format_bytes(download.received_bytes),
+            text::format_bytes(download.total_bytes)
+        ),
+        _ => text::format_bytes(download.received_bytes),
+    };
+    format!("{} {} ({})", download.filename, download.state, size)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DownloadResult {
+    pub download: Download,
+}
+
+impl output::OutputFormatter for DownloadResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        format!(
+            "{}\n{}\n{}",
+            text::success(&format!("Downloaded: {}", self.download.filename)),
+            text::key_value("URL", &self.download.url),
+            text::key_value("Path", &self.download.path)
+        )
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DownloadSaveResult {
+    pub guid: String,
+    pub saved: String,
+}
+
+impl output::OutputFormatter for DownloadSaveResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        text::success(&format!("Saved: {}", self.saved))
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+pub async fn handle_downloads_list(collector: &DownloadCollector) -> Result<DownloadList> {
+    Ok(DownloadList {
+        downloads: collector.list().await,
+    })
+}
+
+/// Waits for the next download to finish and fails if it was canceled
+pub async fn handle_downloads_wait(
+    collector: &DownloadCollector,
+    timeout_secs: u64,
+) -> Result<DownloadResult> {
+    let download = collector
+        .wait_finished(Duration::from_secs(timeout_secs))
+        .await?;
+    if download.state == DownloadState::Canceled {
+        return Err(ChromeError::General(format!(
+            "Download canceled: {} ({})",
+            download.filename, download.url
+        )));
+    }
+    Ok(DownloadResult { download })
+}
+
+/// Copies a finished download to `dest`. A directory destination keeps the suggested filename.
+pub async fn handle_downloads_save(
+    collector: &DownloadCollector,
+    id: &str,
+    dest: &Path,
+) -> Result<DownloadSaveResult> {
+    let download = collector
+        .get(id)
+        .await
+        .ok_or_else(|| ChromeError::General(format!("No download '{}'", id)))?;
+    if download.state != DownloadState::Completed {
+        return Err(ChromeError::General(format!(
+            "Download {} is {}",
+            download.filename, download.state
+        )));
+    }
+
+    let target = save_path(dest, &download.filename);
+    if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(&download.path, &target)?;
+
+    Ok(DownloadSaveResult {
+        guid: download.guid,
+        saved: target.display().to_string(),
+    })
+}
+
+fn save_path(dest: &Path, filename: &str) -> PathBuf {
+    if dest.is_dir() {
+        // Suggested names come from the page; never let them leave the directory
+        let name = Path::new(filename)
+            .file_name()
+            .map(|n| n.to_os_string())
+            .unwrap_or_else(|| "download".into());
+        dest.join(name)
+    } else {
+        dest.to_path_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_path() {
+        let dir = std::env::temp_dir();
+        assert_eq!(save_path(&dir, "report.csv"), dir.join("report.csv"));
+        assert_eq!(save_path(&dir, "../../etc/passwd"), dir.join("passwd"));
+        assert_eq!(
+            save_path(Path::new("/tmp/nope/out.csv"), "report.csv"),
+            PathBuf::from("/tmp/nope/out.csv")
+        );
+    }
+}
@@ -1,15 +1,18 @@
 use crate::{
     ChromeError, Result,
     chrome::{PageProvider, models::Viewport},
-    devices::{self, DeviceProfile},
+    devices::{self, DeviceProfile, EnvironmentOverrides},
     output,
     timeouts::{ms, secs},
 };
 use chromiumoxide::cdp::browser_protocol::emulation::{
-    SetDeviceMetricsOverrideParams, SetTouchEmulationEnabledParams, SetUserAgentOverrideParams,
+    ClearGeolocationOverrideParams, MediaFeature, SetDeviceMetricsOverrideParams,
+    SetEmulatedMediaParams, SetGeolocationOverrideParams, SetLocaleOverrideParams,
+    SetTimezoneOverrideParams, SetTouchEmulationEnabledParams, SetUserAgentOverrideParams,
 };
 use chromiumoxide::cdp::browser_protocol::page::ReloadParams;
-use serde::Serialize;
+use chromiumoxide::page::Page;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize)]
 pub struct EmulationResult {
@@ -17,6 +20,9 @@ pub struct EmulationResult {
     pub viewport: Viewport,
     pub user_agent: String,
     pub status: String,
+    /// Full environment in effect: `environment` with the device's overrides on top
+    #[serde(skip_serializing_if = "EnvironmentOverrides::is_empty")]
+    pub env: EnvironmentOverrides,
 }
 
 impl output::OutputFormatter for EmulationResult {
@@ -40,12 +46,15 @@ impl output::OutputFormatter for EmulationResult {
     }
 }
 
+/// `environment` is the session's current environment, which the device's own
+/// overrides are merged into
 pub async fn handle_emulate(
     provider: &impl PageProvider,
     device_name: &str,
+    environment: &EnvironmentOverrides,
 ) -> Result<EmulationResult> {
-    let device = devices::get_device_by_name(device_name)?;
-    apply_device_emulation(provider, &device).await
+    let device = devices::find_device(device_name)?;
+    apply_device_emulation(provider, &device, environment).await
 }
 
 pub async fn handle_viewport(
@@ -63,15 +72,17 @@ pub async fn handle_viewport(
         touch: false,
         mobile: false,
         landscape: width > height,
+        env: EnvironmentOverrides::default(),
     };
 
     custom_device.validate()?;
-    apply_device_emulation(provider, &custom_device).await
+    apply_device_emulation(provider, &custom_device, &EnvironmentOverrides::default()).await
 }
 
 async fn apply_device_emulation(
     provider: &impl PageProvider,
     device: &DeviceProfile,
+    environment: &EnvironmentOverrides,
 ) -> Result<EmulationResult> {
     let page = provider.get_or_create_page().await?;
 
@@ -105,6 +116,16 @@ async fn apply_device_emulation(
             .map_err(|e| ChromeError::General(format!("Failed to set touch emulation: {}", e)))?;
     }
 
+    // Media features are replaced as a set, so the device's overrides go out merged
+    // with the rest of the environment
+    let mut env = environment.clone();
+    env.merge(&device.env);
+    if !device.env.is_empty() {
+        for page in provider.all_pages().await? {
+            apply_environment(&page, &env).await?;
+        }
+    }
+
     let current_url = page.url().await.ok().flatten().unwrap_or_default();
     if !current_url.is_empty() && current_url != "about:blank" {
         let reload_params = ReloadParams::builder().ignore_cache(true).build();
@@ -160,5 +181,143 @@ async fn apply_device_emulation(
         },
         user_agent: device.user_agent.clone(),
         status: "applied".to_string(),
+        env,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnvironmentResult {
+    pub env: EnvironmentOverrides,
+}
+
+impl output::OutputFormatter for EnvironmentResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        if self.env.is_empty() {
+            return text::success("Environment: browser defaults");
+        }
+        let env = &self.env;
+        let mut lines = vec![text::success("Environment emulated")];
+        if let Some(geo) = env.geolocation {
+            lines.push(text::key_value(
+                "Geolocation",
+                &format!("{}, {} (±{}m)", geo.latitude, geo.longitude, geo.accuracy),
+            ));
+        }
+        for (label, value) in [
+            ("Timezone", &env.timezone),
+            ("Locale", &env.locale),
+            ("Color Scheme", &env.color_scheme),
+            ("Reduced Motion", &env.reduced_motion),
+            ("Forced Colors", &env.forced_colors),
+            ("Media", &env.media),
+        ] {
+            if let Some(value) = value {
+                lines.push(text::key_value(label, value));
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+/// Applies every override in `env` to one page. Media features are replaced as a
+/// set, so `env` should hold the full state rather than a delta.
+pub async fn apply_environment(page: &Page, env: &EnvironmentOverrides) -> Result<()> {
+    if let Some(geo) = env.geolocation {
+        let params = SetGeolocationOverrideParams::builder()
+            .latitude(geo.latitude)
+            .longitude(geo.longitude)
+            .accuracy(geo.accuracy)
+            .build();
+        page.execute(params)
+            .await
+            .map_err(|e| ChromeError::General(format!("Failed to set geolocation: {}", e)))?;
+    }
+
+    if let Some(ref timezone) = env.timezone {
+        page.execute(SetTimezoneOverrideParams::new(timezone.clone()))
+            .await
+            .map_err(|e| ChromeError::General(format!("Failed to set timezone: {}", e)))?;
+    }
+
+    if let Some(ref locale) = env.locale {
+        // Chrome refuses a second locale override until the first is cleared
+        page.execute(SetLocaleOverrideParams::default())
+            .await
+            .map_err(|e| ChromeError::General(format!("Failed to reset locale: {}", e)))?;
+        page.execute(
+            SetLocaleOverrideParams::builder()
+                .locale(locale.clone())
+                .build(),
+        )
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to set locale: {}", e)))?;
+    }
+
+    let features: Vec<MediaFeature> = [
+        ("prefers-color-scheme", &env.color_scheme),
+        ("prefers-reduced-motion", &env.reduced_motion),
+        ("forced-colors", &env.forced_colors),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| value.as_ref().map(|v| MediaFeature::new(name, v.clone())))
+    .collect();
+    if !features.is_empty() || env.media.is_some() {
+        let mut params = SetEmulatedMediaParams::builder().features(features);
+        if let Some(ref media) = env.media {
+            params = params.media(media.clone());
+        }
+        page.execute(params.build())
+            .await
+            .map_err(|e| ChromeError::General(format!("Failed to emulate media: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+async fn reset_environment(page: &Page) -> Result<()> {
+    page.execute(ClearGeolocationOverrideParams::default())
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to clear geolocation: {}", e)))?;
+    // An empty ID restores the host time zone
+    page.execute(SetTimezoneOverrideParams::new(""))
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to reset timezone: {}", e)))?;
+    page.execute(SetLocaleOverrideParams::default())
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to reset locale: {}", e)))?;
+    page.execute(
+        SetEmulatedMediaParams::builder()
+            .media("")
+            .features(Vec::<MediaFeature>::new())
+            .build(),
+    )
+    .await
+    .map_err(|e| ChromeError::General(format!("Failed to reset media: {}", e)))?;
+    Ok(())
+}
+
+/// Applies the session's full environment, `env`, to every open page
+pub async fn handle_env_set(
+    provider: &impl PageProvider,
+    env: EnvironmentOverrides,
+) -> Result<EnvironmentResult> {
+    env.validate()?;
+    for page in provider.all_pages().await? {
+        apply_environment(&page, &env).await?;
+    }
+    Ok(EnvironmentResult { env })
+}
+
+pub async fn handle_env_reset(provider: &impl PageProvider) -> Result<EnvironmentResult> {
+    for page in provider.all_pages().await? {
+        reset_environment(&page).await?;
+    }
+    Ok(EnvironmentResult {
+        env: EnvironmentOverrides::default(),
     })
 }
@@ -0,0 +1,161 @@
+use super::{ScriptGenerator, TargetInfo, css_selector, escape_string, url_to_pattern};
+use crate::locator::Locator;
+
+/// Cypress spec; Cypress retries and waits on its own, so no explicit load waits
+pub struct Cypress;
+
+impl ScriptGenerator for Cypress {
+    fn header(&self, test_name: &str) -> Vec<String> {
+        vec![
+            format!("describe('{test_name}', () => {{"),
+            format!("  it('{test_name}', () => {{"),
+        ]
+    }
+
+    fn footer(&self) -> Vec<String> {
+        vec!["  });".into(), "});".into()]
+    }
+
+    fn indent(&self) -> &'static str {
+        "    "
+    }
+
+    fn navigate(&self, url: &str) -> Vec<String> {
+        vec![
+            format!("cy.visit('{}');", escape_string(url)),
+            format!("cy.url().should('match', /{}/);", url_to_pattern(url)),
+        ]
+    }
+
+    fn click(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!(
+            "{}.should('be.visible').click();",
+            to_command(target)
+        )]
+    }
+
+    fn fill(&self, target: &TargetInfo, value: &str) -> Vec<String> {
+        let command = to_command(target);
+        if value.is_empty() {
+            return vec![format!("{command}.should('be.visible').clear();")];
+        }
+        vec![
+            format!(
+                "{command}.should('be.visible').clear().type('{}');",
+                escape_string(&escape_type_text(value))
+            ),
+            format!(
+                "{command}.should('have.value', '{}');",
+                escape_string(value)
+            ),
+        ]
+    }
+
+    fn upload(&self, target: &TargetInfo, files: &[String]) -> Vec<String> {
+        let files: Vec<String> = files
+            .iter()
+            .map(|f| format!("'{}'", escape_string(f)))
+            .collect();
+        vec![format!(
+            "cy.get('{}').selectFile([{}]);",
+            escape_string(&css_selector(target)),
+            files.join(", ")
+        )]
+    }
+
+    fn press(&self, key: &str) -> Vec<String> {
+        let sequence = match key {
+            "Enter" => "{enter}",
+            "Escape" => "{esc}",
+            "Backspace" => "{backspace}",
+            "Delete" => "{del}",
+            "ArrowUp" => "{uparrow}",
+            "ArrowDown" => "{downarrow}",
+            "ArrowLeft" => "{leftarrow}",
+            "ArrowRight" => "{rightarrow}",
+            "Home" => "{home}",
+            "End" => "{end}",
+            "PageUp" => "{pageup}",
+            "PageDown" => "{pagedown}",
+            key if key.chars().count() == 1 => {
+                return vec![format!(
+                    "cy.focused().type('{}');",
+                    escape_string(&escape_type_text(key))
+                )];
+            }
+            other => return vec![format!("// Unsupported key: {other}")],
+        };
+        vec![format!("cy.focused().type('{sequence}');")]
+    }
+
+    fn wait_for_load(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn scroll(&self, x: i32, y: i32) -> Vec<String> {
+        vec![format!("cy.window().then(win => win.scrollBy({x}, {y}));")]
+    }
+
+    fn select(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!("{}.click();", to_command(target))]
+    }
+
+    fn hover(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!("{}.trigger('mouseover');", to_command(target))]
+    }
+
+    fn screenshot(&self, filename: &str) -> Vec<String> {
+        let name = filename.rsplit_once('.').map_or(filename, |(stem, _)| stem);
+        vec![format!("cy.screenshot('{}');", escape_string(name))]
+    }
+
+    fn dialog(&self, accept: bool) -> Vec<String> {
+        vec![format!("cy.on('window:confirm', () => {accept});")]
+    }
+}
+
+/// `cy.type` treats `{` as the start of a special sequence
+fn escape_type_text(s: &str) -> String {
+    s.replace('{', "{{}")
+}
+
+fn to_command(target: &TargetInfo) -> String {
+    match Locator::from_target(target) {
+        Locator::Role {
+            name: Some(name), ..
+        } if target.css.is_none() => format!("cy.contains('{}')", escape_string(&name)),
+        Locator::Text(q) if target.css.is_none() => {
+            format!("cy.contains('{}')", escape_string(&q.value))
+        }
+        _ => format!("cy.get('{}')", escape_string(&css_selector(target))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::generate;
+    use super::super::tests::{make_target, sample_events};
+    use super::*;
+
+    #[test]
+    fn test_command() {
+        let role_only = make_target(&["button", "Submit"], None, None);
+        assert_eq!(to_command(&role_only), "cy.contains('Submit')");
+        let testid = make_target(&[], Some("go"), Some("#go"));
+        assert_eq!(to_command(&testid), "cy.get('[data-testid=\"go\"]')");
+        assert_eq!(escape_type_text("{a}"), "{{}a}");
+    }
+
+    #[test]
+    fn test_generate_script() {
+        let script = generate(&Cypress, &sample_events());
+        assert!(script.contains("    cy.visit('https://example.com');"));
+        assert!(
+            script.contains(
+                "cy.get('#email').should('be.visible').clear().type('test@example.com');"
+            )
+        );
+        assert!(script.contains("cy.focused().type('{enter}');"));
+        assert!(script.ends_with("  });\n});"));
+    }
+}
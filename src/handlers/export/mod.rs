@@ -0,0 +1,536 @@
+mod cypress;
+mod native;
+mod playwright;
+mod puppeteer;
+mod selenium;
+
+use crate::chrome::collectors::{ExtensionEvent, TargetInfo};
+use crate::chrome::event_store::EventMetadata;
+use crate::chrome::storage::SessionStorage;
+use crate::output::OutputFormatter;
+use crate::{ChromeError, Result};
+use serde::Serialize;
+use std::fs;
+use std::str::FromStr;
+
+pub use crate::chrome::collectors::extension::TargetInfo as ElementTarget;
+
+#[derive(Debug, Serialize)]
+pub struct ExportResult {
+    pub session_id: String,
+    pub recording_id: Option<String>,
+    pub format: String,
+    pub events_processed: usize,
+    pub output: Option<String>,
+    pub script: String,
+}
+
+impl OutputFormatter for ExportResult {
+    fn format_text(&self) -> String {
+        match &self.output {
+            Some(path) => format!(
+                "Exported {} events to {} ({})",
+                self.events_processed, path, self.format
+            ),
+            None => self.script.clone(),
+        }
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        let result = if pretty {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
+        };
+        result.map_err(|e| ChromeError::General(e.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Playwright,
+    PlaywrightPython,
+    Puppeteer,
+    SeleniumPython,
+    SeleniumJava,
+    Cypress,
+    Native,
+}
+
+impl ExportFormat {
+    pub const NAMES: &[&str] = &[
+        "playwright",
+        "playwright-python",
+        "puppeteer",
+        "selenium-python",
+        "selenium-java",
+        "cypress",
+        "native",
+    ];
+
+    fn generator(self) -> Box<dyn ScriptGenerator> {
+        match self {
+            Self::Playwright => Box::new(playwright::Playwright),
+            Self::PlaywrightPython => Box::new(playwright::PlaywrightPython),
+            Self::Puppeteer => Box::new(puppeteer::Puppeteer),
+            Self::SeleniumPython => Box::new(selenium::SeleniumPython),
+            Self::SeleniumJava => Box::new(selenium::SeleniumJava),
+            Self::Cypress => Box::new(cypress::Cypress),
+            Self::Native => Box::new(native::Native),
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = ChromeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "playwright" | "playwright-ts" => Ok(Self::Playwright),
+            "playwright-python" | "playwright-py" => Ok(Self::PlaywrightPython),
+            "puppeteer" => Ok(Self::Puppeteer),
+            "selenium-python" | "selenium-py" => Ok(Self::SeleniumPython),
+            "selenium-java" => Ok(Self::SeleniumJava),
+            "cypress" => Ok(Self::Cypress),
+            "native" | "cli" => Ok(Self::Native),
+            _ => Err(ChromeError::General(format!(
+                "Unsupported format: {}. Supported: {}",
+                s,
+                Self::NAMES.join(", ")
+            ))),
+        }
+    }
+}
+
+pub fn handle_export(
+    session_id: &str,
+    recording_id: Option<&str>,
+    format: &str,
+    output: Option<String>,
+) -> Result<ExportResult> {
+    let export_format: ExportFormat = format.parse()?;
+
+    let storage = SessionStorage::from_session_id(session_id)?;
+    let all_events: Vec<ExtensionEvent> = storage.read_all("extension")?;
+
+    let (events, rec_id) = match recording_id {
+        Some(rid) => (filter_by_recording(&all_events, rid), Some(rid.to_string())),
+        None => {
+            let rid = find_latest_recording_id(&all_events);
+            match &rid {
+                Some(r) => (filter_by_recording(&all_events, r), rid),
+                None => (all_events, None),
+            }
+        }
+    };
+
+    if events.is_empty() {
+        return Err(ChromeError::General("No events found for export".into()));
+    }
+
+    let script = generate(export_format.generator().as_ref(), &events);
+
+    if let Some(ref path) = output {
+        fs::write(path, &script)
+            .map_err(|e| ChromeError::General(format!("Failed to write file: {e}")))?;
+    }
+
+    Ok(ExportResult {
+        session_id: session_id.to_string(),
+        recording_id: rec_id,
+        format: format.to_string(),
+        events_processed: events.len(),
+        output,
+        script,
+    })
+}
+
+fn find_latest_recording_id(events: &[ExtensionEvent]) -> Option<String> {
+    events.iter().rev().find_map(|e| match e {
+        ExtensionEvent::RecordingStart(m) | ExtensionEvent::RecordingStop(m) => {
+            Some(m.recording_id.clone())
+        }
+        _ => None,
+    })
+}
+
+fn filter_by_recording(events: &[ExtensionEvent], recording_id: &str) -> Vec<ExtensionEvent> {
+    let (start_ts, end_ts) = events
+        .iter()
+        .fold((None, None), |(start, end), event| match event {
+            ExtensionEvent::RecordingStart(m) if m.recording_id == recording_id => {
+                (Some(m.ts), end)
+            }
+            ExtensionEvent::RecordingStop(m) if m.recording_id == recording_id => {
+                (start, Some(m.ts))
+            }
+            _ => (start, end),
+        });
+
+    let (start, end) = match (start_ts, end_ts) {
+        (Some(s), Some(e)) => (s, e),
+        (Some(s), None) => (s, u64::MAX),
+        _ => return Vec::new(),
+    };
+
+    events
+        .iter()
+        .filter(|e| e.timestamp_ms().is_some_and(|ts| ts >= start && ts <= end))
+        .cloned()
+        .collect()
+}
+
+/// Renders recorded steps in one target language. `generate` decides which
+/// steps to emit and where waits go; implementations only render them.
+trait ScriptGenerator {
+    /// Lines before the first step, opening the test body
+    fn header(&self, test_name: &str) -> Vec<String>;
+    fn footer(&self) -> Vec<String>;
+    /// Prefix for every step line
+    fn indent(&self) -> &'static str;
+
+    fn navigate(&self, url: &str) -> Vec<String>;
+    fn click(&self, target: &TargetInfo) -> Vec<String>;
+    fn fill(&self, target: &TargetInfo, value: &str) -> Vec<String>;
+    /// `files` are the names the browser reported, not full paths
+    fn upload(&self, target: &TargetInfo, files: &[String]) -> Vec<String>;
+    fn press(&self, key: &str) -> Vec<String>;
+    fn wait_for_load(&self) -> Vec<String>;
+    fn scroll(&self, x: i32, y: i32) -> Vec<String>;
+    fn select(&self, target: &TargetInfo) -> Vec<String>;
+    fn hover(&self, target: &TargetInfo) -> Vec<String>;
+    fn screenshot(&self, filename: &str) -> Vec<String>;
+    fn dialog(&self, accept: bool) -> Vec<String>;
+}
+
+fn generate(generator: &dyn ScriptGenerator, events: &[ExtensionEvent]) -> String {
+    let test_name = infer_test_name(events);
+    let mut lines = Vec::with_capacity(events.len() * 3);
+    lines.extend(generator.header(test_name));
+
+    let mut last_url: Option<&str> = None;
+    let merged = merge_events(events);
+    for (i, event) in merged.iter().enumerate() {
+        let next = merged.get(i + 1);
+        let (step, spaced) = match event {
+            ExtensionEvent::Navigate(data) => {
+                if last_url == Some(data.url.as_str()) {
+                    continue;
+                }
+                last_url = Some(&data.url);
+                (generator.navigate(&data.url), true)
+            }
+            ExtensionEvent::Click(target) => {
+                let mut step = generator.click(target);
+                if triggers_navigation(target, next) {
+                    step.extend(generator.wait_for_load());
+                }
+                (step, true)
+            }
+            ExtensionEvent::Input(data) => (
+                generator.fill(&data.target, data.value.as_deref().unwrap_or("")),
+                true,
+            ),
+            ExtensionEvent::Upload(data) => (generator.upload(&data.target, &data.files), true),
+            ExtensionEvent::KeyPress(data) => {
+                let mut step = generator.press(&data.key);
+                if data.key == "Enter" && matches!(next, Some(ExtensionEvent::Navigate(_))) {
+                    step.extend(generator.wait_for_load());
+                }
+                (step, true)
+            }
+            ExtensionEvent::Scroll(data) => (generator.scroll(data.x, data.y), false),
+            ExtensionEvent::Select(target) => (generator.select(target), false),
+            ExtensionEvent::Hover(target) => (generator.hover(target), false),
+            ExtensionEvent::Screenshot(data) => (generator.screenshot(&data.filename), false),
+            ExtensionEvent::Dialog(data) => (generator.dialog(data.ok), false),
+            ExtensionEvent::Snapshot(_)
+            | ExtensionEvent::RecordingStart(_)
+            | ExtensionEvent::RecordingStop(_) => continue,
+        };
+
+        lines.extend(
+            step.into_iter()
+                .map(|line| format!("{}{}", generator.indent(), line)),
+        );
+        if spaced {
+            lines.push(String::new());
+        }
+    }
+
+    lines.extend(generator.footer());
+    lines.join("\n")
+}
+
+fn merge_events(events: &[ExtensionEvent]) -> Vec<ExtensionEvent> {
+    let mut result = Vec::with_capacity(events.len());
+    let mut i = 0;
+
+    while i < events.len() {
+        let current = &events[i];
+        let next = events.get(i + 1);
+
+        // Skip recording markers
+        if matches!(
+            current,
+            ExtensionEvent::RecordingStart(_) | ExtensionEvent::RecordingStop(_)
+        ) {
+            i += 1;
+            continue;
+        }
+
+        // Skip duplicate clicks on same element within 500ms
+        if let ExtensionEvent::Click(target) = current
+            && let Some(ExtensionEvent::Click(next_target)) = next
+            && is_same_element(target, next_target)
+            && within_threshold(target.ts, next_target.ts, 500)
+        {
+            i += 1;
+            continue;
+        }
+
+        // Skip click if followed by input on same element (fill() auto-focuses)
+        if let ExtensionEvent::Click(click_target) = current
+            && let Some(ExtensionEvent::Input(input_data)) = next
+            && is_same_element(click_target, &input_data.target)
+        {
+            i += 1;
+            continue;
+        }
+
+        // Skip click that opened the file chooser for an upload on the same element
+        if let ExtensionEvent::Click(click_target) = current
+            && let Some(ExtensionEvent::Upload(upload)) = next
+            && is_same_element(click_target, &upload.target)
+        {
+            i += 1;
+            continue;
+        }
+
+        // Skip navigate after keypress Enter (form already submitted)
+        if let ExtensionEvent::KeyPress(kp) = current
+            && kp.key == "Enter"
+            && let Some(ExtensionEvent::Navigate(_)) = next
+        {
+            result.push(current.clone());
+            i += 2; // Skip both current and navigate
+            continue;
+        }
+
+        result.push(current.clone());
+        i += 1;
+    }
+
+    result
+}
+
+fn is_same_element(a: &TargetInfo, b: &TargetInfo) -> bool {
+    (a.css.is_some() && a.css == b.css) || (a.xpath.is_some() && a.xpath == b.xpath)
+}
+
+fn within_threshold(ts1: Option<u64>, ts2: Option<u64>, threshold_ms: u64) -> bool {
+    match (ts1, ts2) {
+        (Some(t1), Some(t2)) => t2.saturating_sub(t1) < threshold_ms,
+        _ => false,
+    }
+}
+
+fn infer_test_name(events: &[ExtensionEvent]) -> &'static str {
+    let has_login = events.iter().any(|e| match e {
+        ExtensionEvent::Navigate(d) => {
+            d.url.contains("login") || d.url.contains("signin") || d.url.contains("auth")
+        }
+        ExtensionEvent::Input(d) => d
+            .target
+            .aria
+            .iter()
+            .any(|a| a.to_lowercase().contains("password")),
+        _ => false,
+    });
+
+    let has_form = events.iter().any(|e| matches!(e, ExtensionEvent::Input(_)));
+    let has_search = events.iter().any(|e| match e {
+        ExtensionEvent::Navigate(d) => d.url.contains("search"),
+        _ => false,
+    });
+
+    if has_login {
+        "user authentication flow"
+    } else if has_search && has_form {
+        "search flow"
+    } else if has_form {
+        "form submission flow"
+    } else {
+        "recorded user flow"
+    }
+}
+
+fn triggers_navigation(target: &TargetInfo, next: Option<&ExtensionEvent>) -> bool {
+    let is_submit = target.aria.iter().any(|a| {
+        let lower = a.to_lowercase();
+        lower.contains("submit")
+            || lower.contains("login")
+            || lower.contains("sign")
+            || lower.contains("search")
+    });
+    is_submit || matches!(next, Some(ExtensionEvent::Navigate(_)))
+}
+
+/// CSS for targets without role or text queries: the test id, then the recorded selector
+fn css_selector(target: &TargetInfo) -> String {
+    match (&target.testid, &target.css) {
+        (Some(testid), _) => format!("[data-testid=\"{}\"]", testid.replace('"', "\\\"")),
+        (None, Some(css)) => css.clone(),
+        (None, None) => match target.aria.get(1) {
+            Some(name) if !name.is_empty() => {
+                format!("[aria-label=\"{}\"]", name.replace('"', "\\\""))
+            }
+            _ => "body".to_string(),
+        },
+    }
+}
+
+fn url_to_pattern(url: &str) -> String {
+    let Ok(parsed) = url::Url::parse(url) else {
+        return escape_regex(url);
+    };
+
+    let host = parsed.host_str().unwrap_or("");
+    let path = parsed.path();
+
+    if path.len() > 1 {
+        let segment = path.split('/').find(|s| !s.is_empty()).unwrap_or("");
+        if !segment.is_empty() {
+            return format!("{}.*{}", escape_regex(host), escape_regex(segment));
+        }
+    }
+
+    escape_regex(host)
+}
+
+fn escape_regex(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '.' | '*' | '+' | '?' | '^' | '$' | '{' | '}' | '[' | ']' | '|' | '(' | ')' | '\\'
+            | '/' => format!("\\{c}"),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+/// Body of a single-quoted JS string
+fn escape_string(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+/// Double-quoted literal, valid in Python, Java and YAML
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chrome::collectors::extension::{InputData, KeyPressData, NavigateData};
+
+    pub(super) fn make_target(
+        aria: &[&str],
+        testid: Option<&str>,
+        css: Option<&str>,
+    ) -> TargetInfo {
+        TargetInfo {
+            aria: aria.iter().map(|s| s.to_string()).collect(),
+            css: css.map(String::from),
+            xpath: None,
+            testid: testid.map(String::from),
+            text: None,
+            rect: None,
+            url: None,
+            ts: None,
+        }
+    }
+
+    pub(super) fn sample_events() -> Vec<ExtensionEvent> {
+        vec![
+            ExtensionEvent::Navigate(NavigateData {
+                url: "https://example.com".into(),
+                from: None,
+                nav_type: "link".into(),
+                ts: 0,
+            }),
+            ExtensionEvent::Click(make_target(&["button", "Submit"], None, None)),
+            ExtensionEvent::Input(InputData {
+                target: make_target(&["textbox", "Email"], None, Some("#email")),
+                value: Some("test@example.com".into()),
+            }),
+            ExtensionEvent::KeyPress(KeyPressData {
+                key: "Enter".into(),
+                aria: None,
+                css: None,
+                xpath: None,
+                testid: None,
+                url: None,
+                ts: Some(300),
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_url_pattern() {
+        assert_eq!(url_to_pattern("https://example.com"), "example\\.com");
+        assert_eq!(
+            url_to_pattern("https://google.com/search?q=test"),
+            "google\\.com.*search"
+        );
+        assert_eq!(
+            url_to_pattern("https://42dot.ai/careers"),
+            "42dot\\.ai.*careers"
+        );
+    }
+
+    #[test]
+    fn test_escape_string() {
+        assert_eq!(escape_string("hello"), "hello");
+        assert_eq!(escape_string("it's"), "it\\'s");
+        assert_eq!(escape_string("path\\to"), "path\\\\to");
+    }
+
+    #[test]
+    fn test_escape_regex() {
+        assert_eq!(escape_regex("test.com"), "test\\.com");
+        assert_eq!(escape_regex("a/b/c"), "a\\/b\\/c");
+    }
+
+    #[test]
+    fn test_css_selector_prefers_testid() {
+        let target = make_target(&["button", "Go"], Some("go"), Some("#go"));
+        assert_eq!(css_selector(&target), "[data-testid=\"go\"]");
+        let target = make_target(&["button", "Go"], None, Some("#go"));
+        assert_eq!(css_selector(&target), "#go");
+        let target = make_target(&["button", "Go"], None, None);
+        assert_eq!(css_selector(&target), "[aria-label=\"Go\"]");
+    }
+
+    #[test]
+    fn test_format_parse() {
+        for name in ExportFormat::NAMES {
+            assert!(name.parse::<ExportFormat>().is_ok(), "{name}");
+        }
+        assert_eq!(
+            "Selenium-Py".parse::<ExportFormat>().unwrap(),
+            ExportFormat::SeleniumPython
+        );
+        assert!("webdriverio".parse::<ExportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_every_format_generates() {
+        let events = sample_events();
+        for name in ExportFormat::NAMES {
+            let format: ExportFormat = name.parse().unwrap();
+            let script = generate(format.generator().as_ref(), &events);
+            assert!(script.contains("example.com"), "{name}: {script}");
+            assert!(script.contains("test@example.com"), "{name}: {script}");
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use super::{ScriptGenerator, TargetInfo, quote};
+use crate::locator::Locator;
+
+/// YAML script for this CLI's `run` command, addressing elements with locators
+pub struct Native;
+
+impl ScriptGenerator for Native {
+    fn header(&self, test_name: &str) -> Vec<String> {
+        vec![format!("name: {}", quote(test_name)), "steps:".into()]
+    }
+
+    fn footer(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn indent(&self) -> &'static str {
+        "  "
+    }
+
+    fn navigate(&self, url: &str) -> Vec<String> {
+        vec![format!("- navigate: {}", quote(url))]
+    }
+
+    fn click(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!(
+            "- click: {}",
+            quote(&Locator::from_target(target).to_string())
+        )]
+    }
+
+    fn fill(&self, target: &TargetInfo, value: &str) -> Vec<String> {
+        vec![format!(
+            "- fill: {{ selector: {}, text: {} }}",
+            quote(&Locator::from_target(target).to_string()),
+            quote(value)
+        )]
+    }
+
+    fn upload(&self, target: &TargetInfo, files: &[String]) -> Vec<String> {
+        let files: Vec<String> = files.iter().map(|f| quote(f)).collect();
+        vec![format!(
+            "- upload: {{ selector: {}, files: [{}] }}",
+            quote(&Locator::from_target(target).to_string()),
+            files.join(", ")
+        )]
+    }
+
+    fn press(&self, key: &str) -> Vec<String> {
+        vec![format!("- press: {}", quote(key))]
+    }
+
+    fn wait_for_load(&self) -> Vec<String> {
+        vec!["- wait: { condition: stable }".into()]
+    }
+
+    fn scroll(&self, x: i32, y: i32) -> Vec<String> {
+        vec![format!("- eval: \"window.scrollBy({x}, {y})\"")]
+    }
+
+    fn select(&self, target: &TargetInfo) -> Vec<String> {
+        self.click(target)
+    }
+
+    fn hover(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!(
+            "- hover: {}",
+            quote(&Locator::from_target(target).to_string())
+        )]
+    }
+
+    fn screenshot(&self, filename: &str) -> Vec<String> {
+        vec![format!("- screenshot: {}", quote(filename))]
+    }
+
+    fn dialog(&self, accept: bool) -> Vec<String> {
+        // Dialogs are answered by the session's dialog.behavior setting
+        if accept {
+            vec!["# dialog accepted while recording: set dialog.behavior = \"accept\"".into()]
+        } else {
+            vec!["# dialog dismissed while recording (default dialog.behavior)".into()]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::generate;
+    use super::super::tests::sample_events;
+    use super::*;
+    use crate::cli::runner::Script;
+
+    #[test]
+    fn test_generates_runnable_script() {
+        let yaml = generate(&Native, &sample_events());
+        let script: Script = serde_yaml_ng::from_str(&yaml).unwrap();
+        assert_eq!(script.name.as_deref(), Some("form submission flow"));
+        let steps: Vec<&str> = script.steps.iter().map(|s| s.action.name()).collect();
+        assert_eq!(steps, ["navigate", "click", "wait", "fill", "press"]);
+    }
+}
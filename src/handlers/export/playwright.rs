@@ -0,0 +1,313 @@
+use super::{ScriptGenerator, TargetInfo, escape_string, quote, url_to_pattern};
+use crate::locator::{Locator, TextQuery};
+
+/// `@playwright/test` in TypeScript
+pub struct Playwright;
+
+impl ScriptGenerator for Playwright {
+    fn header(&self, test_name: &str) -> Vec<String> {
+        vec![
+            "import { test, expect } from '@playwright/test';".into(),
+            String::new(),
+            format!("test('{test_name}', async ({{ page }}) => {{"),
+        ]
+    }
+
+    fn footer(&self) -> Vec<String> {
+        vec!["});".into()]
+    }
+
+    fn indent(&self) -> &'static str {
+        "  "
+    }
+
+    fn navigate(&self, url: &str) -> Vec<String> {
+        vec![
+            format!("await page.goto('{}');", escape_string(url)),
+            format!("await expect(page).toHaveURL(/{}/);", url_to_pattern(url)),
+        ]
+    }
+
+    fn click(&self, target: &TargetInfo) -> Vec<String> {
+        let locator = to_locator(target);
+        vec![
+            format!("await expect({locator}).toBeVisible();"),
+            format!("await {locator}.click();"),
+        ]
+    }
+
+    fn fill(&self, target: &TargetInfo, value: &str) -> Vec<String> {
+        let locator = to_locator(target);
+        let mut lines = vec![
+            format!("await expect({locator}).toBeVisible();"),
+            format!("await {locator}.fill('{}');", escape_string(value)),
+        ];
+        if !value.is_empty() {
+            lines.push(format!(
+                "await expect({locator}).toHaveValue('{}');",
+                escape_string(value)
+            ));
+        }
+        lines
+    }
+
+    fn upload(&self, target: &TargetInfo, files: &[String]) -> Vec<String> {
+        let files: Vec<String> = files
+            .iter()
+            .map(|f| format!("'{}'", escape_string(f)))
+            .collect();
+        vec![format!(
+            "await {}.setInputFiles([{}]);",
+            to_locator(target),
+            files.join(", ")
+        )]
+    }
+
+    fn press(&self, key: &str) -> Vec<String> {
+        vec![format!(
+            "await page.keyboard.press('{}');",
+            escape_string(key)
+        )]
+    }
+
+    fn wait_for_load(&self) -> Vec<String> {
+        vec!["await page.waitForLoadState('networkidle');".into()]
+    }
+
+    fn scroll(&self, x: i32, y: i32) -> Vec<String> {
+        vec![format!("await page.mouse.wheel({x}, {y});")]
+    }
+
+    fn select(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!("await {}.click();", to_locator(target))]
+    }
+
+    fn hover(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!("await {}.hover();", to_locator(target))]
+    }
+
+    fn screenshot(&self, filename: &str) -> Vec<String> {
+        vec![format!(
+            "await page.screenshot({{ path: '{}' }});",
+            escape_string(filename)
+        )]
+    }
+
+    fn dialog(&self, accept: bool) -> Vec<String> {
+        let action = if accept { "accept" } else { "dismiss" };
+        vec![format!("page.on('dialog', dialog => dialog.{action}());")]
+    }
+}
+
+/// Playwright's sync Python API, as a pytest test
+pub struct PlaywrightPython;
+
+impl ScriptGenerator for PlaywrightPython {
+    fn header(&self, test_name: &str) -> Vec<String> {
+        vec![
+            "import re".into(),
+            String::new(),
+            "from playwright.sync_api import Page, expect".into(),
+            String::new(),
+            String::new(),
+            format!(
+                "def test_{}(page: Page) -> None:",
+                test_name.replace(' ', "_")
+            ),
+        ]
+    }
+
+    fn footer(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn indent(&self) -> &'static str {
+        "    "
+    }
+
+    fn navigate(&self, url: &str) -> Vec<String> {
+        vec![
+            format!("page.goto({})", quote(url)),
+            format!(
+                "expect(page).to_have_url(re.compile(r\"{}\"))",
+                url_to_pattern(url)
+            ),
+        ]
+    }
+
+    fn click(&self, target: &TargetInfo) -> Vec<String> {
+        let locator = to_python_locator(target);
+        vec![
+            format!("expect({locator}).to_be_visible()"),
+            format!("{locator}.click()"),
+        ]
+    }
+
+    fn fill(&self, target: &TargetInfo, value: &str) -> Vec<String> {
+        let locator = to_python_locator(target);
+        let mut lines = vec![
+            format!("expect({locator}).to_be_visible()"),
+            format!("{locator}.fill({})", quote(value)),
+        ];
+        if !value.is_empty() {
+            lines.push(format!("expect({locator}).to_have_value({})", quote(value)));
+        }
+        lines
+    }
+
+    fn upload(&self, target: &TargetInfo, files: &[String]) -> Vec<String> {
+        let files: Vec<String> = files.iter().map(|f| quote(f)).collect();
+        vec![format!(
+            "{}.set_input_files([{}])",
+            to_python_locator(target),
+            files.join(", ")
+        )]
+    }
+
+    fn press(&self, key: &str) -> Vec<String> {
+        vec![format!("page.keyboard.press({})", quote(key))]
+    }
+
+    fn wait_for_load(&self) -> Vec<String> {
+        vec!["page.wait_for_load_state(\"networkidle\")".into()]
+    }
+
+    fn scroll(&self, x: i32, y: i32) -> Vec<String> {
+        vec![format!("page.mouse.wheel({x}, {y})")]
+    }
+
+    fn select(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!("{}.click()", to_python_locator(target))]
+    }
+
+    fn hover(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!("{}.hover()", to_python_locator(target))]
+    }
+
+    fn screenshot(&self, filename: &str) -> Vec<String> {
+        vec![format!("page.screenshot(path={})", quote(filename))]
+    }
+
+    fn dialog(&self, accept: bool) -> Vec<String> {
+        let action = if accept { "accept" } else { "dismiss" };
+        vec![format!(
+            "page.on(\"dialog\", lambda dialog: dialog.{action}())"
+        )]
+    }
+}
+
+fn to_locator(target: &TargetInfo) -> String {
+    let text_query = |method: &str, q: &TextQuery| {
+        let exact = if q.exact { ", { exact: true }" } else { "" };
+        format!("page.{method}('{}'{exact})", escape_string(&q.value))
+    };
+    match Locator::from_target(target) {
+        Locator::TestId(testid) => format!("page.getByTestId('{}')", escape_string(&testid)),
+        Locator::Role {
+            role,
+            name: Some(name),
+        } => format!(
+            "page.getByRole('{}', {{ name: '{}', exact: true }})",
+            escape_string(&role),
+            escape_string(&name)
+        ),
+        Locator::Role { role, name: None } => format!("page.getByRole('{}')", escape_string(&role)),
+        Locator::Text(q) => text_query("getByText", &q),
+        Locator::Label(q) => text_query("getByLabel", &q),
+        Locator::Placeholder(q) => text_query("getByPlaceholder", &q),
+        Locator::Css(css) => format!("page.locator('{}')", escape_string(&css)),
+        Locator::XPath(xpath) => format!("page.locator('xpath={}')", escape_string(&xpath)),
+    }
+}
+
+fn to_python_locator(target: &TargetInfo) -> String {
+    let text_query = |method: &str, q: &TextQuery| {
+        let exact = if q.exact { ", exact=True" } else { "" };
+        format!("page.{method}({}{exact})", quote(&q.value))
+    };
+    match Locator::from_target(target) {
+        Locator::TestId(testid) => format!("page.get_by_test_id({})", quote(&testid)),
+        Locator::Role {
+            role,
+            name: Some(name),
+        } => format!(
+            "page.get_by_role({}, name={}, exact=True)",
+            quote(&role),
+            quote(&name)
+        ),
+        Locator::Role { role, name: None } => format!("page.get_by_role({})", quote(&role)),
+        Locator::Text(q) => text_query("get_by_text", &q),
+        Locator::Label(q) => text_query("get_by_label", &q),
+        Locator::Placeholder(q) => text_query("get_by_placeholder", &q),
+        Locator::Css(css) => format!("page.locator({})", quote(&css)),
+        Locator::XPath(xpath) => format!("page.locator({})", quote(&format!("xpath={xpath}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::generate;
+    use super::super::tests::{make_target, sample_events};
+    use super::*;
+    use crate::chrome::collectors::extension::{ExtensionEvent, UploadData};
+
+    #[test]
+    fn test_locator_priority() {
+        let testid = make_target(&[], Some("submit"), None);
+        assert_eq!(to_locator(&testid), "page.getByTestId('submit')");
+
+        let aria = make_target(&["button", "Submit"], None, Some("#btn"));
+        assert_eq!(
+            to_locator(&aria),
+            "page.getByRole('button', { name: 'Submit', exact: true })"
+        );
+
+        let css = make_target(&[], None, Some("#submit-btn"));
+        assert_eq!(to_locator(&css), "page.locator('#submit-btn')");
+    }
+
+    #[test]
+    fn test_generate_script() {
+        let script = generate(&Playwright, &sample_events());
+        assert!(script.contains("import { test, expect }"));
+        assert!(script.contains("page.goto('https://example.com')"));
+        assert!(script.contains("getByRole('button', { name: 'Submit', exact: true }).click()"));
+        assert!(script.contains(
+            "getByRole('textbox', { name: 'Email', exact: true }).fill('test@example.com')"
+        ));
+        assert!(script.contains("page.keyboard.press('Enter')"));
+    }
+
+    #[test]
+    fn test_generate_upload() {
+        let target = make_target(&[], None, Some("#avatar"));
+        let events = vec![
+            ExtensionEvent::Click(target.clone()),
+            ExtensionEvent::Upload(UploadData {
+                target,
+                files: vec!["me.png".into(), "cv.pdf".into()],
+            }),
+        ];
+        let script = generate(&Playwright, &events);
+        assert!(
+            script.contains("await page.locator('#avatar').setInputFiles(['me.png', 'cv.pdf']);")
+        );
+        assert!(!script.contains(".click()"));
+
+        let script = generate(&PlaywrightPython, &events);
+        assert!(
+            script.contains("page.locator(\"#avatar\").set_input_files([\"me.png\", \"cv.pdf\"])")
+        );
+    }
+
+    #[test]
+    fn test_generate_python_script() {
+        let script = generate(&PlaywrightPython, &sample_events());
+        assert!(script.contains("def test_form_submission_flow(page: Page) -> None:"));
+        assert!(script.contains("    page.goto(\"https://example.com\")"));
+        assert!(
+            script.contains("page.get_by_role(\"button\", name=\"Submit\", exact=True).click()")
+        );
+        assert!(script.contains("page.keyboard.press(\"Enter\")"));
+    }
+}
@@ -0,0 +1,147 @@
+use super::{ScriptGenerator, TargetInfo, escape_string};
+use crate::locator::Locator;
+
+/// Puppeteer (v22+) ES module using the locator API
+pub struct Puppeteer;
+
+impl ScriptGenerator for Puppeteer {
+    fn header(&self, test_name: &str) -> Vec<String> {
+        vec![
+            "import puppeteer from 'puppeteer';".into(),
+            String::new(),
+            format!("// {test_name}"),
+            "const browser = await puppeteer.launch();".into(),
+            "const page = await browser.newPage();".into(),
+            String::new(),
+            "try {".into(),
+        ]
+    }
+
+    fn footer(&self) -> Vec<String> {
+        vec![
+            "} finally {".into(),
+            "  await browser.close();".into(),
+            "}".into(),
+        ]
+    }
+
+    fn indent(&self) -> &'static str {
+        "  "
+    }
+
+    fn navigate(&self, url: &str) -> Vec<String> {
+        vec![format!(
+            "await page.goto('{}', {{ waitUntil: 'networkidle0' }});",
+            escape_string(url)
+        )]
+    }
+
+    fn click(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!("await {}.click();", to_locator(target))]
+    }
+
+    fn fill(&self, target: &TargetInfo, value: &str) -> Vec<String> {
+        vec![format!(
+            "await {}.fill('{}');",
+            to_locator(target),
+            escape_string(value)
+        )]
+    }
+
+    fn upload(&self, target: &TargetInfo, files: &[String]) -> Vec<String> {
+        let files: Vec<String> = files
+            .iter()
+            .map(|f| format!("'{}'", escape_string(f)))
+            .collect();
+        vec![format!(
+            "await (await {}.waitHandle()).uploadFile({});",
+            to_locator(target),
+            files.join(", ")
+        )]
+    }
+
+    fn press(&self, key: &str) -> Vec<String> {
+        vec![format!(
+            "await page.keyboard.press('{}');",
+            escape_string(key)
+        )]
+    }
+
+    fn wait_for_load(&self) -> Vec<String> {
+        vec!["await page.waitForNetworkIdle();".into()]
+    }
+
+    fn scroll(&self, x: i32, y: i32) -> Vec<String> {
+        vec![format!(
+            "await page.mouse.wheel({{ deltaX: {x}, deltaY: {y} }});"
+        )]
+    }
+
+    fn select(&self, target: &TargetInfo) -> Vec<String> {
+        self.click(target)
+    }
+
+    fn hover(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!("await {}.hover();", to_locator(target))]
+    }
+
+    fn screenshot(&self, filename: &str) -> Vec<String> {
+        vec![format!(
+            "await page.screenshot({{ path: '{}' }});",
+            escape_string(filename)
+        )]
+    }
+
+    fn dialog(&self, accept: bool) -> Vec<String> {
+        let action = if accept { "accept" } else { "dismiss" };
+        vec![format!("page.on('dialog', dialog => dialog.{action}());")]
+    }
+}
+
+fn to_locator(target: &TargetInfo) -> String {
+    let attr = |s: &str| s.replace('"', "\\\"");
+    let selector = match Locator::from_target(target) {
+        Locator::Role {
+            role,
+            name: Some(name),
+        } => format!(
+            "::-p-aria([name=\"{}\"][role=\"{}\"])",
+            attr(&name),
+            attr(&role)
+        ),
+        Locator::Role { role, name: None } => format!("::-p-aria([role=\"{}\"])", attr(&role)),
+        Locator::Text(q) => format!("::-p-text({})", q.value),
+        Locator::Label(q) => format!("::-p-aria([name=\"{}\"])", attr(&q.value)),
+        Locator::Placeholder(q) => format!("[placeholder=\"{}\"]", attr(&q.value)),
+        Locator::XPath(xpath) => format!("::-p-xpath({xpath})"),
+        css => css.as_css().unwrap_or_default(),
+    };
+    format!("page.locator('{}')", escape_string(&selector))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::generate;
+    use super::super::tests::{make_target, sample_events};
+    use super::*;
+
+    #[test]
+    fn test_locator() {
+        let aria = make_target(&["button", "Submit"], None, Some("#btn"));
+        assert_eq!(
+            to_locator(&aria),
+            "page.locator('::-p-aria([name=\"Submit\"][role=\"button\"])')"
+        );
+        let testid = make_target(&[], Some("go"), None);
+        assert_eq!(to_locator(&testid), "page.locator('[data-testid=\"go\"]')");
+    }
+
+    #[test]
+    fn test_generate_script() {
+        let script = generate(&Puppeteer, &sample_events());
+        assert!(script.starts_with("import puppeteer from 'puppeteer';"));
+        assert!(script.contains("  await page.goto('https://example.com'"));
+        assert!(script.contains(".fill('test@example.com');"));
+        assert!(script.ends_with("  await browser.close();\n}"));
+    }
+}
@@ -0,0 +1,361 @@
+use super::{ScriptGenerator, TargetInfo, css_selector, quote};
+use crate::locator::Locator;
+
+/// Selenium's `By` strategies, shared by the Python and Java bindings
+enum By {
+    Css(String),
+    XPath(String),
+}
+
+impl By {
+    /// Role and text queries become XPath unless a CSS selector was recorded
+    fn new(target: &TargetInfo) -> Self {
+        match Locator::from_target(target) {
+            Locator::Role {
+                name: Some(name), ..
+            } if target.css.is_none() => Self::XPath(format!(
+                "//*[@aria-label={0} or normalize-space()={0}]",
+                xpath_literal(&name)
+            )),
+            Locator::Text(q) if target.css.is_none() => Self::XPath(format!(
+                "//*[normalize-space()={}]",
+                xpath_literal(&q.value)
+            )),
+            Locator::XPath(xpath) => Self::XPath(xpath),
+            _ => Self::Css(css_selector(target)),
+        }
+    }
+
+    fn python(&self) -> String {
+        match self {
+            Self::Css(css) => format!("(By.CSS_SELECTOR, {})", quote(css)),
+            Self::XPath(xpath) => format!("(By.XPATH, {})", quote(xpath)),
+        }
+    }
+
+    fn java(&self) -> String {
+        match self {
+            Self::Css(css) => format!("By.cssSelector({})", quote(css)),
+            Self::XPath(xpath) => format!("By.xpath({})", quote(xpath)),
+        }
+    }
+}
+
+fn xpath_literal(s: &str) -> String {
+    if !s.contains('"') {
+        format!("\"{s}\"")
+    } else if !s.contains('\'') {
+        format!("'{s}'")
+    } else {
+        let parts: Vec<String> = s.split('"').map(|p| format!("\"{p}\"")).collect();
+        format!("concat({})", parts.join(", '\"', "))
+    }
+}
+
+/// `Keys` constant for a DOM key name; `None` for printable characters
+fn special_key(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "Enter" => "ENTER",
+        "Tab" => "TAB",
+        "Escape" => "ESCAPE",
+        "Backspace" => "BACK_SPACE",
+        "Delete" => "DELETE",
+        "Space" | " " => "SPACE",
+        "ArrowUp" => "ARROW_UP",
+        "ArrowDown" => "ARROW_DOWN",
+        "ArrowLeft" => "ARROW_LEFT",
+        "ArrowRight" => "ARROW_RIGHT",
+        "Home" => "HOME",
+        "End" => "END",
+        "PageUp" => "PAGE_UP",
+        "PageDown" => "PAGE_DOWN",
+        _ => return None,
+    })
+}
+
+fn to_class_name(test_name: &str) -> String {
+    test_name
+        .split(' ')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Selenium WebDriver for Python, as a pytest test
+pub struct SeleniumPython;
+
+impl ScriptGenerator for SeleniumPython {
+    fn header(&self, test_name: &str) -> Vec<String> {
+        vec![
+            "from selenium import webdriver".into(),
+            "from selenium.webdriver.common.action_chains import ActionChains".into(),
+            "from selenium.webdriver.common.by import By".into(),
+            "from selenium.webdriver.common.keys import Keys".into(),
+            "from selenium.webdriver.support import expected_conditions as EC".into(),
+            "from selenium.webdriver.support.ui import WebDriverWait".into(),
+            String::new(),
+            String::new(),
+            format!("def test_{}():", test_name.replace(' ', "_")),
+            "    driver = webdriver.Chrome()".into(),
+            "    wait = WebDriverWait(driver, 10)".into(),
+            "    try:".into(),
+        ]
+    }
+
+    fn footer(&self) -> Vec<String> {
+        vec!["    finally:".into(), "        driver.quit()".into()]
+    }
+
+    fn indent(&self) -> &'static str {
+        "        "
+    }
+
+    fn navigate(&self, url: &str) -> Vec<String> {
+        vec![format!("driver.get({})", quote(url))]
+    }
+
+    fn click(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!(
+            "wait.until(EC.element_to_be_clickable({})).click()",
+            By::new(target).python()
+        )]
+    }
+
+    fn fill(&self, target: &TargetInfo, value: &str) -> Vec<String> {
+        vec![
+            format!(
+                "field = wait.until(EC.visibility_of_element_located({}))",
+                By::new(target).python()
+            ),
+            "field.clear()".into(),
+            format!("field.send_keys({})", quote(value)),
+        ]
+    }
+
+    fn upload(&self, target: &TargetInfo, files: &[String]) -> Vec<String> {
+        // send_keys takes absolute paths, one per line
+        vec![format!(
+            "wait.until(EC.presence_of_element_located({})).send_keys({})",
+            By::new(target).python(),
+            quote(&files.join("\n"))
+        )]
+    }
+
+    fn press(&self, key: &str) -> Vec<String> {
+        let keys = match special_key(key) {
+            Some(name) => format!("Keys.{name}"),
+            None => quote(key),
+        };
+        vec![format!("ActionChains(driver).send_keys({keys}).perform()")]
+    }
+
+    fn wait_for_load(&self) -> Vec<String> {
+        vec![
+            "wait.until(lambda d: d.execute_script(\"return document.readyState\") == \"complete\")"
+                .into(),
+        ]
+    }
+
+    fn scroll(&self, x: i32, y: i32) -> Vec<String> {
+        vec![format!(
+            "ActionChains(driver).scroll_by_amount({x}, {y}).perform()"
+        )]
+    }
+
+    fn select(&self, target: &TargetInfo) -> Vec<String> {
+        self.click(target)
+    }
+
+    fn hover(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!(
+            "ActionChains(driver).move_to_element(driver.find_element(*{})).perform()",
+            By::new(target).python()
+        )]
+    }
+
+    fn screenshot(&self, filename: &str) -> Vec<String> {
+        vec![format!("driver.save_screenshot({})", quote(filename))]
+    }
+
+    fn dialog(&self, accept: bool) -> Vec<String> {
+        let action = if accept { "accept" } else { "dismiss" };
+        vec![format!("wait.until(EC.alert_is_present()).{action}()")]
+    }
+}
+
+/// Selenium WebDriver for Java, as a JUnit 5 test class
+pub struct SeleniumJava;
+
+impl ScriptGenerator for SeleniumJava {
+    fn header(&self, test_name: &str) -> Vec<String> {
+        let class_name = to_class_name(test_name);
+        let mut method_name = class_name.clone();
+        if let Some(first) = method_name.get_mut(0..1) {
+            first.make_ascii_lowercase();
+        }
+        vec![
+            "import java.nio.file.Files;".into(),
+            "import java.nio.file.Path;".into(),
+            "import java.time.Duration;".into(),
+            "import org.junit.jupiter.api.AfterEach;".into(),
+            "import org.junit.jupiter.api.BeforeEach;".into(),
+            "import org.junit.jupiter.api.Test;".into(),
+            "import org.openqa.selenium.By;".into(),
+            "import org.openqa.selenium.Keys;".into(),
+            "import org.openqa.selenium.OutputType;".into(),
+            "import org.openqa.selenium.TakesScreenshot;".into(),
+            "import org.openqa.selenium.WebDriver;".into(),
+            "import org.openqa.selenium.WebElement;".into(),
+            "import org.openqa.selenium.chrome.ChromeDriver;".into(),
+            "import org.openqa.selenium.interactions.Actions;".into(),
+            "import org.openqa.selenium.support.ui.ExpectedConditions;".into(),
+            "import org.openqa.selenium.support.ui.WebDriverWait;".into(),
+            String::new(),
+            format!("class {class_name}Test {{"),
+            "    private WebDriver driver;".into(),
+            "    private WebDriverWait wait;".into(),
+            String::new(),
+            "    @BeforeEach".into(),
+            "    void setUp() {".into(),
+            "        driver = new ChromeDriver();".into(),
+            "        wait = new WebDriverWait(driver, Duration.ofSeconds(10));".into(),
+            "    }".into(),
+            String::new(),
+            "    @AfterEach".into(),
+            "    void tearDown() {".into(),
+            "        driver.quit();".into(),
+            "    }".into(),
+            String::new(),
+            "    @Test".into(),
+            format!("    void {method_name}() throws Exception {{"),
+            "        WebElement field;".into(),
+        ]
+    }
+
+    fn footer(&self) -> Vec<String> {
+        vec!["    }".into(), "}".into()]
+    }
+
+    fn indent(&self) -> &'static str {
+        "        "
+    }
+
+    fn navigate(&self, url: &str) -> Vec<String> {
+        vec![format!("driver.get({});", quote(url))]
+    }
+
+    fn click(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!(
+            "wait.until(ExpectedConditions.elementToBeClickable({})).click();",
+            By::new(target).java()
+        )]
+    }
+
+    fn fill(&self, target: &TargetInfo, value: &str) -> Vec<String> {
+        vec![
+            format!(
+                "field = wait.until(ExpectedConditions.visibilityOfElementLocated({}));",
+                By::new(target).java()
+            ),
+            "field.clear();".into(),
+            format!("field.sendKeys({});", quote(value)),
+        ]
+    }
+
+    fn upload(&self, target: &TargetInfo, files: &[String]) -> Vec<String> {
+        // sendKeys takes absolute paths, one per line
+        vec![format!(
+            "wait.until(ExpectedConditions.presenceOfElementLocated({})).sendKeys({});",
+            By::new(target).java(),
+            quote(&files.join("\n"))
+        )]
+    }
+
+    fn press(&self, key: &str) -> Vec<String> {
+        let keys = match special_key(key) {
+            Some(name) => format!("Keys.{name}"),
+            None => quote(key),
+        };
+        vec![format!("new Actions(driver).sendKeys({keys}).perform();")]
+    }
+
+    fn wait_for_load(&self) -> Vec<String> {
+        vec![
+            "wait.until(d -> \"complete\".equals(((org.openqa.selenium.JavascriptExecutor) d).executeScript(\"return document.readyState\")));"
+                .into(),
+        ]
+    }
+
+    fn scroll(&self, x: i32, y: i32) -> Vec<String> {
+        vec![format!(
+            "new Actions(driver).scrollByAmount({x}, {y}).perform();"
+        )]
+    }
+
+    fn select(&self, target: &TargetInfo) -> Vec<String> {
+        self.click(target)
+    }
+
+    fn hover(&self, target: &TargetInfo) -> Vec<String> {
+        vec![format!(
+            "new Actions(driver).moveToElement(driver.findElement({})).perform();",
+            By::new(target).java()
+        )]
+    }
+
+    fn screenshot(&self, filename: &str) -> Vec<String> {
+        vec![format!(
+            "Files.write(Path.of({}), ((TakesScreenshot) driver).getScreenshotAs(OutputType.BYTES));",
+            quote(filename)
+        )]
+    }
+
+    fn dialog(&self, accept: bool) -> Vec<String> {
+        let action = if accept { "accept" } else { "dismiss" };
+        vec![format!(
+            "wait.until(ExpectedConditions.alertIsPresent()).{action}();"
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::generate;
+    use super::super::tests::{make_target, sample_events};
+    use super::*;
+
+    #[test]
+    fn test_by_strategy() {
+        let role_only = make_target(&["button", "Submit"], None, None);
+        assert_eq!(
+            By::new(&role_only).python(),
+            r#"(By.XPATH, "//*[@aria-label=\"Submit\" or normalize-space()=\"Submit\"]")"#
+        );
+        let with_css = make_target(&["button", "Submit"], None, Some("#go"));
+        assert_eq!(By::new(&with_css).java(), "By.cssSelector(\"#go\")");
+        assert_eq!(xpath_literal("say \"hi\""), "'say \"hi\"'");
+        assert_eq!(
+            xpath_literal("it's \"x\""),
+            "concat(\"it's \", '\"', \"x\", '\"', \"\")"
+        );
+    }
+
+    #[test]
+    fn test_generate_scripts() {
+        let python = generate(&SeleniumPython, &sample_events());
+        assert!(python.contains("def test_form_submission_flow():"));
+        assert!(python.contains("        driver.get(\"https://example.com\")"));
+        assert!(python.contains("ActionChains(driver).send_keys(Keys.ENTER).perform()"));
+        assert!(python.ends_with("    finally:\n        driver.quit()"));
+
+        let java = generate(&SeleniumJava, &sample_events());
+        assert!(java.contains("class FormSubmissionFlowTest {"));
+        assert!(java.contains("    void formSubmissionFlow() throws Exception {"));
+        assert!(java.contains("field.sendKeys(\"test@example.com\");"));
+    }
+}
@@ -1,4 +1,10 @@
-use crate::{ChromeError, Result, chrome::PageProvider, output};
+use crate::{
+    ChromeError, Result,
+    chrome::{PageProvider, frames},
+    handlers::input::{ActionabilityOptions, wait_for_actionable},
+    output,
+    timeouts::ms,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize)]
@@ -30,23 +36,21 @@ pub async fn handle_scroll(
 ) -> Result<ScrollResult> {
     let page = provider.get_or_create_page().await?;
 
-    let escaped = crate::js_templates::escape_selector(selector);
+    let target = crate::js_templates::deep_query(selector);
     let script = format!(
         r#"(function(){{
-            const el = document.querySelector('{}');
+            const el = {};
             if (!el) return false;
             el.scrollIntoView({{ behavior: '{}', block: '{}' }});
             return true;
         }})()"#,
-        escaped, behavior, block
+        target, behavior, block
     );
 
-    let result = page
-        .evaluate(script)
-        .await
-        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?;
-
-    let scrolled: bool = result.into_value().unwrap_or(false);
+    let scrolled = frames::evaluate(&page, selector, script)
+        .await?
+        .as_bool()
+        .unwrap_or(false);
 
     Ok(ScrollResult {
         selector: selector.to_string(),
@@ -89,8 +93,15 @@ pub async fn handle_select(
     label: Option<&str>,
 ) -> Result<SelectResult> {
     let page = provider.get_or_create_page().await?;
-
-    let escaped = crate::js_templates::escape_selector(selector);
+    wait_for_actionable(
+        &page,
+        selector,
+        ms::SELECTOR_TIMEOUT,
+        ActionabilityOptions::for_select(),
+    )
+    .await?;
+
+    let target = crate::js_templates::deep_query(selector);
     let select_code = if let Some(val) = value {
         format!(
             "el.value = '{}';",
@@ -111,28 +122,24 @@ pub async fn handle_select(
 
     let script = format!(
         r#"(function(){{
-            const el = document.querySelector('{}');
+            const el = {};
             if (!el || el.tagName !== 'SELECT') return null;
             {};
             el.dispatchEvent(new Event('change', {{ bubbles: true }}));
             const opt = el.options[el.selectedIndex];
             return {{ value: el.value, text: opt?.text || null }};
         }})()"#,
-        escaped, select_code
+        target, select_code
     );
 
-    let result = page
-        .evaluate(script)
-        .await
-        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?;
-
     #[derive(Deserialize)]
     struct JsSelectResult {
         value: String,
         text: Option<String>,
     }
 
-    let js: Option<JsSelectResult> = result.into_value().unwrap_or(None);
+    let js: Option<JsSelectResult> =
+        serde_json::from_value(frames::evaluate(&page, selector, script).await?).unwrap_or(None);
 
     Ok(SelectResult {
         selector: selector.to_string(),
@@ -165,9 +172,9 @@ pub async fn handle_html(
 ) -> Result<HtmlResult> {
     let page = provider.get_or_create_page().await?;
 
-    let (escaped, is_doc) = selector
-        .map(|s| (crate::js_templates::escape_selector(s), false))
-        .unwrap_or_else(|| ("html".to_string(), true));
+    let (target, is_doc) = selector
+        .map(|s| (crate::js_templates::deep_query(s), false))
+        .unwrap_or_else(|| ("document.documentElement".to_string(), true));
 
     let prop = if inner { "innerHTML" } else { "outerHTML" };
 
@@ -176,19 +183,17 @@ pub async fn handle_html(
     } else {
         format!(
             r#"(function(){{
-                const el = document.querySelector('{}');
+                const el = {};
                 return el ? el.{} : null;
             }})()"#,
-            escaped, prop
+            target, prop
         )
     };
 
-    let result = page
-        .evaluate(script)
-        .await
-        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?;
-
-    let html: Option<String> = result.into_value().unwrap_or(None);
+    let html = frames::evaluate(&page, selector.unwrap_or_default(), script)
+        .await?
+        .as_str()
+        .map(str::to_string);
     let html = html.ok_or_else(|| {
         ChromeError::General(
             selector
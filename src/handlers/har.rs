@@ -0,0 +1,743 @@
+use crate::chrome::collectors::RequestTiming;
+use crate::chrome::{
+    HarReplay, NetworkRequest, ReplayFallback, ReplayResponse, ReplaySummary, RequestInterceptor,
+};
+use crate::output::{self, OutputFormatter};
+use crate::{ChromeError, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarLog {
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub creator: HarCreator,
+    #[serde(default)]
+    pub pages: Vec<serde_json::Value>,
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    pub started_date_time: String,
+    #[serde(default)]
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    #[serde(default)]
+    pub cache: serde_json::Value,
+    #[serde(default)]
+    pub timings: HarTimings,
+    #[serde(rename = "serverIPAddress", skip_serializing_if = "Option::is_none")]
+    pub server_ip_address: Option<String>,
+    #[serde(rename = "_resourceType", skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarHeader {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<HarHeader>,
+    #[serde(default)]
+    pub headers: Vec<HarHeader>,
+    #[serde(default)]
+    pub query_string: Vec<HarHeader>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub status_text: String,
+    #[serde(default)]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<HarHeader>,
+    #[serde(default)]
+    pub headers: Vec<HarHeader>,
+    #[serde(default)]
+    pub content: HarContent,
+    #[serde(rename = "redirectURL", default)]
+    pub redirect_url: String,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+    #[serde(rename = "_error", default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    #[serde(default)]
+    pub size: i64,
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// Phase durations in milliseconds; `-1` marks a phase that does not apply
+/// or was not measured.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarTimings {
+    #[serde(default = "not_measured")]
+    pub blocked: f64,
+    #[serde(default = "not_measured")]
+    pub dns: f64,
+    #[serde(default = "not_measured")]
+    pub connect: f64,
+    #[serde(default)]
+    pub send: f64,
+    #[serde(default)]
+    pub wait: f64,
+    #[serde(default)]
+    pub receive: f64,
+    #[serde(default = "not_measured")]
+    pub ssl: f64,
+}
+
+impl Default for HarTimings {
+    fn default() -> Self {
+        Self {
+            blocked: -1.0,
+            dns: -1.0,
+            connect: -1.0,
+            send: 0.0,
+            wait: 0.0,
+            receive: 0.0,
+            ssl: -1.0,
+        }
+    }
+}
+
+impl HarTimings {
+    /// HAR counts TLS inside `connect` and requires send/wait/receive, so
+    /// unknown required phases become 0 rather than -1.
+    fn from_timing(timing: &RequestTiming) -> Self {
+        Self {
+            blocked: -1.0,
+            dns: timing.dns_ms.unwrap_or(-1.0),
+            connect: timing.connect_ms.unwrap_or(-1.0),
+            send: timing.send_ms.unwrap_or(0.0),
+            wait: timing.ttfb_ms.unwrap_or(0.0),
+            receive: timing.download_ms.unwrap_or(0.0),
+            ssl: timing.tls_ms.unwrap_or(-1.0),
+        }
+    }
+}
+
+fn har_http_version(protocol: &str) -> String {
+    match protocol.to_ascii_lowercase().as_str() {
+        "h2" => "HTTP/2".to_string(),
+        "h3" | "h3-29" => "HTTP/3".to_string(),
+        "http/1.1" => "HTTP/1.1".to_string(),
+        "http/1.0" => "HTTP/1.0".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+fn not_measured() -> f64 {
+    -1.0
+}
+
+fn unknown_size() -> i64 {
+    -1
+}
+
+impl Har {
+    pub fn from_requests(requests: &[NetworkRequest]) -> Self {
+        Self {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator {
+                    name: env!("CARGO_PKG_NAME").to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                pages: Vec::new(),
+                entries: requests.iter().map(HarEntry::from_request).collect(),
+            },
+        }
+    }
+}
+
+impl HarEntry {
+    fn from_request(req: &NetworkRequest) -> Self {
+        let request_headers = to_har_headers(req.request_headers.as_ref());
+        let response_headers = to_har_headers(req.response_headers.as_ref());
+
+        let request_cookies = header_values(&request_headers, "cookie")
+            .flat_map(|v| v.split(';'))
+            .filter_map(parse_cookie_pair)
+            .collect();
+        let response_cookies = header_values(&response_headers, "set-cookie")
+            .filter_map(|v| v.split(';').next())
+            .filter_map(parse_cookie_pair)
+            .collect();
+
+        let redirect_url = header_values(&response_headers, "location")
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        let query_string = url::Url::parse(&req.url)
+            .map(|u| {
+                u.query_pairs()
+                    .map(|(k, v)| HarHeader {
+                        name: k.into_owned(),
+                        value: v.into_owned(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let post_data = req.request_body.as_ref().map(|text| HarPostData {
+            mime_type: header_values(&request_headers, "content-type")
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            text: text.clone(),
+        });
+        let request_body_size = req.request_body.as_ref().map_or(0, |b| b.len() as i64);
+
+        let body_size = req.response_size.unwrap_or(-1);
+        let content_size = req
+            .response_body_size
+            .or_else(|| req.response_body.as_ref().map(|b| b.len() as i64))
+            .unwrap_or(body_size.max(0));
+        // A cut body would be exported as if it were the whole response
+        let (content_text, content_comment) = if req.response_body_truncated {
+            (
+                None,
+                Some("Body omitted: larger than network_max_body_size".to_string()),
+            )
+        } else {
+            (req.response_body.clone(), None)
+        };
+
+        let http_version = req
+            .protocol
+            .as_deref()
+            .map(har_http_version)
+            .unwrap_or_default();
+        let timings = req
+            .timing
+            .as_ref()
+            .map(HarTimings::from_timing)
+            .unwrap_or_default();
+
+        Self {
+            started_date_time: req.timestamp.to_rfc3339(),
+            time: req.timing.as_ref().and_then(|t| t.total_ms).unwrap_or(0.0),
+            request: HarRequest {
+                method: req.method.clone(),
+                url: req.url.clone(),
+                http_version: http_version.clone(),
+                cookies: request_cookies,
+                headers: request_headers,
+                query_string,
+                post_data,
+                headers_size: -1,
+                body_size: request_body_size,
+            },
+            response: HarResponse {
+                status: req.status.unwrap_or(0),
+                status_text: req.status_text.clone().unwrap_or_default(),
+                http_version,
+                cookies: response_cookies,
+                headers: response_headers,
+                content: HarContent {
+                    size: content_size,
+                    mime_type: req.mime_type.clone().unwrap_or_default(),
+                    text: content_text,
+                    encoding: None,
+                    comment: content_comment,
+                },
+                redirect_url,
+                headers_size: -1,
+                body_size,
+                error: req.error_text.clone(),
+            },
+            cache: serde_json::json!({}),
+            timings,
+            server_ip_address: req.remote_ip.clone(),
+            resource_type: req.resource_type.clone(),
+        }
+    }
+}
+
+/// CDP reports headers as a JSON object; repeated headers (e.g. Set-Cookie)
+/// arrive newline-joined in a single value.
+fn to_har_headers(headers: Option<&serde_json::Value>) -> Vec<HarHeader> {
+    let Some(obj) = headers.and_then(|h| h.as_object()) else {
+        return Vec::new();
+    };
+
+    obj.iter()
+        .flat_map(|(name, value)| {
+            let value = value
+                .as_str()
+                .map(String::from)
+                .unwrap_or_else(|| value.to_string());
+            value
+                .split('\n')
+                .map(|v| HarHeader {
+                    name: name.clone(),
+                    value: v.to_string(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn header_values<'a>(headers: &'a [HarHeader], name: &'a str) -> impl Iterator<Item = &'a str> {
+    headers
+        .iter()
+        .filter(move |h| h.name.eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str())
+}
+
+fn parse_cookie_pair(pair: &str) -> Option<HarHeader> {
+    let (name, value) = pair.trim().split_once('=')?;
+    Some(HarHeader {
+        name: name.trim().to_string(),
+        value: value.trim().to_string(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct HarExportResult {
+    pub format: String,
+    pub entries: usize,
+    pub output: Option<String>,
+    #[serde(skip)]
+    pub har: Har,
+}
+
+impl OutputFormatter for HarExportResult {
+    fn format_text(&self) -> String {
+        match &self.output {
+            Some(path) => format!(
+                "Exported {} requests to {} ({})",
+                self.entries, path, self.format
+            ),
+            None => serde_json::to_string_pretty(&self.har).unwrap_or_default(),
+        }
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        if self.output.is_some() {
+            output::to_json(self, pretty)
+        } else {
+            output::to_json(&self.har, pretty)
+        }
+    }
+}
+
+pub fn handle_har_export(
+    requests: &[NetworkRequest],
+    output: Option<&str>,
+) -> Result<HarExportResult> {
+    let har = Har::from_requests(requests);
+
+    if let Some(path) = output {
+        let json = serde_json::to_string_pretty(&har)?;
+        fs::write(path, json)
+            .map_err(|e| ChromeError::General(format!("Failed to write file: {e}")))?;
+    }
+
+    Ok(HarExportResult {
+        format: "har".to_string(),
+        entries: har.log.entries.len(),
+        output: output.map(String::from),
+        har,
+    })
+}
+
+/// Headers that describe the original transfer rather than the decoded body
+/// we hand back to Chrome; replaying them would corrupt the response.
+const REPLAY_SKIP_HEADERS: &[&str] = &["content-encoding", "content-length", "transfer-encoding"];
+
+/// Suffix the collector appended to bodies cut at `network_max_body_size`
+const TRUNCATED_SUFFIX: &str = "... [truncated]";
+
+impl HarEntry {
+    /// Whether the recorded content is the whole response body. Bodies the collector
+    /// skipped (images, fonts) or cut are missing, except for responses without one.
+    fn has_full_body(&self) -> bool {
+        let content = &self.response.content;
+        match content.text {
+            Some(ref text) => content.comment.is_none() && !text.ends_with(TRUNCATED_SUFFIX),
+            None => {
+                content.size <= 0
+                    || matches!(self.response.status, 100..=199 | 204 | 300..=399)
+                    || self.request.method.eq_ignore_ascii_case("HEAD")
+            }
+        }
+    }
+
+    /// The recorded response, or `None` when its body was not fully recorded
+    fn to_replay_response(&self) -> Option<ReplayResponse> {
+        if !self.has_full_body() {
+            return None;
+        }
+        let headers = self
+            .response
+            .headers
+            .iter()
+            .filter(|h| {
+                !REPLAY_SKIP_HEADERS
+                    .iter()
+                    .any(|skip| h.name.eq_ignore_ascii_case(skip))
+            })
+            .map(|h| (h.name.clone(), h.value.clone()))
+            .collect();
+
+        let content = &self.response.content;
+        let body = content
+            .text
+            .as_ref()
+            .map(|text| match content.encoding.as_deref() {
+                Some("base64") => text.clone(),
+                _ => base64::engine::general_purpose::STANDARD.encode(text),
+            });
+
+        Some(ReplayResponse {
+            status: self.response.status,
+            headers,
+            body,
+        })
+    }
+}
+
+pub fn load_replay(path: &Path, fallback: ReplayFallback) -> Result<HarReplay> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        ChromeError::General(format!("Failed to read HAR {}: {}", path.display(), e))
+    })?;
+    let har: Har = serde_json::from_str(&content)
+        .map_err(|e| ChromeError::General(format!("Invalid HAR file: {}", e)))?;
+
+    // Entries without a status never completed, so there is nothing to replay
+    let entries = har
+        .log
+        .entries
+        .iter()
+        .filter(|e| e.response.status > 0)
+        .map(|e| {
+            (
+                e.request.method.clone(),
+                e.request.url.clone(),
+                e.to_replay_response(),
+            )
+        });
+
+    Ok(HarReplay::new(
+        path.display().to_string(),
+        fallback,
+        entries,
+    ))
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplayResult {
+    pub active: bool,
+    pub summary: Option<ReplaySummary>,
+}
+
+impl OutputFormatter for ReplayResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+
+        let Some(ref summary) = self.summary else {
+            return text::info("No HAR replay active");
+        };
+
+        let mut lines = vec![if self.active {
+            text::success(&format!("Replaying {}", summary.source))
+        } else {
+            text::success(&format!("Replay of {} finished", summary.source))
+        }];
+        lines.push(text::key_value("Entries", &summary.entries.to_string()));
+        lines.push(text::key_value(
+            "Fallback",
+            match summary.fallback {
+                ReplayFallback::Abort => "abort",
+                ReplayFallback::Live => "live",
+            },
+        ));
+        lines.push(text::key_value("Matched", &summary.matched.to_string()));
+        lines.push(text::key_value(
+            "Unmatched",
+            &summary.unmatched.len().to_string(),
+        ));
+        for req in &summary.unmatched {
+            lines.push(text::bullet(&format!(
+                "{} {} (x{})",
+                req.method, req.url, req.count
+            )));
+        }
+        if !summary.incomplete.is_empty() {
+            lines.push(text::key_value(
+                "Body not recorded",
+                &summary.incomplete.len().to_string(),
+            ));
+            for req in &summary.incomplete {
+                lines.push(text::bullet(&format!(
+                    "{} {} (x{})",
+                    req.method, req.url, req.count
+                )));
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+pub async fn handle_replay_start(
+    interceptor: &RequestInterceptor,
+    har_path: &Path,
+    fallback: ReplayFallback,
+) -> Result<ReplayResult> {
+    let replay = load_replay(har_path, fallback)?;
+    let summary = interceptor.start_replay(replay).await;
+    Ok(ReplayResult {
+        active: true,
+        summary: Some(summary),
+    })
+}
+
+pub async fn handle_replay_status(interceptor: &RequestInterceptor) -> Result<ReplayResult> {
+    let summary = interceptor.replay_summary().await;
+    Ok(ReplayResult {
+        active: summary.is_some(),
+        summary,
+    })
+}
+
+pub async fn handle_replay_stop(interceptor: &RequestInterceptor) -> Result<ReplayResult> {
+    Ok(ReplayResult {
+        active: false,
+        summary: interceptor.stop_replay().await,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn sample_request() -> NetworkRequest {
+        NetworkRequest {
+            id: "1".to_string(),
+            url: "https://example.com/api?q=rust&page=2".to_string(),
+            method: "GET".to_string(),
+            status: Some(200),
+            status_text: Some("OK".to_string()),
+            resource_type: Some("Fetch".to_string()),
+            mime_type: Some("application/json".to_string()),
+            request_headers: Some(serde_json::json!({"Cookie": "a=1; b=2"})),
+            request_body: None,
+            response_headers: Some(serde_json::json!({"Set-Cookie": "c=3; Path=/\nd=4"})),
+            response_body: Some("{\"ok\":true}".to_string()),
+            response_body_truncated: false,
+            response_body_size: Some(11),
+            response_size: Some(120),
+            timestamp: Utc::now(),
+            protocol: Some("h2".to_string()),
+            remote_ip: Some("93.184.216.34".to_string()),
+            from_cache: false,
+            redirect_chain: Vec::new(),
+            error_text: None,
+            timing: Some(RequestTiming {
+                dns_ms: Some(4.0),
+                connect_ms: Some(20.0),
+                tls_ms: Some(12.0),
+                send_ms: Some(0.5),
+                ttfb_ms: Some(80.0),
+                download_ms: Some(3.5),
+                total_ms: Some(110.0),
+            }),
+            in_flight: false,
+        }
+    }
+
+    #[test]
+    fn test_har_entry_fields() {
+        let har = Har::from_requests(&[sample_request()]);
+        assert_eq!(har.log.version, "1.2");
+
+        let entry = &har.log.entries[0];
+        assert_eq!(entry.request.query_string.len(), 2);
+        assert_eq!(entry.request.cookies.len(), 2);
+        assert_eq!(entry.response.cookies.len(), 2);
+        assert_eq!(entry.response.headers.len(), 2);
+        assert_eq!(entry.response.content.size, 11);
+        assert_eq!(entry.response.body_size, 120);
+        assert_eq!(entry.response.http_version, "HTTP/2");
+        assert_eq!(entry.timings.wait, 80.0);
+        assert_eq!(entry.time, 110.0);
+    }
+
+    #[test]
+    fn test_har_omits_truncated_body() {
+        let max = crate::config::FilterConfig::default().network_max_body_size;
+        let full = "x".repeat(max + 500);
+        let mut req = sample_request();
+        req.response_body = Some(format!("{}... [truncated]", &full[..max]));
+        req.response_body_truncated = true;
+        req.response_body_size = Some(full.len() as i64);
+
+        let content = HarEntry::from_request(&req).response.content;
+        assert_eq!(content.text, None);
+        assert_eq!(content.size, full.len() as i64);
+        assert!(content.comment.is_some());
+    }
+
+    #[test]
+    fn test_har_post_data() {
+        let mut req = sample_request();
+        req.method = "POST".to_string();
+        req.request_headers = Some(serde_json::json!({"Content-Type": "application/json"}));
+        req.request_body = Some("{\"name\":\"a\"}".to_string());
+
+        let entry = HarEntry::from_request(&req);
+        let post_data = entry.request.post_data.unwrap();
+        assert_eq!(post_data.mime_type, "application/json");
+        assert_eq!(post_data.text, "{\"name\":\"a\"}");
+        assert_eq!(entry.request.body_size, 12);
+    }
+
+    #[test]
+    fn test_har_serializes_spec_field_names() {
+        let har = Har::from_requests(&[sample_request()]);
+        let value = serde_json::to_value(&har).unwrap();
+        let entry = &value["log"]["entries"][0];
+        assert!(entry.get("startedDateTime").is_some());
+        assert!(entry["response"].get("redirectURL").is_some());
+        assert!(entry["request"].get("queryString").is_some());
+    }
+
+    #[test]
+    fn test_load_replay_skips_transfer_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.har");
+        let mut har = Har::from_requests(&[sample_request()]);
+        har.log.entries[0].response.headers.push(HarHeader {
+            name: "Content-Encoding".to_string(),
+            value: "gzip".to_string(),
+        });
+        fs::write(&path, serde_json::to_string(&har).unwrap()).unwrap();
+
+        let mut replay = load_replay(&path, ReplayFallback::Abort).unwrap();
+        let response = replay
+            .lookup("GET", "https://example.com/api?q=rust&page=2")
+            .unwrap();
+        assert_eq!(response.status, 200);
+        assert!(
+            !response
+                .headers
+                .iter()
+                .any(|(k, _)| k.eq_ignore_ascii_case("content-encoding"))
+        );
+        assert_eq!(response.body.as_deref(), Some("eyJvayI6dHJ1ZX0="));
+    }
+
+    #[test]
+    fn test_load_replay_leaves_incomplete_bodies_to_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.har");
+
+        let mut truncated = sample_request();
+        truncated.url = "https://example.com/big.json".to_string();
+        truncated.response_body = Some(format!("{{\"a\":1{}", TRUNCATED_SUFFIX));
+        truncated.response_body_truncated = true;
+        truncated.response_body_size = Some(50_000);
+        let mut image = sample_request();
+        image.url = "https://example.com/logo.png".to_string();
+        image.mime_type = Some("image/png".to_string());
+        image.response_body = None;
+        image.response_body_size = None;
+        let mut redirect = sample_request();
+        redirect.url = "https://example.com/old".to_string();
+        redirect.status = Some(301);
+        redirect.response_body = None;
+        redirect.response_body_size = None;
+
+        let har = Har::from_requests(&[truncated, image, redirect]);
+        fs::write(&path, serde_json::to_string(&har).unwrap()).unwrap();
+
+        let mut replay = load_replay(&path, ReplayFallback::Live).unwrap();
+        assert!(
+            replay
+                .lookup("GET", "https://example.com/big.json")
+                .is_none()
+        );
+        assert!(
+            replay
+                .lookup("GET", "https://example.com/logo.png")
+                .is_none()
+        );
+        assert_eq!(
+            replay
+                .lookup("GET", "https://example.com/old")
+                .unwrap()
+                .status,
+            301
+        );
+
+        let summary = replay.summary();
+        assert_eq!(summary.entries, 1);
+        assert_eq!(summary.incomplete.len(), 2);
+        assert!(summary.unmatched.is_empty());
+    }
+}
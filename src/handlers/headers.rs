@@ -0,0 +1,134 @@
+use crate::chrome::{Credentials, PageProvider, RequestInterceptor};
+use crate::handlers::permissions::normalize_origin;
+use crate::{ChromeError, Result, output};
+use chromiumoxide::cdp::browser_protocol::network::{Headers, SetExtraHttpHeadersParams};
+use chromiumoxide::page::Page;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Sends `headers` with every request from the page; an empty map removes them
+pub async fn apply_headers(page: &Page, headers: &BTreeMap<String, String>) -> Result<()> {
+    let headers = serde_json::to_value(headers)?;
+    page.execute(SetExtraHttpHeadersParams::new(Headers::new(headers)))
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to set extra headers: {}", e)))?;
+    Ok(())
+}
+
+/// RFC 9110 token: visible ASCII except separators
+fn is_valid_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?={}".contains(&b))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HeadersResult {
+    pub headers: BTreeMap<String, String>,
+}
+
+impl output::OutputFormatter for HeadersResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        if self.headers.is_empty() {
+            return text::success("Extra headers cleared");
+        }
+        let mut lines = vec![text::success(&format!(
+            "{} extra header(s) sent with every request",
+            self.headers.len()
+        ))];
+        for (name, value) in &self.headers {
+            lines.push(text::key_value(name, value));
+        }
+        lines.join("\n")
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+pub async fn handle_headers(
+    provider: &impl PageProvider,
+    headers: BTreeMap<String, String>,
+) -> Result<HeadersResult> {
+    if let Some(name) = headers.keys().find(|name| !is_valid_header_name(name)) {
+        return Err(ChromeError::General(format!(
+            "Invalid header name '{}'",
+            name
+        )));
+    }
+    for page in provider.all_pages().await? {
+        apply_headers(&page, &headers).await?;
+    }
+    Ok(HeadersResult { headers })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HttpAuthResult {
+    /// Registered credentials, without passwords
+    pub credentials: Vec<Credentials>,
+}
+
+impl output::OutputFormatter for HttpAuthResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        if self.credentials.is_empty() {
+            return text::success("HTTP authentication cleared");
+        }
+        let mut lines = vec![text::success("HTTP authentication answered automatically")];
+        for credentials in &self.credentials {
+            lines.push(text::key_value(
+                credentials.origin.as_deref().unwrap_or("All origins"),
+                &credentials.username,
+            ));
+        }
+        lines.join("\n")
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+pub async fn handle_http_auth(
+    interceptor: &RequestInterceptor,
+    username: &str,
+    password: &str,
+    origin: Option<&str>,
+) -> Result<HttpAuthResult> {
+    if username.is_empty() {
+        return Err(ChromeError::General("Username required".to_string()));
+    }
+    let credentials = Credentials {
+        username: username.to_string(),
+        password: password.to_string(),
+        origin: origin.map(normalize_origin).transpose()?,
+    };
+    interceptor.set_http_credentials(credentials).await;
+    Ok(HttpAuthResult {
+        credentials: interceptor.http_credentials().await,
+    })
+}
+
+pub async fn handle_http_auth_clear(interceptor: &RequestInterceptor) -> Result<HttpAuthResult> {
+    interceptor.clear_http_credentials().await;
+    Ok(HttpAuthResult {
+        credentials: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_names() {
+        assert!(is_valid_header_name("X-Staging-Bypass"));
+        assert!(is_valid_header_name("x_custom.1"));
+        assert!(!is_valid_header_name(""));
+        assert!(!is_valid_header_name("Bad Header"));
+        assert!(!is_valid_header_name("Bad:Header"));
+    }
+}
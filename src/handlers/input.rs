@@ -3,12 +3,18 @@ use crate::{
     chrome::{
         PageProvider,
         action_executor::{ActionConfig, ActionExecutor},
+        frames::{self, PageFrame},
     },
-    js_templates, output,
+    handlers::mouse,
+    js_templates,
+    locator::Locator,
+    output,
     timeouts::ms,
 };
-use chromiumoxide::{element::Element, page::Page};
-use serde::Serialize;
+use chromiumoxide::cdp::browser_protocol::input::MouseButton;
+use chromiumoxide::layout::Point;
+use chromiumoxide::page::Page;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -32,6 +38,117 @@ impl std::str::FromStr for InteractionMode {
     }
 }
 
+/// Turns a `--selector` value into CSS. Locators, `>>` chains and anything scoped to a
+/// `frame` are matched on the page, waiting up to the selector timeout, and the matches are
+/// stamped so the CSS stays exact. Plain CSS is returned as is.
+pub async fn resolve_selector(
+    provider: &impl PageProvider,
+    selector: &str,
+    frame: Option<&str>,
+) -> Result<String> {
+    let start = std::time::Instant::now();
+    loop {
+        if let Some(css) = match_selector(provider, selector, frame).await? {
+            return Ok(css);
+        }
+        if start.elapsed() >= Duration::from_millis(ms::SELECTOR_TIMEOUT) {
+            return Err(ChromeError::ElementNotFound {
+                selector: match frame {
+                    Some(frame) => format!("{} in frame '{}'", selector, frame),
+                    None => selector.to_string(),
+                },
+            });
+        }
+        tokio::time::sleep(Duration::from_millis(ms::POLL_INTERVAL)).await;
+    }
+}
+
+/// Single attempt of [`resolve_selector`] for callers that poll on their own, such as
+/// `wait` and `assert`: `None` when the locator matches nothing right now.
+pub async fn match_selector(
+    provider: &impl PageProvider,
+    selector: &str,
+    frame: Option<&str>,
+) -> Result<Option<String>> {
+    let chain = Locator::parse_chain(selector)?;
+    if frame.is_none()
+        && let [single] = chain.as_slice()
+        && let Some(css) = single.as_css()
+    {
+        return Ok(Some(css));
+    }
+
+    let page = provider.get_or_create_page().await?;
+    let browser = provider.browser().await;
+    let mut listed: Option<Vec<Arc<PageFrame>>> = None;
+    let mut scope = match frame {
+        Some(spec) => {
+            let all = frames::list(&page, browser.as_ref()).await?;
+            let found = frames::find(&all, spec)?;
+            listed = Some(all);
+            Some(found)
+        }
+        None => None,
+    };
+
+    // Each `>>` step that lands on an iframe continues in that frame's own document
+    let mut queries: Vec<serde_json::Value> = chain.iter().map(Locator::query).collect();
+    loop {
+        let script = js_templates::locate_element(&queries);
+        let outcome = match &scope {
+            Some(frame) => frame.evaluate(script).await?,
+            None => page
+                .evaluate(script)
+                .await
+                .map_err(|e| ChromeError::EvaluationError(e.to_string()))?
+                .into_value()
+                .unwrap_or_default(),
+        };
+
+        let serde_json::Value::Object(obj) = outcome else {
+            let serde_json::Value::String(stamp) = outcome else {
+                return Ok(None);
+            };
+            let css = format!("[{}=\"{}\"]", js_templates::LOCATOR_ATTRIBUTE, stamp);
+            frames::remember(&page, &css, scope);
+            return Ok(Some(css));
+        };
+        if let Some(stamp) = obj.get("frame").and_then(|v| v.as_str()) {
+            let owner = format!("[{}=\"{}\"]", js_templates::FRAME_ATTRIBUTE, stamp);
+            let parent = match scope {
+                Some(frame) => frame,
+                None => frames::main_frame(&page).await?,
+            };
+            let Some(object) = parent
+                .evaluate_handle(js_templates::deep_query(&owner))
+                .await?
+            else {
+                return Ok(None);
+            };
+            if listed.is_none() {
+                listed = Some(frames::list(&page, browser.as_ref()).await?);
+            }
+            let all = listed.as_deref().unwrap_or_default();
+            // A frame that has not committed its document yet matches nothing for now
+            let Some(child) = parent.child_owned_by(object, all).await? else {
+                return Ok(None);
+            };
+            let next = obj.get("next").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            queries.drain(..next.min(queries.len()));
+            scope = Some(child);
+            continue;
+        }
+        let reason = obj
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown");
+        return Err(ChromeError::General(format!(
+            "Cannot resolve '{}': {}",
+            selector, reason
+        )));
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct ClickResult {
     pub clicked: String,
@@ -48,6 +165,36 @@ impl output::OutputFormatter for ClickResult {
     }
 }
 
+/// How `click` presses an element: button, number of clicks, held modifiers (CDP bitmask) and
+/// an optional position relative to the element's top-left corner instead of its center
+#[derive(Debug, Clone)]
+pub struct ClickOptions {
+    pub button: MouseButton,
+    pub click_count: i64,
+    pub modifiers: i64,
+    pub position: Option<(f64, f64)>,
+}
+
+impl Default for ClickOptions {
+    fn default() -> Self {
+        Self {
+            button: MouseButton::Left,
+            click_count: 1,
+            modifiers: 0,
+            position: None,
+        }
+    }
+}
+
+impl ClickOptions {
+    fn is_plain(&self) -> bool {
+        self.button == MouseButton::Left
+            && self.click_count == 1
+            && self.modifiers == 0
+            && self.position.is_none()
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct FillResult {
     pub filled: String,
@@ -100,40 +247,36 @@ pub async fn handle_click(
     provider: &impl PageProvider,
     selector: &str,
     mode: InteractionMode,
+    options: ClickOptions,
 ) -> Result<ClickResult> {
     let page = provider.get_or_create_page().await?;
-
-    match mode {
-        InteractionMode::JavaScript => click_via_js(&page, selector).await,
-        InteractionMode::Cdp => click_via_cdp(&page, selector).await,
-        InteractionMode::Auto => {
-            let cdp_result = tokio::time::timeout(
-                Duration::from_millis(ms::CDP_ACTION),
-                click_via_cdp(&page, selector),
-            )
-            .await;
-
-            match cdp_result {
-                Ok(Ok(result)) => Ok(result),
-                Ok(Err(e)) if e.to_string().contains("No node") => {
-                    Err(ChromeError::ElementNotFound {
-                        selector: selector.to_string(),
-                    })
-                }
-                _ => {
-                    tracing::debug!("CDP click failed/timeout, falling back to JS");
-                    click_via_js(&page, selector).await
-                }
-            }
+    if mode == InteractionMode::JavaScript {
+        if !options.is_plain() {
+            return Err(ChromeError::General(
+                "--mode js only performs a plain left click".to_string(),
+            ));
         }
+        return click_via_js(&page, selector).await;
     }
-}
-
-async fn click_via_cdp(page: &Arc<Page>, selector: &str) -> Result<ClickResult> {
-    let executor = ActionExecutor::new(page.clone(), ActionConfig::default());
 
+    let point = wait_for_actionable(
+        &page,
+        selector,
+        ms::SELECTOR_TIMEOUT,
+        ActionabilityOptions::for_click().at(options.position),
+    )
+    .await?;
+    let executor = ActionExecutor::new(page.clone(), provider.action_config().await);
     executor
-        .execute(|| async { click_element(page, selector).await })
+        .execute(|| async {
+            match click_at(&page, point, &options).await {
+                Err(e) if mode == InteractionMode::Auto && options.is_plain() => {
+                    tracing::debug!("CDP click failed ({}), falling back to JS", e);
+                    click_via_js(&page, selector).await.map(|_| ())
+                }
+                result => result,
+            }
+        })
         .await?;
 
     Ok(ClickResult {
@@ -144,15 +287,10 @@ async fn click_via_cdp(page: &Arc<Page>, selector: &str) -> Result<ClickResult>
 async fn click_via_js(page: &Arc<Page>, selector: &str) -> Result<ClickResult> {
     let script = js_templates::click_element(selector);
 
-    let result = page
-        .evaluate(script)
+    let value = frames::evaluate(page, selector, script)
         .await
         .map_err(|e| ChromeError::General(format!("JS click failed: {}", e)))?;
 
-    let value: serde_json::Value = result
-        .into_value()
-        .map_err(|e| ChromeError::General(format!("Failed to parse result: {}", e)))?;
-
     let found = value
         .get("found")
         .and_then(|v| v.as_bool())
@@ -176,39 +314,28 @@ pub async fn handle_fill(
     mode: InteractionMode,
 ) -> Result<FillResult> {
     let page = provider.get_or_create_page().await?;
-
-    match mode {
-        InteractionMode::JavaScript => fill_via_js(&page, selector, text).await,
-        InteractionMode::Cdp => fill_via_cdp(&page, selector, text).await,
-        InteractionMode::Auto => {
-            let cdp_result = tokio::time::timeout(
-                Duration::from_millis(ms::CDP_ACTION),
-                fill_via_cdp(&page, selector, text),
-            )
-            .await;
-
-            match cdp_result {
-                Ok(Ok(result)) => Ok(result),
-                Ok(Err(e)) if e.to_string().contains("No node") => {
-                    Err(ChromeError::ElementNotFound {
-                        selector: selector.to_string(),
-                    })
-                }
-                _ => {
-                    tracing::debug!("CDP fill failed/timeout, falling back to JS");
-                    fill_via_js(&page, selector, text).await
-                }
-            }
-        }
+    if mode == InteractionMode::JavaScript {
+        return fill_via_js(&page, selector, text).await;
     }
-}
-
-async fn fill_via_cdp(page: &Arc<Page>, selector: &str, text: &str) -> Result<FillResult> {
-    let executor = ActionExecutor::new(page.clone(), ActionConfig::default());
-    let text_owned = text.to_string();
 
+    wait_for_actionable(
+        &page,
+        selector,
+        ms::SELECTOR_TIMEOUT,
+        ActionabilityOptions::for_fill(),
+    )
+    .await?;
+    let executor = ActionExecutor::new(page.clone(), provider.action_config().await);
     executor
-        .execute(|| async { fill_element(page, selector, &text_owned).await })
+        .execute(|| async {
+            match fill_element(&page, selector, text).await {
+                Err(e) if mode == InteractionMode::Auto => {
+                    tracing::debug!("CDP fill failed ({}), falling back to JS", e);
+                    fill_via_js(&page, selector, text).await.map(|_| ())
+                }
+                result => result,
+            }
+        })
         .await?;
 
     Ok(FillResult {
@@ -220,15 +347,10 @@ async fn fill_via_cdp(page: &Arc<Page>, selector: &str, text: &str) -> Result<Fi
 async fn fill_via_js(page: &Arc<Page>, selector: &str, text: &str) -> Result<FillResult> {
     let script = js_templates::fill_element(selector, text);
 
-    let result = page
-        .evaluate(script)
+    let value = frames::evaluate(page, selector, script)
         .await
         .map_err(|e| ChromeError::General(format!("JS fill failed: {}", e)))?;
 
-    let value: serde_json::Value = result
-        .into_value()
-        .map_err(|e| ChromeError::General(format!("Failed to parse result: {}", e)))?;
-
     let found = value
         .get("found")
         .and_then(|v| v.as_bool())
@@ -255,49 +377,33 @@ pub async fn handle_type(
 ) -> Result<TypeResult> {
     let page = provider.get_or_create_page().await?;
     let delay = delay_ms.unwrap_or(100);
-
-    match mode {
-        InteractionMode::JavaScript => type_via_js(&page, selector, text, delay).await,
-        InteractionMode::Cdp => type_via_cdp(&page, selector, text, delay).await,
-        InteractionMode::Auto => {
-            let cdp_result = tokio::time::timeout(
-                Duration::from_millis(ms::CDP_ACTION + delay * text.len() as u64),
-                type_via_cdp(&page, selector, text, delay),
-            )
-            .await;
-
-            match cdp_result {
-                Ok(Ok(result)) => Ok(result),
-                Ok(Err(e)) if e.to_string().contains("No node") => {
-                    Err(ChromeError::ElementNotFound {
-                        selector: selector.to_string(),
-                    })
-                }
-                _ => {
-                    tracing::debug!("CDP type failed/timeout, falling back to JS");
-                    type_via_js(&page, selector, text, delay).await
-                }
-            }
-        }
+    if mode == InteractionMode::JavaScript {
+        return type_via_js(&page, selector, text, delay).await;
     }
-}
 
-async fn type_via_cdp(
-    page: &Arc<Page>,
-    selector: &str,
-    text: &str,
-    delay: u64,
-) -> Result<TypeResult> {
+    wait_for_actionable(
+        &page,
+        selector,
+        ms::SELECTOR_TIMEOUT,
+        ActionabilityOptions::for_fill(),
+    )
+    .await?;
     let config = ActionConfig {
         wait_for_navigation: false,
         wait_for_stable_dom: false,
-        ..Default::default()
+        ..provider.action_config().await
     };
     let executor = ActionExecutor::new(page.clone(), config);
-    let text_owned = text.to_string();
-
     executor
-        .execute(|| async { type_element(page, selector, &text_owned, delay).await })
+        .execute(|| async {
+            match type_element(&page, selector, text, delay).await {
+                Err(e) if mode == InteractionMode::Auto => {
+                    tracing::debug!("CDP type failed ({}), falling back to JS", e);
+                    type_via_js(&page, selector, text, delay).await.map(|_| ())
+                }
+                result => result,
+            }
+        })
         .await?;
 
     Ok(TypeResult {
@@ -315,15 +421,10 @@ async fn type_via_js(
 ) -> Result<TypeResult> {
     let script = js_templates::type_element(selector, text, delay);
 
-    let result = page
-        .evaluate(script)
+    let value = frames::evaluate(page, selector, script)
         .await
         .map_err(|e| ChromeError::General(format!("JS type failed: {}", e)))?;
 
-    let value: serde_json::Value = result
-        .into_value()
-        .map_err(|e| ChromeError::General(format!("Failed to parse result: {}", e)))?;
-
     let found = value
         .get("found")
         .and_then(|v| v.as_bool())
@@ -342,241 +443,273 @@ async fn type_via_js(
     }
 }
 
+/// Checks an element must pass before input is dispatched to it. Attachment is always checked.
 #[derive(Debug, Clone, Copy)]
-struct ActionabilityOptions {
+pub(crate) struct ActionabilityOptions {
     check_visible: bool,
     check_stable: bool,
     check_enabled: bool,
+    check_editable: bool,
+    check_hit_target: bool,
     scroll_into_view: bool,
+    position: Option<(f64, f64)>,
 }
 
-impl Default for ActionabilityOptions {
-    fn default() -> Self {
+impl ActionabilityOptions {
+    pub(crate) fn for_click() -> Self {
         Self {
             check_visible: true,
             check_stable: true,
-            check_enabled: false,
+            check_enabled: true,
+            check_editable: false,
+            check_hit_target: true,
             scroll_into_view: true,
+            position: None,
         }
     }
-}
 
-impl ActionabilityOptions {
-    fn for_click() -> Self {
+    pub(crate) fn for_fill() -> Self {
         Self {
             check_visible: true,
-            check_stable: true,
+            check_stable: false,
             check_enabled: true,
+            check_editable: true,
+            check_hit_target: false,
+            scroll_into_view: true,
+            position: None,
+        }
+    }
+
+    pub(crate) fn for_hover() -> Self {
+        Self {
+            check_visible: true,
+            check_stable: true,
+            check_enabled: false,
+            check_editable: false,
+            check_hit_target: true,
             scroll_into_view: true,
+            position: None,
         }
     }
 
-    fn for_fill() -> Self {
+    pub(crate) fn for_select() -> Self {
         Self {
             check_visible: true,
             check_stable: false,
             check_enabled: true,
+            check_editable: false,
+            check_hit_target: false,
             scroll_into_view: true,
+            position: None,
         }
     }
 
-    fn for_hover() -> Self {
+    /// Drop targets only need to be visible and settled; the dragged element may cover them
+    pub(crate) fn for_drop() -> Self {
         Self {
             check_visible: true,
             check_stable: true,
             check_enabled: false,
+            check_editable: false,
+            check_hit_target: false,
             scroll_into_view: true,
+            position: None,
+        }
+    }
+
+    /// Measures the element where it is, so one outside the viewport fails the hit check
+    pub(crate) fn in_place(self) -> Self {
+        Self {
+            scroll_into_view: false,
+            ..self
         }
     }
+
+    /// Targets `position` from the element's top-left corner instead of its center
+    pub(crate) fn at(self, position: Option<(f64, f64)>) -> Self {
+        Self { position, ..self }
+    }
+
+    pub(crate) fn checks(&self) -> Vec<&'static str> {
+        [
+            (self.scroll_into_view, "scroll"),
+            (self.check_visible, "visible"),
+            (self.check_stable, "stable"),
+            (self.check_enabled, "enabled"),
+            (self.check_editable, "editable"),
+            (self.check_hit_target, "hit"),
+        ]
+        .into_iter()
+        .filter_map(|(on, name)| on.then_some(name))
+        .collect()
+    }
 }
 
-async fn wait_for_actionable(
+#[derive(Debug, Deserialize)]
+struct ActionabilityProbe {
+    ok: bool,
+    #[serde(default)]
+    check: String,
+    #[serde(default)]
+    reason: String,
+    #[serde(default)]
+    x: f64,
+    #[serde(default)]
+    y: f64,
+}
+
+/// Polls until `selector` passes every check in `opts`, returning the point to act on in
+/// viewport coordinates. On timeout the last failed check is reported, including the element that
+/// intercepts pointer events when the hit-target check fails.
+pub(crate) async fn wait_for_actionable(
     page: &Page,
     selector: &str,
     timeout_ms: u64,
     opts: ActionabilityOptions,
-) -> Result<Element> {
+) -> Result<Point> {
     let start = std::time::Instant::now();
     let timeout = Duration::from_millis(timeout_ms);
-    let escaped = selector.replace('\\', "\\\\").replace('\'', "\\'");
+    let script = js_templates::actionability(selector, &opts.checks(), opts.position);
+    let mut last_failure: Option<ActionabilityProbe> = None;
 
     loop {
-        if start.elapsed() >= timeout {
-            return Err(ChromeError::ElementNotFound {
-                selector: selector.to_string(),
-            });
-        }
-
-        let element = match page.find_element(selector).await {
-            Ok(el) => el,
-            Err(_) => {
-                tokio::time::sleep(Duration::from_millis(ms::POLL_INTERVAL)).await;
-                continue;
-            }
-        };
-
-        if opts.scroll_into_view {
-            let scroll_script = format!(
-                "document.querySelector('{}')?.scrollIntoView({{block:'center',behavior:'instant'}})",
-                escaped
-            );
-            let _ = page.evaluate(scroll_script).await;
-            tokio::time::sleep(Duration::from_millis(ms::VIEWPORT_SETTLE)).await;
-        }
-
-        if opts.check_visible {
-            let visible_script = format!(
-                r#"(function(){{
-                    const el=document.querySelector('{}');
-                    if(!el)return false;
-                    const style=window.getComputedStyle(el);
-                    const rect=el.getBoundingClientRect();
-                    return style.display!=='none' &&
-                           style.visibility!=='hidden' &&
-                           parseFloat(style.opacity||'1')>0 &&
-                           rect.width>0 && rect.height>0;
-                }})()"#,
-                escaped
-            );
-            let is_visible = page
-                .evaluate(visible_script)
-                .await
-                .ok()
-                .and_then(|r| r.into_value::<bool>().ok())
-                .unwrap_or(false);
-
-            if !is_visible {
-                tokio::time::sleep(Duration::from_millis(ms::POLL_INTERVAL)).await;
-                continue;
-            }
-        }
-
-        if opts.check_stable {
-            let pos_script = format!(
-                r#"(function(){{
-                    const el=document.querySelector('{}');
-                    if(!el)return null;
-                    const r=el.getBoundingClientRect();
-                    return {{x:r.x,y:r.y,w:r.width,h:r.height}};
-                }})()"#,
-                escaped
-            );
-
-            let pos1 = page.evaluate(pos_script.clone()).await.ok();
-            tokio::time::sleep(Duration::from_millis(ms::VIEWPORT_SETTLE)).await;
-            let pos2 = page.evaluate(pos_script).await.ok();
-
-            let is_stable = match (pos1, pos2) {
-                (Some(p1), Some(p2)) => {
-                    let v1: Option<serde_json::Value> = p1.into_value().ok();
-                    let v2: Option<serde_json::Value> = p2.into_value().ok();
-                    v1 == v2 && v1.is_some()
-                }
-                _ => false,
-            };
-
-            if !is_stable {
-                tokio::time::sleep(Duration::from_millis(ms::POLL_INTERVAL)).await;
-                continue;
+        let probe = frames::evaluate(page, selector, script.clone())
+            .await
+            .ok()
+            .and_then(|v| serde_json::from_value::<ActionabilityProbe>(v).ok());
+
+        match probe {
+            Some(p) if p.ok => {
+                let (dx, dy) = frames::offset(page, selector).await?;
+                return Ok(Point {
+                    x: p.x + dx,
+                    y: p.y + dy,
+                });
             }
+            Some(p) => last_failure = Some(p),
+            None => {}
         }
 
-        if opts.check_enabled {
-            let enabled_script = format!(
-                r#"(function(){{
-                    const el=document.querySelector('{}');
-                    if(!el)return false;
-                    return !el.disabled && !el.hasAttribute('readonly');
-                }})()"#,
-                escaped
-            );
-            let is_enabled = page
-                .evaluate(enabled_script)
-                .await
-                .ok()
-                .and_then(|r| r.into_value::<bool>().ok())
-                .unwrap_or(true);
-
-            if !is_enabled {
-                tokio::time::sleep(Duration::from_millis(ms::POLL_INTERVAL)).await;
-                continue;
-            }
+        if start.elapsed() >= timeout {
+            return Err(match last_failure {
+                Some(p) if p.check != "attached" => ChromeError::NotActionable {
+                    selector: selector.to_string(),
+                    check: p.check,
+                    reason: p.reason,
+                },
+                _ => ChromeError::ElementNotFound {
+                    selector: selector.to_string(),
+                },
+            });
         }
-
-        return Ok(element);
+        tokio::time::sleep(Duration::from_millis(ms::POLL_INTERVAL)).await;
     }
 }
 
-async fn click_element(page: &Page, selector: &str) -> Result<()> {
-    let element = wait_for_actionable(
+async fn click_at(page: &Page, point: Point, options: &ClickOptions) -> Result<()> {
+    use chromiumoxide::cdp::browser_protocol::input::DispatchMouseEventType;
+
+    let ClickOptions {
+        button, modifiers, ..
+    } = options;
+    mouse::dispatch(
         page,
-        selector,
-        ms::SELECTOR_TIMEOUT,
-        ActionabilityOptions::for_click(),
+        DispatchMouseEventType::MouseMoved,
+        point,
+        &MouseButton::None,
+        None,
+        0,
+        *modifiers,
     )
     .await?;
-    element
-        .click()
-        .await
-        .map_err(|e| ChromeError::General(format!("Click failed: {}", e)))?;
+    for count in 1..=options.click_count {
+        mouse::dispatch(
+            page,
+            DispatchMouseEventType::MousePressed,
+            point,
+            button,
+            Some(button),
+            count,
+            *modifiers,
+        )
+        .await?;
+        mouse::dispatch(
+            page,
+            DispatchMouseEventType::MouseReleased,
+            point,
+            button,
+            None,
+            count,
+            *modifiers,
+        )
+        .await?;
+    }
     Ok(())
 }
 
-async fn fill_element(page: &Page, selector: &str, text: &str) -> Result<()> {
-    let element = wait_for_actionable(
+async fn focus(page: &Page, selector: &str, select_all: bool) -> Result<()> {
+    let focused = frames::evaluate(
         page,
         selector,
-        ms::SELECTOR_TIMEOUT,
-        ActionabilityOptions::for_fill(),
+        js_templates::focus_element(selector, select_all),
     )
-    .await?;
-
-    element
-        .click()
-        .await
-        .map_err(|e| ChromeError::General(format!("Focus failed: {}", e)))?;
+    .await
+    .map_err(|e| ChromeError::General(format!("Focus failed: {}", e)))?
+    .as_bool()
+    .unwrap_or(false);
+    if !focused {
+        return Err(ChromeError::ElementNotFound {
+            selector: selector.to_string(),
+        });
+    }
+    Ok(())
+}
 
-    let escaped = selector.replace('\\', "\\\\").replace('\'', "\\'");
-    page.evaluate(format!("document.querySelector('{}').value = ''", escaped))
-        .await
-        .map_err(|e| ChromeError::General(format!("Clear failed: {}", e)))?;
+async fn fill_element(page: &Page, selector: &str, text: &str) -> Result<()> {
+    use chromiumoxide::cdp::browser_protocol::input::InsertTextParams;
+
+    focus(page, selector, true).await?;
+    if text.is_empty() {
+        let clear = format!(
+            "(function(){{const el={};el.value='';el.dispatchEvent(new Event('input',{{bubbles:true}}))}})()",
+            js_templates::deep_query(selector)
+        );
+        frames::evaluate(page, selector, clear)
+            .await
+            .map_err(|e| ChromeError::General(format!("Clear failed: {}", e)))?;
+        return Ok(());
+    }
 
-    element
-        .type_str(text)
+    page.execute(InsertTextParams::new(text))
         .await
-        .map_err(|e| ChromeError::General(format!("Type failed: {}", e)))?;
-
+        .map_err(|e| ChromeError::General(format!("Insert text failed: {}", e)))?;
     Ok(())
 }
 
 async fn type_element(page: &Page, selector: &str, text: &str, delay_ms: u64) -> Result<()> {
-    let element = wait_for_actionable(
-        page,
-        selector,
-        ms::SELECTOR_TIMEOUT,
-        ActionabilityOptions::for_fill(),
-    )
-    .await?;
-
-    element
-        .click()
-        .await
-        .map_err(|e| ChromeError::General(format!("Focus failed: {}", e)))?;
+    use chromiumoxide::cdp::browser_protocol::input::{
+        DispatchKeyEventParams, DispatchKeyEventType,
+    };
 
-    if delay_ms > 0 {
-        for ch in text.chars() {
-            element
-                .type_str(&ch.to_string())
+    focus(page, selector, false).await?;
+    for ch in text.chars() {
+        for event_type in [DispatchKeyEventType::KeyDown, DispatchKeyEventType::KeyUp] {
+            let mut params = DispatchKeyEventParams::builder()
+                .r#type(event_type.clone())
+                .key(ch.to_string());
+            if event_type == DispatchKeyEventType::KeyDown {
+                params = params.text(ch.to_string());
+            }
+            let params = params
+                .build()
+                .map_err(|e| ChromeError::General(format!("Failed to build key params: {}", e)))?;
+            page.execute(params)
                 .await
                 .map_err(|e| ChromeError::General(format!("Failed to type character: {}", e)))?;
-
-            tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
         }
-    } else {
-        element
-            .type_str(text)
-            .await
-            .map_err(|e| ChromeError::General(format!("Failed to type text: {}", e)))?;
+        if delay_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+        }
     }
 
     Ok(())
@@ -598,26 +731,10 @@ impl output::OutputFormatter for HoverResult {
     }
 }
 
-#[derive(Debug, Serialize)]
-pub struct PressResult {
-    pub pressed: String,
-}
-
-impl output::OutputFormatter for PressResult {
-    fn format_text(&self) -> String {
-        use crate::output::text;
-        text::success(&format!("Pressed: {}", self.pressed))
-    }
-
-    fn format_json(&self, pretty: bool) -> Result<String> {
-        output::to_json(self, pretty)
-    }
-}
-
 pub async fn handle_hover(provider: &impl PageProvider, selector: &str) -> Result<HoverResult> {
     let page = provider.get_or_create_page().await?;
 
-    let element = wait_for_actionable(
+    let point = wait_for_actionable(
         &page,
         selector,
         ms::SELECTOR_TIMEOUT,
@@ -625,8 +742,7 @@ pub async fn handle_hover(provider: &impl PageProvider, selector: &str) -> Resul
     )
     .await?;
 
-    element
-        .hover()
+    page.move_mouse(point)
         .await
         .map_err(|e| ChromeError::General(format!("Failed to hover element: {}", e)))?;
 
@@ -634,35 +750,3 @@ pub async fn handle_hover(provider: &impl PageProvider, selector: &str) -> Resul
         hovered: selector.to_string(),
     })
 }
-
-pub async fn handle_press(provider: &impl PageProvider, key: &str) -> Result<PressResult> {
-    let page = provider.get_or_create_page().await?;
-
-    use chromiumoxide::cdp::browser_protocol::input::{
-        DispatchKeyEventParams, DispatchKeyEventType,
-    };
-
-    let key_down = DispatchKeyEventParams::builder()
-        .r#type(DispatchKeyEventType::KeyDown)
-        .key(key.to_string())
-        .build()
-        .map_err(|e| ChromeError::General(format!("Failed to build key down params: {}", e)))?;
-
-    page.execute(key_down)
-        .await
-        .map_err(|e| ChromeError::General(format!("Failed to dispatch key down: {}", e)))?;
-
-    let key_up = DispatchKeyEventParams::builder()
-        .r#type(DispatchKeyEventType::KeyUp)
-        .key(key.to_string())
-        .build()
-        .map_err(|e| ChromeError::General(format!("Failed to build key up params: {}", e)))?;
-
-    page.execute(key_up)
-        .await
-        .map_err(|e| ChromeError::General(format!("Failed to dispatch key up: {}", e)))?;
-
-    Ok(PressResult {
-        pressed: key.to_string(),
-    })
-}
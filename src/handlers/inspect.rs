@@ -1,4 +1,8 @@
-use crate::{ChromeError, Result, chrome::PageProvider, output};
+use crate::{
+    ChromeError, Result,
+    chrome::{PageProvider, frames},
+    output,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -305,10 +309,10 @@ pub async fn handle_inspect(
 ) -> Result<InspectResult> {
     let page = provider.get_or_create_page().await?;
 
-    let escaped = crate::js_templates::escape_selector(selector);
+    let target = crate::js_templates::deep_query(selector);
     let script = format!(
         r#"(function(){{
-            const el = document.querySelector('{}');
+            const el = {};
             if (!el) return null;
             const rect = el.getBoundingClientRect();
             const style = window.getComputedStyle(el);
@@ -337,15 +341,12 @@ pub async fn handle_inspect(
                 children: children
             }};
         }})()"#,
-        escaped
+        target
     );
 
-    let result = page
-        .evaluate(script)
-        .await
-        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?;
+    let result = frames::evaluate(&page, selector, script).await?;
 
-    let js_result: Option<JsInspectResult> = result.into_value().unwrap_or(None);
+    let js_result: Option<JsInspectResult> = serde_json::from_value(result).unwrap_or(None);
 
     let js = js_result
         .ok_or_else(|| ChromeError::General(format!("Element not found: {}", selector)))?;
@@ -411,10 +412,10 @@ pub async fn handle_listeners(
 ) -> Result<ListenersResult> {
     let page = provider.get_or_create_page().await?;
 
-    let escaped = crate::js_templates::escape_selector(selector);
+    let target = crate::js_templates::deep_query(selector);
     let script = format!(
         r#"(function(){{
-            const el = document.querySelector('{}');
+            const el = {};
             if (!el) return null;
             const listeners = [];
             if (typeof getEventListeners === 'function') {{
@@ -433,15 +434,12 @@ pub async fn handle_listeners(
             }}
             return {{ listeners }};
         }})()"#,
-        escaped
+        target
     );
 
-    let result = page
-        .evaluate(script)
-        .await
-        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?;
+    let result = frames::evaluate(&page, selector, script).await?;
 
-    let js_result: Option<JsListenersResult> = result.into_value().unwrap_or(None);
+    let js_result: Option<JsListenersResult> = serde_json::from_value(result).unwrap_or(None);
 
     let js = js_result
         .ok_or_else(|| ChromeError::General(format!("Element not found: {}", selector)))?;
@@ -486,10 +484,10 @@ pub async fn handle_query(
     let page = provider.get_or_create_page().await?;
     let limit = limit.unwrap_or(20);
 
-    let escaped = crate::js_templates::escape_selector(selector);
+    let target = crate::js_templates::deep_query_all(selector);
     let script = format!(
         r#"(function(){{
-            const els = document.querySelectorAll('{}');
+            const els = {};
             const count = els.length;
             const elements = [];
             const limit = {};
@@ -505,16 +503,12 @@ pub async fn handle_query(
             }}
             return {{ count, elements }};
         }})()"#,
-        escaped, limit
+        target, limit
     );
 
-    let result = page
-        .evaluate(script)
-        .await
-        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?;
+    let result = frames::evaluate(&page, selector, script).await?;
 
-    let js_result: JsQueryResult = result
-        .into_value()
+    let js_result: JsQueryResult = serde_json::from_value(result)
         .map_err(|_| ChromeError::General("Failed to parse query result".to_string()))?;
 
     Ok(QueryResult {
@@ -557,7 +551,7 @@ pub async fn handle_dom(
 ) -> Result<DomResult> {
     let page = provider.get_or_create_page().await?;
 
-    let escaped = crate::js_templates::escape_selector(selector);
+    let target = crate::js_templates::deep_query(selector);
     let script = format!(
         r#"(function(){{
             function traverse(el, d, maxDepth) {{
@@ -580,18 +574,15 @@ pub async fn handle_dom(
                     children
                 }};
             }}
-            const el = document.querySelector('{}');
+            const el = {};
             return el ? traverse(el, 0, {}) : null;
         }})()"#,
-        escaped, depth
+        target, depth
     );
 
-    let result = page
-        .evaluate(script)
-        .await
-        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?;
+    let result = frames::evaluate(&page, selector, script).await?;
 
-    let js_node: Option<JsDomNode> = result.into_value().unwrap_or(None);
+    let js_node: Option<JsDomNode> = serde_json::from_value(result).unwrap_or(None);
 
     let node =
         js_node.ok_or_else(|| ChromeError::General(format!("Element not found: {}", selector)))?;
@@ -0,0 +1,324 @@
+use crate::{ChromeError, Result, chrome::PageProvider, handlers::mouse::modifier_bit, output};
+use chromiumoxide::cdp::browser_protocol::input::{DispatchKeyEventParams, DispatchKeyEventType};
+use chromiumoxide::keys::{KeyDefinition, USKEYBOARD_LAYOUT, get_key_definition};
+use chromiumoxide::page::Page;
+use serde::Serialize;
+
+/// Key name `ControlOrMeta` stands for on this platform
+fn control_or_meta() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "Meta"
+    } else {
+        "Control"
+    }
+}
+
+/// Looks up a key by its `key` value (`a`, `Enter`, `ArrowDown`) or `code` (`KeyA`, `Digit1`),
+/// accepting a few common aliases such as `Ctrl`, `Cmd`, `Esc` and `Space`
+pub fn resolve_key(name: &str) -> Result<&'static KeyDefinition> {
+    let canonical = match name.to_lowercase().as_str() {
+        "ctrl" | "control" => "Control",
+        "cmd" | "command" | "meta" => "Meta",
+        "option" | "alt" => "Alt",
+        "shift" => "Shift",
+        "controlormeta" => control_or_meta(),
+        "esc" => "Escape",
+        "space" => " ",
+        "return" => "Enter",
+        "del" => "Delete",
+        "up" => "ArrowUp",
+        "down" => "ArrowDown",
+        "left" => "ArrowLeft",
+        "right" => "ArrowRight",
+        _ => name,
+    };
+    get_key_definition(canonical)
+        .or_else(|| USKEYBOARD_LAYOUT.iter().find(|def| def.code == canonical))
+        .ok_or_else(|| ChromeError::General(format!("Unknown key: {}", name)))
+}
+
+/// A key pressed while holding modifiers, written `Control+Shift+K`
+#[derive(Debug)]
+pub struct Chord {
+    pub modifiers: Vec<&'static KeyDefinition>,
+    pub key: &'static KeyDefinition,
+}
+
+impl Chord {
+    pub fn parse(input: &str) -> Result<Self> {
+        // A trailing `+` is the plus key itself, as in `Control++`
+        let (head, last) = match input.strip_suffix("++") {
+            Some(head) => (head, "+"),
+            None => match input.rsplit_once('+') {
+                Some((head, last)) if !last.is_empty() => (head, last),
+                _ => ("", input),
+            },
+        };
+
+        let modifiers = head
+            .split('+')
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let def = resolve_key(part)?;
+                match modifier_bit(def.key) {
+                    Some(_) => Ok(def),
+                    None => Err(ChromeError::General(format!(
+                        "'{}' in '{}' is not a modifier (Alt, Control, Meta, Shift)",
+                        part, input
+                    ))),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            modifiers,
+            key: resolve_key(last)?,
+        })
+    }
+}
+
+const SHIFT: i64 = 8;
+
+/// Character a US keyboard types for `c` while Shift is held
+fn shifted(c: char) -> char {
+    match c {
+        '1' => '!',
+        '2' => '@',
+        '3' => '#',
+        '4' => '$',
+        '5' => '%',
+        '6' => '^',
+        '7' => '&',
+        '8' => '*',
+        '9' => '(',
+        '0' => ')',
+        '-' => '_',
+        '=' => '+',
+        '[' => '{',
+        ']' => '}',
+        '\\' => '|',
+        ';' => ':',
+        '\'' => '"',
+        ',' => '<',
+        '.' => '>',
+        '/' => '?',
+        '`' => '~',
+        c => c.to_ascii_uppercase(),
+    }
+}
+
+/// `key` value of an event: printable keys become their shifted character while Shift is held
+fn key_value(def: &KeyDefinition, modifiers: i64) -> String {
+    let mut chars = def.key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if modifiers & SHIFT != 0 => shifted(c).to_string(),
+        _ => def.key.to_string(),
+    }
+}
+
+/// Text a key inserts: none for named keys or when a shortcut modifier other than Shift is held
+fn key_text(def: &KeyDefinition, modifiers: i64) -> Option<String> {
+    if modifiers & !SHIFT != 0 {
+        return None;
+    }
+    let text = def
+        .text
+        .map(String::from)
+        .or_else(|| (def.key.chars().count() == 1).then(|| def.key.to_string()))?;
+    Some(if modifiers & SHIFT != 0 {
+        text.chars().map(shifted).collect()
+    } else {
+        text
+    })
+}
+
+/// Dispatches a keydown (`down`) or keyup with the given modifier bitmask in effect
+async fn key_event(page: &Page, def: &KeyDefinition, down: bool, modifiers: i64) -> Result<()> {
+    let text = if down { key_text(def, modifiers) } else { None };
+    let event_type = match (down, &text) {
+        (false, _) => DispatchKeyEventType::KeyUp,
+        (true, Some(_)) => DispatchKeyEventType::KeyDown,
+        (true, None) => DispatchKeyEventType::RawKeyDown,
+    };
+    let mut params = DispatchKeyEventParams::builder()
+        .r#type(event_type)
+        .key(key_value(def, modifiers))
+        .code(def.code)
+        .windows_virtual_key_code(def.key_code)
+        .native_virtual_key_code(def.key_code)
+        .modifiers(modifiers);
+    if let Some(text) = text {
+        params = params.text(text.clone()).unmodified_text(text);
+    }
+    let params = params
+        .build()
+        .map_err(|e| ChromeError::General(format!("Failed to build key event: {}", e)))?;
+
+    page.execute(params).await.map_err(|e| {
+        ChromeError::General(format!(
+            "Failed to dispatch key {}: {}",
+            if down { "down" } else { "up" },
+            e
+        ))
+    })?;
+    Ok(())
+}
+
+/// Presses a chord: modifiers go down in order, the key is pressed, modifiers come up in reverse.
+/// `held` are modifiers already down from an earlier `keydown`.
+async fn press_chord(page: &Page, chord: &Chord, held: i64) -> Result<()> {
+    let mut mask = held;
+    for def in &chord.modifiers {
+        mask |= modifier_bit(def.key).unwrap_or(0);
+        key_event(page, def, true, mask).await?;
+    }
+    key_event(page, chord.key, true, mask).await?;
+    key_event(page, chord.key, false, mask).await?;
+    for def in chord.modifiers.iter().rev() {
+        mask &= !modifier_bit(def.key).unwrap_or(0);
+        key_event(page, def, false, mask | held).await?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct PressResult {
+    pub pressed: String,
+}
+
+impl output::OutputFormatter for PressResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        text::success(&format!("Pressed: {}", self.pressed))
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+/// Presses each chord in `keys` in turn, e.g. `["Tab", "Tab", "Control+Enter"]`
+pub async fn handle_press(
+    provider: &impl PageProvider,
+    keys: &[String],
+    held: i64,
+) -> Result<PressResult> {
+    if keys.is_empty() {
+        return Err(ChromeError::General("No key to press".to_string()));
+    }
+    let chords = keys
+        .iter()
+        .map(|k| Chord::parse(k))
+        .collect::<Result<Vec<_>>>()?;
+
+    let page = provider.get_or_create_page().await?;
+    for chord in &chords {
+        press_chord(&page, chord, held).await?;
+    }
+
+    Ok(PressResult {
+        pressed: keys.join(" "),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyHoldResult {
+    pub key: String,
+    pub action: String,
+    /// CDP bitmask of modifiers held after this event (Alt=1, Control=2, Meta=4, Shift=8)
+    pub modifiers: i64,
+}
+
+impl output::OutputFormatter for KeyHoldResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        text::success(&format!("Key {}: {}", self.action, self.key))
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+/// Holds (`down`) or releases a single key. Modifiers stay in effect for later presses and
+/// clicks through the returned mask.
+pub async fn handle_key_hold(
+    provider: &impl PageProvider,
+    key: &str,
+    down: bool,
+    held: i64,
+) -> Result<KeyHoldResult> {
+    let def = resolve_key(key)?;
+    let bit = modifier_bit(def.key).unwrap_or(0);
+    let modifiers = if down { held | bit } else { held & !bit };
+
+    let page = provider.get_or_create_page().await?;
+    key_event(&page, def, down, modifiers).await?;
+
+    Ok(KeyHoldResult {
+        key: def.key.to_string(),
+        action: if down { "down" } else { "up" }.to_string(),
+        modifiers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_key() {
+        assert_eq!(resolve_key("Enter").unwrap().code, "Enter");
+        assert_eq!(resolve_key("KeyA").unwrap().key, "a");
+        assert_eq!(resolve_key("ctrl").unwrap().key, "Control");
+        assert_eq!(resolve_key("Space").unwrap().key_code, 32);
+        assert_eq!(resolve_key("ControlOrMeta").unwrap().key, control_or_meta());
+        assert!(resolve_key("Hyper").is_err());
+    }
+
+    fn modifier_keys(chord: &Chord) -> Vec<&str> {
+        chord.modifiers.iter().map(|def| def.key).collect()
+    }
+
+    #[test]
+    fn test_parse_chord() {
+        let chord = Chord::parse("Control+Shift+K").unwrap();
+        assert_eq!(chord.key.key, "K");
+        assert_eq!(modifier_keys(&chord), ["Control", "Shift"]);
+
+        let plus = Chord::parse("Control++").unwrap();
+        assert_eq!(plus.key.key, "+");
+        assert_eq!(modifier_keys(&plus), ["Control"]);
+
+        assert_eq!(Chord::parse("+").unwrap().key.key, "+");
+        assert!(Chord::parse("A+B").is_err());
+    }
+
+    #[test]
+    fn test_key_text() {
+        let a = resolve_key("a").unwrap();
+        assert_eq!(key_text(a, 0).as_deref(), Some("a"));
+        assert_eq!(key_text(a, 8).as_deref(), Some("A"));
+        assert_eq!(key_text(a, 2), None);
+        assert_eq!(
+            key_text(resolve_key("Enter").unwrap(), 0).as_deref(),
+            Some("\r")
+        );
+        assert_eq!(key_text(resolve_key("ArrowDown").unwrap(), 0), None);
+    }
+
+    #[test]
+    fn test_shifted_keys() {
+        let one = resolve_key("Digit1").unwrap();
+        assert_eq!(key_value(one, 8), "!");
+        assert_eq!(key_text(one, 8).as_deref(), Some("!"));
+        assert_eq!(key_value(one, 0), "1");
+
+        let quote = resolve_key("Quote").unwrap();
+        assert_eq!(key_text(quote, 8).as_deref(), Some("\""));
+        assert_eq!(key_value(resolve_key("Enter").unwrap(), 8), "Enter");
+        // Shortcuts still report the shifted key, without inserting text
+        assert_eq!(key_value(one, 2 | 8), "!");
+        assert_eq!(key_text(one, 2 | 8), None);
+    }
+}
@@ -1,19 +1,31 @@
 pub mod a11y;
+pub mod assert;
 pub mod auth;
 pub mod config_handler;
 pub mod console;
 pub mod describe;
 pub mod dialog;
+pub mod downloads;
 pub mod emulation;
 pub mod export;
 pub mod extras;
+pub mod har;
+pub mod headers;
 pub mod input;
 pub mod inspect;
+pub mod keyboard;
 pub mod label;
+pub mod mouse;
 pub mod navigation;
 pub mod network;
 pub mod performance;
+pub mod permissions;
+pub mod route;
 pub mod screenshot;
 pub mod script;
 pub mod session;
 pub mod sessions;
+pub mod throttle;
+pub mod touch;
+pub mod upload;
+pub mod visual;
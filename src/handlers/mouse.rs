@@ -0,0 +1,364 @@
+use crate::{
+    ChromeError, Result,
+    chrome::{PageProvider, action_executor::ActionExecutor},
+    handlers::input::{ActionabilityOptions, wait_for_actionable},
+    output,
+    timeouts::ms,
+};
+use chromiumoxide::cdp::browser_protocol::input::{
+    DispatchDragEventParams, DispatchDragEventType, DispatchMouseEventParams,
+    DispatchMouseEventType, DragData, EventDragIntercepted, MouseButton, SetInterceptDragsParams,
+};
+use chromiumoxide::layout::Point;
+use chromiumoxide::page::Page;
+use futures::StreamExt;
+use serde::Serialize;
+use std::time::Duration;
+
+/// CDP modifier bit for a key name: Alt=1, Control=2, Meta=4, Shift=8. `ControlOrMeta` is
+/// Meta on macOS and Control elsewhere.
+pub fn modifier_bit(name: &str) -> Option<i64> {
+    match name.trim().to_lowercase().as_str() {
+        "alt" | "option" => Some(1),
+        "control" | "ctrl" => Some(2),
+        "meta" | "cmd" | "command" => Some(4),
+        "shift" => Some(8),
+        "controlormeta" if cfg!(target_os = "macos") => Some(4),
+        "controlormeta" => Some(2),
+        _ => None,
+    }
+}
+
+/// Comma-separated modifier names such as `Shift,Ctrl` as a CDP modifier bitmask
+pub fn parse_modifiers(names: &str) -> Result<i64> {
+    names
+        .split(',')
+        .filter(|n| !n.trim().is_empty())
+        .try_fold(0, |mask, name| {
+            modifier_bit(name)
+                .map(|bit| mask | bit)
+                .ok_or_else(|| ChromeError::General(format!("Unknown modifier: {}", name.trim())))
+        })
+}
+
+pub fn parse_button(name: &str) -> Result<MouseButton> {
+    match name.to_lowercase().parse::<MouseButton>() {
+        Ok(MouseButton::None) | Err(_) => Err(ChromeError::General(format!(
+            "Invalid mouse button: {} (use left, right, middle, back or forward)",
+            name
+        ))),
+        Ok(button) => Ok(button),
+    }
+}
+
+/// `x,y` coordinates in CSS pixels
+pub fn parse_point(value: &str) -> Result<(f64, f64)> {
+    value
+        .split_once(',')
+        .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+        .ok_or_else(|| ChromeError::General(format!("Invalid position '{}': expected x,y", value)))
+}
+
+/// Bit for `button` in the `buttons` field of a mouse event
+fn button_mask(button: &MouseButton) -> i64 {
+    match button {
+        MouseButton::Left => 1,
+        MouseButton::Right => 2,
+        MouseButton::Middle => 4,
+        MouseButton::Back => 8,
+        MouseButton::Forward => 16,
+        MouseButton::None => 0,
+    }
+}
+
+/// Dispatches one mouse event at `point` in viewport coordinates. `held` is the button that is
+/// down while the event fires, which moves need for drags.
+pub(crate) async fn dispatch(
+    page: &Page,
+    kind: DispatchMouseEventType,
+    point: Point,
+    button: &MouseButton,
+    held: Option<&MouseButton>,
+    click_count: i64,
+    modifiers: i64,
+) -> Result<()> {
+    let params = DispatchMouseEventParams::builder()
+        .r#type(kind)
+        .x(point.x)
+        .y(point.y)
+        .button(button.clone())
+        .buttons(held.map(button_mask).unwrap_or(0))
+        .click_count(click_count)
+        .modifiers(modifiers)
+        .build()
+        .map_err(|e| ChromeError::General(format!("Failed to build mouse event: {}", e)))?;
+    page.execute(params)
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to dispatch mouse event: {}", e)))?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct MouseResult {
+    pub action: String,
+    pub x: f64,
+    pub y: f64,
+    pub button: String,
+}
+
+impl output::OutputFormatter for MouseResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        text::success(&format!(
+            "Mouse {} at ({}, {}) [{}]",
+            self.action, self.x, self.y, self.button
+        ))
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseAction {
+    Move,
+    Down,
+    Up,
+}
+
+impl MouseAction {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Move => "move",
+            Self::Down => "down",
+            Self::Up => "up",
+        }
+    }
+}
+
+/// Raw mouse event at page coordinates. For `Move`, `button` is the button held during the
+/// move (none unless given).
+pub async fn handle_mouse(
+    provider: &impl PageProvider,
+    action: MouseAction,
+    x: f64,
+    y: f64,
+    button: Option<MouseButton>,
+    modifiers: i64,
+) -> Result<MouseResult> {
+    let page = provider.get_or_create_page().await?;
+    let point = Point { x, y };
+
+    let (kind, button, held, click_count) = match action {
+        MouseAction::Move => (
+            DispatchMouseEventType::MouseMoved,
+            button.clone().unwrap_or(MouseButton::None),
+            button.clone(),
+            0,
+        ),
+        MouseAction::Down => {
+            let button = button.unwrap_or(MouseButton::Left);
+            (
+                DispatchMouseEventType::MousePressed,
+                button.clone(),
+                Some(button),
+                1,
+            )
+        }
+        MouseAction::Up => (
+            DispatchMouseEventType::MouseReleased,
+            button.unwrap_or(MouseButton::Left),
+            None,
+            1,
+        ),
+    };
+    dispatch(
+        &page,
+        kind,
+        point,
+        &button,
+        held.as_ref(),
+        click_count,
+        modifiers,
+    )
+    .await?;
+
+    Ok(MouseResult {
+        action: action.name().to_string(),
+        x,
+        y,
+        button: button.as_ref().to_string(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct DragResult {
+    pub from: String,
+    pub to: String,
+    /// `html5` when the page started a native drag, `pointer` for plain mouse events
+    pub mode: String,
+}
+
+impl output::OutputFormatter for DragResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        format!(
+            "{}\n{}",
+            text::success(&format!("Dragged: {} → {}", self.from, self.to)),
+            text::key_value("Mode", &self.mode)
+        )
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+/// Presses on `from`, moves to `to` in `steps` moves and releases. Native drags are
+/// intercepted and finished with `dragEnter`/`dragOver`/`drop`, since Chrome does not run
+/// HTML5 drag and drop from synthetic mouse events.
+pub async fn handle_drag(
+    provider: &impl PageProvider,
+    from: &str,
+    to: &str,
+    steps: u32,
+) -> Result<DragResult> {
+    let page = provider.get_or_create_page().await?;
+    wait_for_actionable(
+        &page,
+        from,
+        ms::SELECTOR_TIMEOUT,
+        ActionabilityOptions::for_hover(),
+    )
+    .await?;
+    let end = wait_for_actionable(
+        &page,
+        to,
+        ms::SELECTOR_TIMEOUT,
+        ActionabilityOptions::for_drop(),
+    )
+    .await?;
+    // Scrolling `to` into view can move `from` off-screen, so measure it again where it is
+    let start =
+        wait_for_actionable(&page, from, 0, ActionabilityOptions::for_hover().in_place()).await?;
+
+    page.execute(SetInterceptDragsParams::new(true))
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to intercept drags: {}", e)))?;
+    let executor = ActionExecutor::new(page.clone(), provider.action_config().await);
+    let result = executor
+        .execute(|| drag_between(&page, start, end, steps.max(1)))
+        .await;
+    let _ = page.execute(SetInterceptDragsParams::new(false)).await;
+
+    Ok(DragResult {
+        from: from.to_string(),
+        to: to.to_string(),
+        mode: if result? { "html5" } else { "pointer" }.to_string(),
+    })
+}
+
+/// Returns whether the page started a native drag
+async fn drag_between(page: &Page, start: Point, end: Point, steps: u32) -> Result<bool> {
+    let mut intercepted = page
+        .event_listener::<EventDragIntercepted>()
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to listen for drags: {}", e)))?;
+    let left = MouseButton::Left;
+    let moved = DispatchMouseEventType::MouseMoved;
+
+    dispatch(page, moved.clone(), start, &MouseButton::None, None, 0, 0).await?;
+    dispatch(
+        page,
+        DispatchMouseEventType::MousePressed,
+        start,
+        &left,
+        Some(&left),
+        1,
+        0,
+    )
+    .await?;
+
+    let mut drag: Option<DragData> = None;
+    for i in 1..=steps {
+        let t = i as f64 / steps as f64;
+        let point = Point {
+            x: start.x + (end.x - start.x) * t,
+            y: start.y + (end.y - start.y) * t,
+        };
+        dispatch(page, moved.clone(), point, &left, Some(&left), 0, 0).await?;
+        let wait = Duration::from_millis(ms::VIEWPORT_SETTLE);
+        if let Ok(Some(event)) = tokio::time::timeout(wait, intercepted.next()).await {
+            drag = Some(event.data.clone());
+            break;
+        }
+    }
+
+    if let Some(ref data) = drag {
+        for kind in [
+            DispatchDragEventType::DragEnter,
+            DispatchDragEventType::DragOver,
+            DispatchDragEventType::Drop,
+        ] {
+            let params = DispatchDragEventParams::builder()
+                .r#type(kind)
+                .x(end.x)
+                .y(end.y)
+                .data(data.clone())
+                .build()
+                .map_err(|e| ChromeError::General(format!("Failed to build drag event: {}", e)))?;
+            page.execute(params)
+                .await
+                .map_err(|e| ChromeError::General(format!("Failed to dispatch drag: {}", e)))?;
+        }
+    }
+
+    dispatch(
+        page,
+        DispatchMouseEventType::MouseReleased,
+        end,
+        &left,
+        None,
+        1,
+        0,
+    )
+    .await?;
+    Ok(drag.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_modifiers() {
+        assert_eq!(parse_modifiers("Shift,Ctrl").unwrap(), 10);
+        assert_eq!(parse_modifiers("alt, meta").unwrap(), 5);
+        assert_eq!(parse_modifiers("").unwrap(), 0);
+        assert!(parse_modifiers("Hyper").is_err());
+    }
+
+    #[test]
+    fn test_parse_button_and_point() {
+        assert_eq!(parse_button("Right").unwrap(), MouseButton::Right);
+        assert!(parse_button("none").is_err());
+        assert_eq!(parse_point("10, 4.5").unwrap(), (10.0, 4.5));
+        assert!(parse_point("10").is_err());
+    }
+
+    #[test]
+    fn test_drag_source_measured_in_place() {
+        // An off-screen source fails the hit check instead of being scrolled back
+        let checks = ActionabilityOptions::for_hover().in_place().checks();
+        assert!(!checks.contains(&"scroll"));
+        assert!(checks.contains(&"hit"));
+        let script = crate::js_templates::actionability("#card", &checks, None);
+        assert!(script.contains("element center is outside the viewport"));
+        assert!(
+            ActionabilityOptions::for_hover()
+                .checks()
+                .contains(&"scroll")
+        );
+    }
+}
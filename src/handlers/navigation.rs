@@ -43,7 +43,8 @@ pub async fn handle_navigate(
     let start = std::time::Instant::now();
 
     let page = provider.get_or_create_page().await?;
-    let timeout = Duration::from_secs(timeout_secs);
+    let multiplier = provider.action_config().await.navigation_multiplier();
+    let timeout = Duration::from_secs_f64(timeout_secs as f64 * multiplier);
 
     let nav_params = NavigateParams::builder()
         .url(url)
@@ -52,7 +53,7 @@ pub async fn handle_navigate(
 
     tokio::time::timeout(timeout, page.execute(nav_params))
         .await
-        .map_err(|_| ChromeError::NavigationTimeout(timeout_secs))?
+        .map_err(|_| ChromeError::NavigationTimeout(timeout.as_secs()))?
         .map_err(|e| ChromeError::General(format!("Navigation failed: {}", e)))?;
 
     let wait_result = match wait_for {
@@ -1,4 +1,8 @@
-use crate::{Result, chrome::NetworkRequest, chrome::PageProvider, output};
+use crate::{
+    Result,
+    chrome::{NetworkFilter, NetworkRequest, PageProvider},
+    output,
+};
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
@@ -23,12 +27,16 @@ impl output::OutputFormatter for NetworkRequestList {
         }
 
         for req in &self.requests {
-            let status = req
-                .status
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| "pending".to_string());
-
-            output.push_str(&format!("  {} {} - {}\n", status, req.method, req.url));
+            output.push_str(&format!(
+                "  {} {} - {}",
+                req.status_label(),
+                req.method,
+                req.url
+            ));
+            if let Some(ref error) = req.error_text {
+                output.push_str(&format!(" ({})", error));
+            }
+            output.push('\n');
         }
 
         output
@@ -41,13 +49,12 @@ impl output::OutputFormatter for NetworkRequestList {
 
 pub async fn handle_list(
     provider: &impl PageProvider,
-    domain_filter: Option<&str>,
-    status_filter: Option<u16>,
+    filter: &NetworkFilter,
 ) -> Result<NetworkRequestList> {
     let requests = provider
         .collectors()
         .network
-        .get_requests_filtered(domain_filter, status_filter)?;
+        .get_requests_filtered(filter)?;
 
     Ok(NetworkRequestList {
         total_count: requests.len(),
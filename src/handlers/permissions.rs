@@ -0,0 +1,202 @@
+use crate::{ChromeError, Result, chrome::PageProvider, output};
+use chromiumoxide::cdp::browser_protocol::browser::{
+    PermissionDescriptor, PermissionSetting, ResetPermissionsParams, SetPermissionParams,
+};
+use chromiumoxide::page::Page;
+use serde::{Deserialize, Serialize};
+
+/// Permissions that can be overridden, by their Permissions API name
+pub const PERMISSIONS: &[&str] = &[
+    "geolocation",
+    "notifications",
+    "camera",
+    "microphone",
+    "clipboard-read",
+    "clipboard-write",
+    "midi",
+];
+
+/// A permission answered without prompting. Without an origin it applies to all
+/// origins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PermissionOverride {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    pub setting: PermissionSetting,
+}
+
+/// Validates permission names and origin into one override per permission
+pub fn build_overrides(
+    names: &[String],
+    setting: PermissionSetting,
+    origin: Option<&str>,
+) -> Result<Vec<PermissionOverride>> {
+    if names.is_empty() {
+        return Err(ChromeError::General(
+            "At least one permission required".to_string(),
+        ));
+    }
+    let origin = origin.map(normalize_origin).transpose()?;
+
+    let mut overrides: Vec<PermissionOverride> = Vec::new();
+    for name in names {
+        let name = name.trim().to_lowercase();
+        if !PERMISSIONS.contains(&name.as_str()) {
+            return Err(ChromeError::General(format!(
+                "Unknown permission '{}' (expected one of: {})",
+                name,
+                PERMISSIONS.join(", ")
+            )));
+        }
+        if !overrides.iter().any(|o| o.name == name) {
+            overrides.push(PermissionOverride {
+                name,
+                origin: origin.clone(),
+                setting: setting.clone(),
+            });
+        }
+    }
+    Ok(overrides)
+}
+
+/// Reduces a URL to its origin, e.g. `https://example.com/app` to `https://example.com`
+pub fn normalize_origin(origin: &str) -> Result<String> {
+    let parsed = url::Url::parse(origin)
+        .ok()
+        .map(|url| url.origin())
+        .filter(url::Origin::is_tuple)
+        .ok_or_else(|| ChromeError::General(format!("Invalid origin: {}", origin)))?;
+    Ok(parsed.ascii_serialization())
+}
+
+/// Records `changes` over `current`, replacing overrides for the same permission and
+/// origin. Prompt is the browser default, so it drops the override.
+pub fn merge_overrides(current: &mut Vec<PermissionOverride>, changes: &[PermissionOverride]) {
+    for change in changes {
+        current.retain(|o| !(o.name == change.name && o.origin == change.origin));
+        if change.setting != PermissionSetting::Prompt {
+            current.push(change.clone());
+        }
+    }
+}
+
+pub async fn apply_override(page: &Page, permission: &PermissionOverride) -> Result<()> {
+    let mut params = SetPermissionParams::new(
+        PermissionDescriptor::new(&permission.name),
+        permission.setting.clone(),
+    );
+    params.origin = permission.origin.clone();
+    page.execute(params).await.map_err(|e| {
+        ChromeError::General(format!(
+            "Failed to set {} permission: {}",
+            permission.name, e
+        ))
+    })?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PermissionsResult {
+    /// Empty when every override was reset
+    pub changed: Vec<PermissionOverride>,
+}
+
+impl output::OutputFormatter for PermissionsResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        let Some(first) = self.changed.first() else {
+            return text::success("All permission overrides reset");
+        };
+        let verb = match first.setting {
+            PermissionSetting::Granted => "Granted",
+            PermissionSetting::Denied => "Denied",
+            PermissionSetting::Prompt => "Reset",
+        };
+        let names: Vec<&str> = self.changed.iter().map(|o| o.name.as_str()).collect();
+        format!(
+            "{}\n{}",
+            text::success(&format!("{} {}", verb, names.join(", "))),
+            text::key_value("Origin", first.origin.as_deref().unwrap_or("all origins"))
+        )
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+pub async fn handle_permissions(
+    provider: &impl PageProvider,
+    changes: Vec<PermissionOverride>,
+) -> Result<PermissionsResult> {
+    let page = provider.get_or_create_page().await?;
+    for permission in &changes {
+        apply_override(&page, permission).await?;
+    }
+    Ok(PermissionsResult { changed: changes })
+}
+
+pub async fn handle_permissions_reset(provider: &impl PageProvider) -> Result<PermissionsResult> {
+    let page = provider.get_or_create_page().await?;
+    page.execute(ResetPermissionsParams::default())
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to reset permissions: {}", e)))?;
+    Ok(PermissionsResult {
+        changed: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_overrides() {
+        let names = vec![
+            "Camera".to_string(),
+            "microphone".to_string(),
+            "camera".to_string(),
+        ];
+        let overrides = build_overrides(
+            &names,
+            PermissionSetting::Granted,
+            Some("https://example.com/app?x=1"),
+        )
+        .unwrap();
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides[0].name, "camera");
+        assert_eq!(overrides[0].origin.as_deref(), Some("https://example.com"));
+
+        assert!(build_overrides(&[], PermissionSetting::Granted, None).is_err());
+        assert!(build_overrides(&["bluetooth".into()], PermissionSetting::Granted, None).is_err());
+        assert!(
+            build_overrides(&["midi".into()], PermissionSetting::Denied, Some("nope")).is_err()
+        );
+    }
+
+    #[test]
+    fn test_merge_overrides() {
+        let mut current = Vec::new();
+        let grant = build_overrides(
+            &["geolocation".into(), "notifications".into()],
+            PermissionSetting::Granted,
+            None,
+        )
+        .unwrap();
+        merge_overrides(&mut current, &grant);
+        assert_eq!(current.len(), 2);
+
+        let deny =
+            build_overrides(&["geolocation".into()], PermissionSetting::Denied, None).unwrap();
+        merge_overrides(&mut current, &deny);
+        assert_eq!(current.len(), 2);
+        assert_eq!(current[1].setting, PermissionSetting::Denied);
+
+        let reset =
+            build_overrides(&["notifications".into()], PermissionSetting::Prompt, None).unwrap();
+        merge_overrides(&mut current, &reset);
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].name, "geolocation");
+    }
+}
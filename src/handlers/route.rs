@@ -0,0 +1,236 @@
+use crate::chrome::{RequestInterceptor, RouteAction, RouteRule};
+use crate::{ChromeError, Result, output};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RouteAddResult {
+    pub route: RouteRule,
+}
+
+impl output::OutputFormatter for RouteAddResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        format!(
+            "{}\n{}",
+            text::success(&format!("Route {} added", self.route.id)),
+            text::key_value("Pattern", &self.route.pattern)
+        )
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RouteList {
+    pub routes: Vec<RouteRule>,
+}
+
+impl output::OutputFormatter for RouteList {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+
+        if self.routes.is_empty() {
+            return text::info("No routes registered");
+        }
+
+        let mut output = text::section("Routes");
+        for route in &self.routes {
+            output.push_str(&format!("\n  {}", describe_route(route)));
+        }
+        output
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RouteRemoveResult {
+    pub removed: usize,
+}
+
+impl output::OutputFormatter for RouteRemoveResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        if self.removed == 0 {
+            text::warning("No matching route")
+        } else {
+            text::success(&format!("Removed {} route(s)", self.removed))
+        }
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+/// One-line summary of a route, e.g. `r1 **/api/* -> fulfill 200 (hits: 2)`.
+pub fn describe_route(route: &RouteRule) -> String {
+    let action = match &route.action {
+        RouteAction::Fulfill {
+            status, body_file, ..
+        } => match body_file {
+            Some(file) => format!("fulfill {} from {}", status, file),
+            None => format!("fulfill {}", status),
+        },
+        RouteAction::Abort { reason } => format!("abort {}", reason),
+        RouteAction::Continue { url, headers } => {
+            let mut parts = vec!["continue".to_string()];
+            if let Some(url) = url {
+                parts.push(format!("url={}", url));
+            }
+            if !headers.is_empty() {
+                parts.push(format!("{} header(s)", headers.len()));
+            }
+            parts.join(" ")
+        }
+    };
+    format!(
+        "{} {} -> {} (hits: {})",
+        route.id, route.pattern, action, route.hits
+    )
+}
+
+pub fn build_action(
+    kind: &str,
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body_file: Option<&Path>,
+    reason: Option<&str>,
+    url: Option<&str>,
+) -> Result<RouteAction> {
+    match kind {
+        "fulfill" => {
+            let body = body_file
+                .map(|path| {
+                    std::fs::read(path)
+                        .map(|bytes| base64::engine::general_purpose::STANDARD.encode(bytes))
+                        .map_err(|e| {
+                            ChromeError::General(format!(
+                                "Failed to read {}: {}",
+                                path.display(),
+                                e
+                            ))
+                        })
+                })
+                .transpose()?;
+
+            let mut headers = headers;
+            if let Some(path) = body_file
+                && !headers
+                    .keys()
+                    .any(|k| k.eq_ignore_ascii_case("content-type"))
+            {
+                headers.insert(
+                    "Content-Type".to_string(),
+                    guess_content_type(path).to_string(),
+                );
+            }
+
+            Ok(RouteAction::Fulfill {
+                status,
+                headers,
+                body_file: body_file.map(|p| p.display().to_string()),
+                body,
+            })
+        }
+        "abort" => Ok(RouteAction::Abort {
+            reason: reason.unwrap_or("Failed").to_string(),
+        }),
+        "continue" => Ok(RouteAction::Continue {
+            url: url.map(String::from),
+            headers,
+        }),
+        other => Err(ChromeError::General(format!(
+            "Unknown route action: {} (expected fulfill, abort, or continue)",
+            other
+        ))),
+    }
+}
+
+fn guess_content_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("json") => "application/json",
+        Some("html" | "htm") => "text/html",
+        Some("js" | "mjs") => "application/javascript",
+        Some("css") => "text/css",
+        Some("xml") => "application/xml",
+        Some("txt") => "text/plain",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+pub async fn handle_route_add(
+    interceptor: &RequestInterceptor,
+    pattern: &str,
+    action: RouteAction,
+) -> Result<RouteAddResult> {
+    let route = interceptor.add(pattern, action).await?;
+    Ok(RouteAddResult { route })
+}
+
+pub async fn handle_route_list(interceptor: &RequestInterceptor) -> Result<RouteList> {
+    Ok(RouteList {
+        routes: interceptor.list().await,
+    })
+}
+
+pub async fn handle_route_remove(
+    interceptor: &RequestInterceptor,
+    id: Option<&str>,
+) -> Result<RouteRemoveResult> {
+    let removed = match id {
+        Some(id) => interceptor.remove(id).await.map(|_| 1).unwrap_or(0),
+        None => interceptor.clear().await,
+    };
+    Ok(RouteRemoveResult { removed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_action_fulfill_sets_content_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("body.json");
+        std::fs::write(&path, "{}").unwrap();
+
+        let action =
+            build_action("fulfill", 201, BTreeMap::new(), Some(&path), None, None).unwrap();
+        match action {
+            RouteAction::Fulfill {
+                status,
+                headers,
+                body,
+                ..
+            } => {
+                assert_eq!(status, 201);
+                assert_eq!(headers.get("Content-Type").unwrap(), "application/json");
+                assert_eq!(body.as_deref(), Some("e30="));
+            }
+            _ => panic!("expected fulfill"),
+        }
+    }
+
+    #[test]
+    fn test_build_action_unknown() {
+        assert!(build_action("drop", 200, BTreeMap::new(), None, None, None).is_err());
+    }
+}
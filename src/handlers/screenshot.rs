@@ -1,10 +1,10 @@
 use crate::{
     ChromeError, Result,
     chrome::{
-        PageProvider,
+        PageProvider, frames,
         models::{ImageFormat, ScreenshotCapture},
     },
-    output,
+    js_templates, output,
     timeouts::ms,
 };
 use base64::Engine;
@@ -60,44 +60,36 @@ pub async fn handle_screenshot(
         ImageFormat::Webp => CaptureScreenshotFormat::Webp,
     };
 
-    let screenshot_data = if let Some(sel) = selector {
-        let element = page
-            .find_element(sel)
-            .await
-            .map_err(|e| ChromeError::General(format!("Element not found: {}", e)))?;
+    let mut params = CaptureScreenshotParams::builder()
+        .format(screenshot_format)
+        .build();
 
-        element
-            .screenshot(screenshot_format)
-            .await
-            .map_err(|e| ChromeError::ScreenshotFailed(e.to_string()))?
-    } else {
-        let mut params = CaptureScreenshotParams::builder()
-            .format(screenshot_format)
-            .build();
-
-        if let Some(q) = quality
-            && format_enum != ImageFormat::Png
-        {
-            params.quality = Some(q as i64);
-        }
+    if let Some(q) = quality
+        && format_enum != ImageFormat::Png
+    {
+        params.quality = Some(q as i64);
+    }
 
-        if full_page {
-            params.capture_beyond_viewport = Some(true);
-        } else if let Ok(metrics) = page.execute(GetLayoutMetricsParams::default()).await {
-            let css = &metrics.css_layout_viewport;
-            params.clip = Some(Viewport {
-                x: 0.0,
-                y: 0.0,
-                width: css.client_width as f64,
-                height: css.client_height as f64,
-                scale: 1.0,
-            });
-        }
+    if let Some(sel) = selector {
+        params.clip = Some(element_clip(&page, sel).await?);
+        params.capture_beyond_viewport = Some(true);
+    } else if full_page {
+        params.capture_beyond_viewport = Some(true);
+    } else if let Ok(metrics) = page.execute(GetLayoutMetricsParams::default()).await {
+        let css = &metrics.css_layout_viewport;
+        params.clip = Some(Viewport {
+            x: 0.0,
+            y: 0.0,
+            width: css.client_width as f64,
+            height: css.client_height as f64,
+            scale: 1.0,
+        });
+    }
 
-        page.screenshot(params)
-            .await
-            .map_err(|e| ChromeError::ScreenshotFailed(e.to_string()))?
-    };
+    let screenshot_data = page
+        .screenshot(params)
+        .await
+        .map_err(|e| ChromeError::ScreenshotFailed(e.to_string()))?;
 
     let output_pathbuf = PathBuf::from(output_path);
 
@@ -134,6 +126,39 @@ pub async fn handle_screenshot(
     })
 }
 
+/// Document-relative clip around `selector`, scrolled into view first. The element is measured
+/// in the frame it was resolved in, so elements inside iframes are captured too.
+async fn element_clip(page: &Page, selector: &str) -> Result<Viewport> {
+    let script = format!(
+        "(function(){{const el={};if(!el)return null;el.scrollIntoView({{block:'center',inline:'center',behavior:'instant'}});const r=el.getBoundingClientRect();return[r.left,r.top,r.width,r.height]}})()",
+        js_templates::deep_query(selector)
+    );
+    let rect = frames::evaluate(page, selector, script).await?;
+    let [x, y, width, height] =
+        serde_json::from_value::<[f64; 4]>(rect).map_err(|_| ChromeError::ElementNotFound {
+            selector: selector.to_string(),
+        })?;
+    if width == 0.0 || height == 0.0 {
+        return Err(ChromeError::ScreenshotFailed(format!(
+            "{} has no visible area",
+            selector
+        )));
+    }
+    let (dx, dy) = frames::offset(page, selector).await?;
+    let metrics = page
+        .execute(GetLayoutMetricsParams::default())
+        .await
+        .map_err(|e| ChromeError::ScreenshotFailed(e.to_string()))?;
+    let scroll = &metrics.css_visual_viewport;
+    Ok(Viewport {
+        x: x + dx + scroll.page_x,
+        y: y + dy + scroll.page_y,
+        width,
+        height,
+        scale: 1.0,
+    })
+}
+
 fn parse_format(format: Option<&str>) -> Result<ImageFormat> {
     match format {
         None | Some("png") => Ok(ImageFormat::Png),
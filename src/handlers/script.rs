@@ -1,4 +1,12 @@
-use crate::{ChromeError, Result, chrome::PageProvider, js_templates, output, timeouts::ms};
+use crate::{
+    ChromeError, Result,
+    chrome::{PageProvider, frames},
+    handlers::input,
+    js_templates,
+    locator::Locator,
+    output,
+    timeouts::ms,
+};
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
@@ -85,6 +93,7 @@ pub async fn handle_wait(
     provider: &impl PageProvider,
     condition: &str,
     selector: Option<&str>,
+    frame: Option<&str>,
     timeout_ms: u64,
 ) -> Result<WaitResult> {
     let page = provider.get_or_create_page().await?;
@@ -92,64 +101,42 @@ pub async fn handle_wait(
     let timeout = std::time::Duration::from_millis(timeout_ms);
 
     match condition {
-        "selector" => {
+        "selector" | "visible" | "hidden" => {
             let sel = selector.ok_or_else(|| {
-                ChromeError::General("Selector required for 'selector' condition".to_string())
+                ChromeError::General(format!("Selector required for '{}' condition", condition))
             })?;
+            // Syntax errors fail now; a frame or element that is not there yet is polled for
+            Locator::parse_chain(sel)?;
 
             tokio::time::timeout(timeout, async {
                 loop {
-                    if page.find_element(sel).await.is_ok() {
-                        return Ok::<(), ChromeError>(());
+                    let check = match input::match_selector(provider, sel, frame).await {
+                        Ok(Some(css)) if condition == "selector" => {
+                            let script = format!("{}!==null", js_templates::deep_query(&css));
+                            Some((css, script))
+                        }
+                        Ok(Some(css)) => {
+                            let script =
+                                js_templates::visibility_check(&css, condition == "visible");
+                            Some((css, script))
+                        }
+                        Ok(None) if condition == "hidden" => return Ok::<(), ChromeError>(()),
+                        _ => None,
+                    };
+                    if let Some((css, script)) = check
+                        && let Ok(result) = frames::evaluate(&page, &css, script).await
+                        && result.as_bool().unwrap_or(false)
+                    {
+                        return Ok(());
                     }
                     tokio::time::sleep(std::time::Duration::from_millis(ms::POLL_INTERVAL)).await;
                 }
             })
             .await
             .map_err(|_| {
-                ChromeError::General(format!("Timeout waiting for selector: {}", sel))
+                ChromeError::General(format!("Timeout waiting for {}: {}", condition, sel))
             })??;
         }
-        "visible" => {
-            let sel = selector.ok_or_else(|| {
-                ChromeError::General("Selector required for 'visible' condition".to_string())
-            })?;
-
-            let script = js_templates::visibility_check(sel, true);
-
-            tokio::time::timeout(timeout, async {
-                loop {
-                    if let Ok(result) = page.evaluate(script.as_str()).await
-                        && result.into_value::<bool>().unwrap_or(false)
-                    {
-                        return Ok::<(), ChromeError>(());
-                    }
-                    tokio::time::sleep(std::time::Duration::from_millis(ms::POLL_INTERVAL)).await;
-                }
-            })
-            .await
-            .map_err(|_| ChromeError::General(format!("Timeout waiting for visible: {}", sel)))??;
-        }
-        "hidden" => {
-            let sel = selector.ok_or_else(|| {
-                ChromeError::General("Selector required for 'hidden' condition".to_string())
-            })?;
-
-            let script = js_templates::visibility_check(sel, false);
-
-            tokio::time::timeout(timeout, async {
-                loop {
-                    if let Ok(result) = page.evaluate(script.as_str()).await
-                        && result.into_value::<bool>().unwrap_or(false)
-                    {
-                        return Ok::<(), ChromeError>(());
-                    }
-                    tokio::time::sleep(std::time::Duration::from_millis(ms::POLL_INTERVAL)).await;
-                }
-            })
-            .await
-            .map_err(|_| ChromeError::General(format!("Timeout waiting for hidden: {}", sel)))??;
-        }
         "stable" => {
             let stability_time = std::time::Duration::from_millis(ms::STABILITY_DURATION);
             let check_interval = std::time::Duration::from_millis(ms::POLL_INTERVAL);
@@ -3,7 +3,8 @@ use crate::{
     chrome::{
         Recording, RecordingDetail, RecordingStatus, SessionStorage,
         collectors::{
-            ConsoleLevel, ConsoleMessage, DevToolsIssue, ExtensionEvent, NetworkRequest, PageError,
+            ConsoleLevel, ConsoleMessage, DevToolsIssue, ExtensionEvent, NetworkFilter,
+            NetworkRequest, PageError,
         },
         event_store::EventMetadata,
     },
@@ -238,6 +239,7 @@ pub fn handle_events(
                 let name = match e {
                     ExtensionEvent::Click(_) => "click",
                     ExtensionEvent::Input(_) => "input",
+                    ExtensionEvent::Upload(_) => "upload",
                     ExtensionEvent::Select(_) => "select",
                     ExtensionEvent::Hover(_) => "hover",
                     ExtensionEvent::Scroll(_) => "scroll",
@@ -272,8 +274,7 @@ pub fn handle_events(
 
 pub fn handle_network(
     session_id: &str,
-    domain: Option<&str>,
-    status: Option<u16>,
+    filter: &NetworkFilter,
     time_filter: TimeFilter,
     limit: Option<usize>,
     offset: Option<usize>,
@@ -281,14 +282,10 @@ pub fn handle_network(
     let storage = SessionStorage::from_session_id(session_id)?;
     let all: Vec<NetworkRequest> = storage.read_all("network")?;
 
-    let filtered: Vec<_> = all
+    let filtered: Vec<_> = filter
+        .apply(all)?
         .into_iter()
-        .filter(|r| {
-            let domain_match = domain.map(|d| r.url.contains(d)).unwrap_or(true);
-            let status_match = status.map(|s| r.status == Some(s)).unwrap_or(true);
-            let time_match = time_filter.matches_utc(r.timestamp);
-            domain_match && status_match && time_match
-        })
+        .filter(|r| time_filter.matches_utc(r.timestamp))
         .collect();
 
     paginate(filtered, limit, offset)
@@ -0,0 +1,330 @@
+use crate::chrome::action_executor::MAX_TIMEOUT_MULTIPLIER;
+use crate::{ChromeError, Result, chrome::PageProvider, output};
+use chromiumoxide::cdp::browser_protocol::emulation::SetCpuThrottlingRateParams;
+#[allow(deprecated)]
+use chromiumoxide::cdp::browser_protocol::network::EmulateNetworkConditionsParams;
+use chromiumoxide::page::Page;
+use serde::{Deserialize, Serialize};
+
+/// Network conditions emulated for a session. Throughput of 0 leaves that direction
+/// unthrottled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkProfile {
+    pub name: String,
+    pub offline: bool,
+    pub latency_ms: f64,
+    pub download_kbps: f64,
+    pub upload_kbps: f64,
+}
+
+impl NetworkProfile {
+    fn new(name: &str, latency_ms: f64, download_kbps: f64, upload_kbps: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            offline: false,
+            latency_ms,
+            download_kbps,
+            upload_kbps,
+        }
+    }
+
+    /// Presets match the DevTools network panel
+    pub fn preset(name: &str) -> Option<Self> {
+        let key: String = name
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        match key.as_str() {
+            "slow3g" => Some(Self::new("Slow 3G", 2000.0, 400.0, 400.0)),
+            "fast3g" => Some(Self::new("Fast 3G", 562.5, 1440.0, 675.0)),
+            "4g" => Some(Self::new("4G", 165.0, 8100.0, 1350.0)),
+            "offline" => Some(Self {
+                offline: true,
+                ..Self::new("Offline", 0.0, 0.0, 0.0)
+            }),
+            _ => None,
+        }
+    }
+
+    /// Scale for action and navigation timeouts: one more base timeout per 500ms of
+    /// latency and per 500kbps of download bandwidth missing, capped at 10x. Offline
+    /// pages fail fast, so they keep the base timeouts.
+    pub fn timeout_multiplier(&self) -> f64 {
+        if self.offline {
+            return 1.0;
+        }
+        let bandwidth = if self.download_kbps > 0.0 {
+            500.0 / self.download_kbps
+        } else {
+            0.0
+        };
+        (1.0 + self.latency_ms / 500.0 + bandwidth).clamp(1.0, MAX_TIMEOUT_MULTIPLIER)
+    }
+
+    pub fn summary(&self) -> String {
+        if self.offline {
+            return self.name.clone();
+        }
+        let rate = |kbps: f64| {
+            if kbps > 0.0 {
+                format!("{}kbps", kbps)
+            } else {
+                "unlimited".to_string()
+            }
+        };
+        format!(
+            "{} ({}ms, ↓{}, ↑{})",
+            self.name,
+            self.latency_ms,
+            rate(self.download_kbps),
+            rate(self.upload_kbps)
+        )
+    }
+}
+
+/// Resolves `throttle` arguments: a preset name, custom values overriding a preset, or
+/// custom values alone. `None` turns throttling off.
+pub fn resolve_profile(
+    name: Option<&str>,
+    latency_ms: Option<f64>,
+    download_kbps: Option<f64>,
+    upload_kbps: Option<f64>,
+) -> Result<Option<NetworkProfile>> {
+    let custom = latency_ms.is_some() || download_kbps.is_some() || upload_kbps.is_some();
+    let mut profile = match name {
+        Some(name) if matches!(name.to_lowercase().as_str(), "off" | "none") => {
+            if custom {
+                return Err(ChromeError::General(
+                    "Custom throttling values cannot be combined with 'off'".to_string(),
+                ));
+            }
+            return Ok(None);
+        }
+        Some(name) => NetworkProfile::preset(name).ok_or_else(|| {
+            ChromeError::General(format!(
+                "Unknown network profile '{}' (expected slow-3g, fast-3g, 4g, offline or off)",
+                name
+            ))
+        })?,
+        None if custom => NetworkProfile::new("Custom", 0.0, 0.0, 0.0),
+        None => {
+            return Err(ChromeError::General(
+                "Network profile or --latency/--download/--upload required".to_string(),
+            ));
+        }
+    };
+
+    if custom {
+        if profile.offline {
+            return Err(ChromeError::General(
+                "Custom throttling values cannot be combined with 'offline'".to_string(),
+            ));
+        }
+        for value in [latency_ms, download_kbps, upload_kbps]
+            .into_iter()
+            .flatten()
+        {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(ChromeError::General(format!(
+                    "Invalid throttling value {}: must be 0 or more",
+                    value
+                )));
+            }
+        }
+        if name.is_some() {
+            profile.name = format!("{} (custom)", profile.name);
+        }
+        profile.latency_ms = latency_ms.unwrap_or(profile.latency_ms);
+        profile.download_kbps = download_kbps.unwrap_or(profile.download_kbps);
+        profile.upload_kbps = upload_kbps.unwrap_or(profile.upload_kbps);
+    }
+
+    Ok(Some(profile))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ThrottleResult {
+    pub network: Option<NetworkProfile>,
+    pub timeout_multiplier: f64,
+}
+
+impl output::OutputFormatter for ThrottleResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        match &self.network {
+            Some(profile) => format!(
+                "{}\n{}",
+                text::success(&format!("Network throttled: {}", profile.summary())),
+                text::key_value("Timeouts", &format!("x{:.2}", self.timeout_multiplier))
+            ),
+            None => text::success("Network throttling off"),
+        }
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+/// Applies `profile` to one page, or lifts throttling when `None`. Uses
+/// `emulateNetworkConditions`, as its by-rule replacement needs a very recent Chrome.
+#[allow(deprecated)]
+pub async fn apply_network(page: &Page, profile: Option<&NetworkProfile>) -> Result<()> {
+    let bytes_per_sec = |kbps: f64| {
+        if kbps > 0.0 {
+            kbps * 1000.0 / 8.0
+        } else {
+            -1.0
+        }
+    };
+    let params = match profile {
+        Some(p) => EmulateNetworkConditionsParams::new(
+            p.offline,
+            p.latency_ms,
+            bytes_per_sec(p.download_kbps),
+            bytes_per_sec(p.upload_kbps),
+        ),
+        None => EmulateNetworkConditionsParams::new(false, 0.0, -1.0, -1.0),
+    };
+    page.execute(params)
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to emulate network: {}", e)))?;
+    Ok(())
+}
+
+pub async fn handle_throttle(
+    provider: &impl PageProvider,
+    profile: Option<NetworkProfile>,
+) -> Result<ThrottleResult> {
+    for page in provider.all_pages().await? {
+        apply_network(&page, profile.as_ref()).await?;
+    }
+    Ok(ThrottleResult {
+        timeout_multiplier: profile
+            .as_ref()
+            .map_or(1.0, NetworkProfile::timeout_multiplier),
+        network: profile,
+    })
+}
+
+/// Scale for action timeouts under a CPU slowdown of `rate`, capped like network throttling
+pub fn cpu_timeout_multiplier(rate: f64) -> f64 {
+    rate.clamp(1.0, MAX_TIMEOUT_MULTIPLIER)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CpuThrottleResult {
+    pub rate: f64,
+}
+
+impl output::OutputFormatter for CpuThrottleResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        if self.rate > 1.0 {
+            format!(
+                "{}\n{}",
+                text::success(&format!("CPU throttled: {}x slowdown", self.rate)),
+                text::key_value(
+                    "Timeouts",
+                    &format!("x{}", cpu_timeout_multiplier(self.rate))
+                )
+            )
+        } else {
+            text::success("CPU throttling off")
+        }
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+/// Slows the page's CPU by `rate` (1 is unthrottled)
+pub async fn apply_cpu(page: &Page, rate: f64) -> Result<()> {
+    page.execute(SetCpuThrottlingRateParams::new(rate))
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to throttle CPU: {}", e)))?;
+    Ok(())
+}
+
+pub async fn handle_cpu_throttle(
+    provider: &impl PageProvider,
+    rate: f64,
+) -> Result<CpuThrottleResult> {
+    if !(rate.is_finite() && rate >= 1.0) {
+        return Err(ChromeError::General(format!(
+            "Invalid CPU throttling rate {}: must be 1 (off) or more",
+            rate
+        )));
+    }
+    // CPU throttling is per target, so each tab needs its own
+    for page in provider.all_pages().await? {
+        apply_cpu(&page, rate).await?;
+    }
+    Ok(CpuThrottleResult { rate })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_names() {
+        assert_eq!(NetworkProfile::preset("Slow 3G").unwrap().name, "Slow 3G");
+        assert_eq!(NetworkProfile::preset("fast-3g").unwrap().name, "Fast 3G");
+        assert!(NetworkProfile::preset("OFFLINE").unwrap().offline);
+        assert!(NetworkProfile::preset("5g").is_none());
+    }
+
+    #[test]
+    fn test_resolve_profile() {
+        assert_eq!(
+            resolve_profile(Some("off"), None, None, None).unwrap(),
+            None
+        );
+
+        let custom = resolve_profile(None, Some(100.0), None, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(custom.name, "Custom");
+        assert_eq!(custom.latency_ms, 100.0);
+        assert_eq!(custom.download_kbps, 0.0);
+
+        let tuned = resolve_profile(Some("4g"), Some(50.0), None, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(tuned.latency_ms, 50.0);
+        assert_eq!(tuned.download_kbps, 8100.0);
+
+        assert!(resolve_profile(None, None, None, None).is_err());
+        assert!(resolve_profile(Some("offline"), Some(10.0), None, None).is_err());
+    }
+
+    #[test]
+    fn test_timeout_multiplier() {
+        let slow = NetworkProfile::preset("slow-3g").unwrap();
+        assert_eq!(slow.timeout_multiplier(), 6.25);
+        assert_eq!(
+            NetworkProfile::preset("offline")
+                .unwrap()
+                .timeout_multiplier(),
+            1.0
+        );
+        let unlimited = NetworkProfile::new("Custom", 0.0, 0.0, 0.0);
+        assert_eq!(unlimited.timeout_multiplier(), 1.0);
+    }
+
+    #[test]
+    fn test_cpu_timeout_multiplier() {
+        use crate::output::OutputFormatter;
+
+        assert_eq!(cpu_timeout_multiplier(4.0), 4.0);
+        assert_eq!(cpu_timeout_multiplier(50.0), MAX_TIMEOUT_MULTIPLIER);
+        assert_eq!(cpu_timeout_multiplier(0.5), 1.0);
+        let text = CpuThrottleResult { rate: 20.0 }.format_text();
+        assert!(text.contains("20x slowdown"));
+        assert!(text.contains("x10"));
+        assert!(!text.contains("x20"));
+    }
+}
@@ -0,0 +1,304 @@
+use crate::{
+    ChromeError, Result,
+    chrome::{PageProvider, action_executor::ActionExecutor},
+    handlers::input::{ActionabilityOptions, wait_for_actionable},
+    output,
+    timeouts::ms,
+};
+use chromiumoxide::cdp::browser_protocol::input::{
+    DispatchTouchEventParams, DispatchTouchEventReturns, DispatchTouchEventType, GestureSourceType,
+    SynthesizePinchGestureParams, SynthesizeScrollGestureParams, TouchPoint,
+};
+use chromiumoxide::layout::Point;
+use chromiumoxide::page::Page;
+use chromiumoxide::types::{Command, Method, MethodId};
+use serde::Serialize;
+use std::time::Duration;
+
+/// `Input.dispatchTouchEvent` ending a touch. The generated params leave out an empty
+/// `touchPoints`, which Chrome rejects as a missing parameter.
+#[derive(Debug, Clone, Serialize)]
+struct TouchEnd {
+    r#type: DispatchTouchEventType,
+    #[serde(rename = "touchPoints")]
+    touch_points: Vec<TouchPoint>,
+}
+
+impl TouchEnd {
+    fn new() -> Self {
+        Self {
+            r#type: DispatchTouchEventType::TouchEnd,
+            touch_points: Vec::new(),
+        }
+    }
+}
+
+impl Method for TouchEnd {
+    fn identifier(&self) -> MethodId {
+        DispatchTouchEventParams::IDENTIFIER.into()
+    }
+}
+
+impl Command for TouchEnd {
+    type Response = DispatchTouchEventReturns;
+}
+
+/// Swipe direction as the unit vector the finger travels along
+pub fn parse_direction(name: &str) -> Option<(f64, f64)> {
+    match name.to_lowercase().as_str() {
+        "up" => Some((0.0, -1.0)),
+        "down" => Some((0.0, 1.0)),
+        "left" => Some((-1.0, 0.0)),
+        "right" => Some((1.0, 0.0)),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GestureResult {
+    pub gesture: String,
+    pub target: String,
+}
+
+impl output::OutputFormatter for GestureResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        text::success(&format!("{}: {}", self.gesture, self.target))
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+/// Touch input only reaches pages with touch emulation on, which `emulate` enables for
+/// touch devices
+async fn ensure_touch(page: &Page) -> Result<()> {
+    let points: i64 = page
+        .evaluate("navigator.maxTouchPoints")
+        .await
+        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?
+        .into_value()
+        .unwrap_or(0);
+    if points == 0 {
+        return Err(ChromeError::General(
+            "Touch is not enabled on this page; emulate a touch device first (e.g. emulate \"iPhone 14\")"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn touch(page: &Page, kind: DispatchTouchEventType, point: Point) -> Result<()> {
+    page.execute(DispatchTouchEventParams::new(
+        kind,
+        vec![TouchPoint::new(point.x, point.y)],
+    ))
+    .await
+    .map_err(|e| ChromeError::General(format!("Failed to dispatch touch: {}", e)))?;
+    Ok(())
+}
+
+async fn release(page: &Page) -> Result<()> {
+    page.execute(TouchEnd::new())
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to dispatch touch: {}", e)))?;
+    Ok(())
+}
+
+/// Center of `selector` once it can be touched, or of the viewport without one
+async fn gesture_point(page: &Page, selector: Option<&str>) -> Result<Point> {
+    match selector {
+        Some(selector) => {
+            wait_for_actionable(
+                page,
+                selector,
+                ms::SELECTOR_TIMEOUT,
+                ActionabilityOptions::for_hover(),
+            )
+            .await
+        }
+        None => {
+            let (x, y): (f64, f64) = page
+                .evaluate("[window.innerWidth / 2, window.innerHeight / 2]")
+                .await
+                .map_err(|e| ChromeError::EvaluationError(e.to_string()))?
+                .into_value()
+                .map_err(|e| ChromeError::EvaluationError(e.to_string()))?;
+            Ok(Point { x, y })
+        }
+    }
+}
+
+/// Touches and releases the element, holding for `hold_ms` in between
+async fn press(
+    provider: &impl PageProvider,
+    selector: &str,
+    hold_ms: u64,
+    gesture: &str,
+) -> Result<GestureResult> {
+    let page = provider.get_or_create_page().await?;
+    ensure_touch(&page).await?;
+    let point = wait_for_actionable(
+        &page,
+        selector,
+        ms::SELECTOR_TIMEOUT,
+        ActionabilityOptions::for_click(),
+    )
+    .await?;
+
+    let executor = ActionExecutor::new(page.clone(), provider.action_config().await);
+    executor
+        .execute(|| async {
+            touch(&page, DispatchTouchEventType::TouchStart, point).await?;
+            if hold_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(hold_ms)).await;
+            }
+            release(&page).await
+        })
+        .await?;
+
+    Ok(GestureResult {
+        gesture: gesture.to_string(),
+        target: selector.to_string(),
+    })
+}
+
+pub async fn handle_tap(provider: &impl PageProvider, selector: &str) -> Result<GestureResult> {
+    press(provider, selector, 0, "Tapped").await
+}
+
+pub async fn handle_longpress(
+    provider: &impl PageProvider,
+    selector: &str,
+    duration_ms: u64,
+) -> Result<GestureResult> {
+    press(
+        provider,
+        selector,
+        duration_ms,
+        &format!("Long-pressed ({}ms)", duration_ms),
+    )
+    .await
+}
+
+/// Swipes `distance` pixels in a direction starting at `selector` (or the viewport center).
+/// Runs as a touch scroll gesture so momentum and overscroll behave as on a device.
+pub async fn handle_swipe(
+    provider: &impl PageProvider,
+    direction: (f64, f64),
+    selector: Option<&str>,
+    distance: f64,
+) -> Result<GestureResult> {
+    let page = provider.get_or_create_page().await?;
+    ensure_touch(&page).await?;
+    let start = gesture_point(&page, selector).await?;
+
+    let params = SynthesizeScrollGestureParams::builder()
+        .x(start.x)
+        .y(start.y)
+        .x_distance(direction.0 * distance)
+        .y_distance(direction.1 * distance)
+        .gesture_source_type(GestureSourceType::Touch)
+        .build()
+        .map_err(ChromeError::General)?;
+    page.execute(params)
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to swipe: {}", e)))?;
+
+    Ok(GestureResult {
+        gesture: format!("Swiped {}px", distance),
+        target: selector.unwrap_or("viewport").to_string(),
+    })
+}
+
+/// Drags a finger from one element to another in `steps` moves
+pub async fn handle_swipe_between(
+    provider: &impl PageProvider,
+    from: &str,
+    to: &str,
+    steps: u32,
+) -> Result<GestureResult> {
+    let page = provider.get_or_create_page().await?;
+    ensure_touch(&page).await?;
+    gesture_point(&page, Some(from)).await?;
+    let end = gesture_point(&page, Some(to)).await?;
+    // Scrolling `to` into view can move `from` off-screen, so measure it again where it is
+    let start =
+        wait_for_actionable(&page, from, 0, ActionabilityOptions::for_hover().in_place()).await?;
+    let steps = steps.max(1);
+
+    let executor = ActionExecutor::new(page.clone(), provider.action_config().await);
+    executor
+        .execute(|| async {
+            touch(&page, DispatchTouchEventType::TouchStart, start).await?;
+            for i in 1..=steps {
+                let t = i as f64 / steps as f64;
+                let point = Point {
+                    x: start.x + (end.x - start.x) * t,
+                    y: start.y + (end.y - start.y) * t,
+                };
+                touch(&page, DispatchTouchEventType::TouchMove, point).await?;
+                tokio::time::sleep(Duration::from_millis(ms::VIEWPORT_SETTLE)).await;
+            }
+            release(&page).await
+        })
+        .await?;
+
+    Ok(GestureResult {
+        gesture: "Swiped".to_string(),
+        target: format!("{} → {}", from, to),
+    })
+}
+
+/// Two-finger pinch around `selector` (or the viewport center); `scale` above 1 zooms in
+pub async fn handle_pinch(
+    provider: &impl PageProvider,
+    scale: f64,
+    selector: Option<&str>,
+) -> Result<GestureResult> {
+    if !(scale.is_finite() && scale > 0.0) {
+        return Err(ChromeError::General(format!(
+            "Invalid pinch scale {}: must be greater than 0",
+            scale
+        )));
+    }
+    let page = provider.get_or_create_page().await?;
+    ensure_touch(&page).await?;
+    let center = gesture_point(&page, selector).await?;
+
+    let params = SynthesizePinchGestureParams::builder()
+        .x(center.x)
+        .y(center.y)
+        .scale_factor(scale)
+        .gesture_source_type(GestureSourceType::Touch)
+        .build()
+        .map_err(ChromeError::General)?;
+    page.execute(params)
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to pinch: {}", e)))?;
+
+    Ok(GestureResult {
+        gesture: format!("Pinched x{}", scale),
+        target: selector.unwrap_or("viewport").to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_direction() {
+        assert_eq!(parse_direction("Up"), Some((0.0, -1.0)));
+        assert_eq!(parse_direction("right"), Some((1.0, 0.0)));
+        assert_eq!(parse_direction("#card"), None);
+    }
+
+    #[test]
+    fn test_touch_end_keeps_empty_points() {
+        let json = serde_json::to_value(TouchEnd::new()).unwrap();
+        assert_eq!(json["type"], "touchEnd");
+        assert_eq!(json["touchPoints"], serde_json::json!([]));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::{
+    ChromeError, Result,
+    chrome::{PageProvider, frames},
+    handlers::input::{ClickOptions, InteractionMode, handle_click},
+    js_templates, output,
+    timeouts::ms,
+};
+use chromiumoxide::cdp::browser_protocol::dom::SetFileInputFilesParams;
+use chromiumoxide::cdp::browser_protocol::page::{
+    EventFileChooserOpened, FileChooserOpenedMode, SetInterceptFileChooserDialogParams,
+};
+use chromiumoxide::cdp::js_protocol::runtime::EvaluateParams;
+use chromiumoxide::page::Page;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UploadResult {
+    pub selector: String,
+    pub files: Vec<String>,
+    /// `input` when set on a file input directly, `chooser` when a file chooser was intercepted
+    pub via: String,
+}
+
+impl output::OutputFormatter for UploadResult {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+        let mut out = text::success(&format!(
+            "Uploaded {} file(s) to {}",
+            self.files.len(),
+            self.selector
+        ));
+        for file in &self.files {
+            out.push_str(&format!("\n  {}", file));
+        }
+        out
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+/// Absolute paths for `files`, failing on the first one that does not exist
+fn absolute_files(files: &[String]) -> Result<Vec<String>> {
+    if files.is_empty() {
+        return Err(ChromeError::General("No files to upload".to_string()));
+    }
+    files
+        .iter()
+        .map(|file| {
+            let path = Path::new(file);
+            if !path.is_file() {
+                return Err(ChromeError::General(format!("File not found: {}", file)));
+            }
+            Ok(path.canonicalize()?.to_string_lossy().into_owned())
+        })
+        .collect()
+}
+
+/// Sets `files` on a file input. When `selector` matches any other element it is clicked
+/// instead and the file chooser it opens is fulfilled, which covers custom upload buttons.
+pub async fn handle_upload(
+    provider: &impl PageProvider,
+    selector: &str,
+    files: &[String],
+) -> Result<UploadResult> {
+    let files = absolute_files(files)?;
+    let page = provider.get_or_create_page().await?;
+    let (is_file_input, multiple) = wait_for_element(&page, selector).await?;
+
+    let via = if is_file_input {
+        if files.len() > 1 && !multiple {
+            return Err(ChromeError::General(format!(
+                "{} accepts a single file ({} given)",
+                selector,
+                files.len()
+            )));
+        }
+        set_on_input(&page, selector, files.clone()).await?;
+        "input"
+    } else {
+        page.execute(SetInterceptFileChooserDialogParams::new(true))
+            .await
+            .map_err(|e| {
+                ChromeError::General(format!("Failed to intercept file chooser: {}", e))
+            })?;
+        let result = fulfil_chooser(provider, &page, selector, files.clone()).await;
+        let _ = page
+            .execute(SetInterceptFileChooserDialogParams::new(false))
+            .await;
+        result?;
+        "chooser"
+    };
+
+    Ok(UploadResult {
+        selector: selector.to_string(),
+        files,
+        via: via.to_string(),
+    })
+}
+
+/// Waits for `selector` to attach and returns whether it is a file input accepting several files
+async fn wait_for_element(page: &Page, selector: &str) -> Result<(bool, bool)> {
+    let script = js_templates::file_input(selector);
+    let start = std::time::Instant::now();
+    loop {
+        let info = frames::evaluate(page, selector, script.clone()).await?;
+        if let Some(file) = info.get("file").and_then(|v| v.as_bool()) {
+            let multiple = info
+                .get("multiple")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            return Ok((file, multiple));
+        }
+        if start.elapsed() >= Duration::from_millis(ms::SELECTOR_TIMEOUT) {
+            return Err(ChromeError::ElementNotFound {
+                selector: selector.to_string(),
+            });
+        }
+        tokio::time::sleep(Duration::from_millis(ms::POLL_INTERVAL)).await;
+    }
+}
+
+async fn set_on_input(page: &Page, selector: &str, files: Vec<String>) -> Result<()> {
+    if let Some(frame) = frames::scope(page, selector) {
+        let object_id = frame
+            .evaluate_handle(js_templates::deep_query(selector))
+            .await?
+            .ok_or_else(|| ChromeError::ElementNotFound {
+                selector: selector.to_string(),
+            })?;
+        frame
+            .execute(
+                SetFileInputFilesParams::builder()
+                    .files(files)
+                    .object_id(object_id)
+                    .build()
+                    .map_err(ChromeError::General)?,
+            )
+            .await
+            .map_err(|e| ChromeError::General(format!("Failed to set files: {}", e)))?;
+        return Ok(());
+    }
+
+    let params = EvaluateParams::builder()
+        .expression(js_templates::deep_query(selector))
+        .build()
+        .map_err(ChromeError::General)?;
+    let object_id = page
+        .execute(params)
+        .await
+        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?
+        .result
+        .result
+        .object_id
+        .clone()
+        .ok_or_else(|| ChromeError::ElementNotFound {
+            selector: selector.to_string(),
+        })?;
+
+    page.execute(
+        SetFileInputFilesParams::builder()
+            .files(files)
+            .object_id(object_id)
+            .build()
+            .map_err(ChromeError::General)?,
+    )
+    .await
+    .map_err(|e| ChromeError::General(format!("Failed to set files: {}", e)))?;
+    Ok(())
+}
+
+/// Clicks `selector` and sets `files` on the input behind the file chooser it opens
+async fn fulfil_chooser(
+    provider: &impl PageProvider,
+    page: &Page,
+    selector: &str,
+    files: Vec<String>,
+) -> Result<()> {
+    let mut opened = page
+        .event_listener::<EventFileChooserOpened>()
+        .await
+        .map_err(|e| ChromeError::General(format!("Failed to listen for file chooser: {}", e)))?;
+
+    handle_click(
+        provider,
+        selector,
+        InteractionMode::Auto,
+        ClickOptions::default(),
+    )
+    .await?;
+
+    let wait = Duration::from_millis(ms::CDP_ACTION);
+    let event = match tokio::time::timeout(wait, opened.next()).await {
+        Ok(Some(event)) => event,
+        _ => {
+            return Err(ChromeError::General(format!(
+                "Clicking {} did not open a file chooser within {}ms",
+                selector,
+                ms::CDP_ACTION
+            )));
+        }
+    };
+    if files.len() > 1 && event.mode == FileChooserOpenedMode::SelectSingle {
+        return Err(ChromeError::General(format!(
+            "The file chooser opened by {} accepts a single file ({} given)",
+            selector,
+            files.len()
+        )));
+    }
+    let backend_node_id = event.backend_node_id.ok_or_else(|| {
+        ChromeError::General(
+            "The file chooser was not opened by an <input type=file> and cannot be fulfilled"
+                .to_string(),
+        )
+    })?;
+
+    page.execute(
+        SetFileInputFilesParams::builder()
+            .files(files)
+            .backend_node_id(backend_node_id)
+            .build()
+            .map_err(ChromeError::General)?,
+    )
+    .await
+    .map_err(|e| ChromeError::General(format!("Failed to set files: {}", e)))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absolute_files() {
+        let manifest = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        let files = absolute_files(&[manifest.to_string()]).unwrap();
+        assert!(Path::new(&files[0]).is_absolute());
+        assert!(absolute_files(&[]).is_err());
+        assert!(absolute_files(&["/no/such/file.txt".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_upload_result_round_trip() {
+        use crate::output::OutputFormatter;
+
+        let result = UploadResult {
+            selector: "#avatar".to_string(),
+            files: vec!["/tmp/a.png".to_string(), "/tmp/b.png".to_string()],
+            via: "input".to_string(),
+        };
+        let parsed: UploadResult =
+            serde_json::from_value(serde_json::to_value(&result).unwrap()).unwrap();
+        let text = parsed.format_text();
+        assert!(text.contains("Uploaded 2 file(s) to #avatar"));
+        assert!(text.contains("\n  /tmp/b.png"));
+    }
+}
@@ -0,0 +1,530 @@
+use crate::{
+    ChromeError, Result,
+    chrome::{PageProvider, frames},
+    js_templates, output,
+};
+use chromiumoxide::Page;
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Per-pixel color tolerance (0-1) in YIQ space, as in pixelmatch
+const PIXEL_TOLERANCE: f64 = 0.1;
+/// Largest possible YIQ color delta
+const MAX_YIQ_DELTA: f64 = 35215.0;
+
+const DIFF_COLOR: Rgba<u8> = Rgba([255, 0, 0, 255]);
+const ANTI_ALIAS_COLOR: Rgba<u8> = Rgba([255, 255, 0, 255]);
+
+/// A rectangle in screenshot pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Region {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Region {
+    /// Parses `x,y,width,height`
+    pub fn parse(s: &str) -> Option<Self> {
+        let parts: Vec<f64> = s
+            .split(',')
+            .map(|p| p.trim().parse().ok())
+            .collect::<Option<_>>()?;
+        match parts[..] {
+            [x, y, width, height] => Some(Self {
+                x,
+                y,
+                width,
+                height,
+            }),
+            _ => None,
+        }
+    }
+
+    fn contains(&self, x: u32, y: u32) -> bool {
+        let (x, y) = (x as f64, y as f64);
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CompareOptions {
+    pub baseline: PathBuf,
+    /// Maximum share of mismatched pixels, in percent
+    pub threshold: f64,
+    pub diff_out: Option<PathBuf>,
+    pub ignore_selectors: Vec<String>,
+    pub ignore_regions: Vec<Region>,
+    pub update_baseline: bool,
+}
+
+impl CompareOptions {
+    /// A baseline is only ever written on request, so a mistyped path cannot pass silently
+    fn check_baseline(&self) -> Result<()> {
+        if self.update_baseline || self.baseline.exists() {
+            return Ok(());
+        }
+        Err(ChromeError::General(format!(
+            "Baseline not found: {} (pass --update-baseline to create it from this capture)",
+            self.baseline.display()
+        )))
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DiffStats {
+    pub mismatched: u64,
+    pub anti_aliased: u64,
+    /// Compared pixels, excluding ignored regions
+    pub total: u64,
+}
+
+impl DiffStats {
+    pub fn mismatch_percentage(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.mismatched as f64 / self.total as f64 * 100.0
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VisualComparison {
+    pub screenshot: PathBuf,
+    pub baseline: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<PathBuf>,
+    pub width: u32,
+    pub height: u32,
+    pub baseline_width: u32,
+    pub baseline_height: u32,
+    pub mismatched_pixels: u64,
+    pub anti_aliased_pixels: u64,
+    pub total_pixels: u64,
+    pub mismatch_percentage: f64,
+    pub threshold: f64,
+    pub ignored_regions: Vec<Region>,
+    pub passed: bool,
+    pub baseline_updated: bool,
+}
+
+impl output::OutputFormatter for VisualComparison {
+    fn format_text(&self) -> String {
+        use crate::output::text;
+
+        if self.baseline_updated {
+            return text::success(&format!("Baseline updated: {}", self.baseline.display()));
+        }
+
+        let summary = format!(
+            "{:.2}% of pixels differ (threshold {:.2}%)",
+            self.mismatch_percentage, self.threshold
+        );
+        let mut lines = vec![if self.passed {
+            text::success(&format!("Screenshot matches baseline: {}", summary))
+        } else {
+            text::error(&format!("Screenshot differs from baseline: {}", summary))
+        }];
+        lines.push(text::key_value(
+            "Mismatched",
+            &format!("{} / {}", self.mismatched_pixels, self.total_pixels),
+        ));
+        if self.anti_aliased_pixels > 0 {
+            lines.push(text::key_value(
+                "Anti-aliased",
+                &self.anti_aliased_pixels.to_string(),
+            ));
+        }
+        if (self.width, self.height) != (self.baseline_width, self.baseline_height) {
+            lines.push(text::warning(&format!(
+                "Size changed: {}x{} -> {}x{}",
+                self.baseline_width, self.baseline_height, self.width, self.height
+            )));
+        }
+        if let Some(ref diff) = self.diff {
+            lines.push(text::key_value("Diff", &diff.display().to_string()));
+        }
+        lines.join("\n")
+    }
+
+    fn format_json(&self, pretty: bool) -> Result<String> {
+        output::to_json(self, pretty)
+    }
+}
+
+impl VisualComparison {
+    /// Converts a mismatch above the threshold into the error that sets the exit code
+    pub fn into_result(self) -> Result<Self> {
+        if self.passed {
+            Ok(self)
+        } else {
+            Err(ChromeError::AssertionFailed(format!(
+                "Screenshot differs from baseline by {:.2}% (threshold {:.2}%)",
+                self.mismatch_percentage, self.threshold
+            )))
+        }
+    }
+}
+
+fn blend(channel: u8, alpha: f64) -> f64 {
+    255.0 + (channel as f64 - 255.0) * alpha
+}
+
+/// Pixel blended onto white, as YIQ
+fn yiq(pixel: &Rgba<u8>) -> (f64, f64, f64) {
+    let [r, g, b, a] = pixel.0;
+    let alpha = a as f64 / 255.0;
+    let (r, g, b) = (blend(r, alpha), blend(g, alpha), blend(b, alpha));
+    (
+        r * 0.29889531 + g * 0.58662247 + b * 0.11448223,
+        r * 0.59597799 - g * 0.27417610 - b * 0.32180189,
+        r * 0.21147017 - g * 0.52261711 + b * 0.31114694,
+    )
+}
+
+fn color_delta(a: &Rgba<u8>, b: &Rgba<u8>) -> f64 {
+    if a == b {
+        return 0.0;
+    }
+    let (y1, i1, q1) = yiq(a);
+    let (y2, i2, q2) = yiq(b);
+    0.5053 * (y1 - y2).powi(2) + 0.299 * (i1 - i2).powi(2) + 0.1957 * (q1 - q2).powi(2)
+}
+
+fn brightness_delta(a: &Rgba<u8>, b: &Rgba<u8>) -> f64 {
+    yiq(a).0 - yiq(b).0
+}
+
+fn neighbours(img: &RgbaImage, x: u32, y: u32) -> impl Iterator<Item = (u32, u32)> {
+    let (x0, y0) = (x.saturating_sub(1), y.saturating_sub(1));
+    let (x1, y1) = ((x + 1).min(img.width() - 1), (y + 1).min(img.height() - 1));
+    (y0..=y1)
+        .flat_map(move |ny| (x0..=x1).map(move |nx| (nx, ny)))
+        .filter(move |&p| p != (x, y))
+}
+
+fn on_edge(img: &RgbaImage, x: u32, y: u32) -> bool {
+    x == 0 || y == 0 || x == img.width() - 1 || y == img.height() - 1
+}
+
+/// More than two neighbours share the pixel's exact color
+fn has_many_siblings(img: &RgbaImage, x: u32, y: u32) -> bool {
+    if x >= img.width() || y >= img.height() {
+        return false;
+    }
+    let pixel = img.get_pixel(x, y);
+    let equal = neighbours(img, x, y)
+        .filter(|&(nx, ny)| img.get_pixel(nx, ny) == pixel)
+        .count()
+        + on_edge(img, x, y) as usize;
+    equal > 2
+}
+
+/// pixelmatch's anti-aliasing heuristic: the pixel sits on a brightness
+/// gradient between two flat areas that exist in both images.
+fn is_anti_aliased(img: &RgbaImage, x: u32, y: u32, other: &RgbaImage) -> bool {
+    let pixel = img.get_pixel(x, y);
+    let mut zeroes = on_edge(img, x, y) as usize;
+    let (mut min, mut max) = (0.0, 0.0);
+    let (mut min_at, mut max_at) = ((x, y), (x, y));
+
+    for (nx, ny) in neighbours(img, x, y) {
+        let delta = brightness_delta(pixel, img.get_pixel(nx, ny));
+        if delta == 0.0 {
+            zeroes += 1;
+            if zeroes > 2 {
+                return false;
+            }
+        } else if delta < min {
+            min = delta;
+            min_at = (nx, ny);
+        } else if delta > max {
+            max = delta;
+            max_at = (nx, ny);
+        }
+    }
+
+    if min == 0.0 || max == 0.0 {
+        return false;
+    }
+
+    (has_many_siblings(img, min_at.0, min_at.1) && has_many_siblings(other, min_at.0, min_at.1))
+        || (has_many_siblings(img, max_at.0, max_at.1)
+            && has_many_siblings(other, max_at.0, max_at.1))
+}
+
+/// Compares two images pixel by pixel. Pixels outside the overlap count as
+/// mismatched; pixels inside `ignore` are skipped. The diff image shows
+/// unchanged pixels faded, mismatches red and anti-aliasing yellow.
+pub fn diff_images(
+    actual: &RgbaImage,
+    baseline: &RgbaImage,
+    ignore: &[Region],
+) -> (DiffStats, RgbaImage) {
+    let width = actual.width().max(baseline.width());
+    let height = actual.height().max(baseline.height());
+    let max_delta = MAX_YIQ_DELTA * PIXEL_TOLERANCE * PIXEL_TOLERANCE;
+
+    let mut stats = DiffStats::default();
+    let mut diff = RgbaImage::new(width, height);
+
+    for y in 0..height {
+        for x in 0..width {
+            let a = actual.get_pixel_checked(x, y);
+            let b = baseline.get_pixel_checked(x, y);
+            let faded = a.or(b).map(|p| {
+                let gray = blend(yiq(p).0.clamp(0.0, 255.0) as u8, 0.1) as u8;
+                Rgba([gray, gray, gray, 255])
+            });
+
+            if ignore.iter().any(|r| r.contains(x, y)) {
+                diff.put_pixel(x, y, faded.unwrap_or(Rgba([0, 0, 0, 0])));
+                continue;
+            }
+            stats.total += 1;
+
+            let color = match (a, b) {
+                (Some(a), Some(b)) if color_delta(a, b) <= max_delta => faded,
+                (Some(_), Some(_))
+                    if is_anti_aliased(actual, x, y, baseline)
+                        || is_anti_aliased(baseline, x, y, actual) =>
+                {
+                    stats.anti_aliased += 1;
+                    Some(ANTI_ALIAS_COLOR)
+                }
+                _ => {
+                    stats.mismatched += 1;
+                    Some(DIFF_COLOR)
+                }
+            };
+            diff.put_pixel(x, y, color.unwrap_or(DIFF_COLOR));
+        }
+    }
+
+    (stats, diff)
+}
+
+fn load_image(path: &Path) -> Result<RgbaImage> {
+    Ok(image::open(path)
+        .map_err(|e| {
+            ChromeError::ScreenshotFailed(format!("Failed to load {}: {}", path.display(), e))
+        })?
+        .to_rgba8())
+}
+
+/// Device-pixel rects of `selector`'s matches in the page's viewport, measured in the frame
+/// the selector was resolved in
+async fn element_regions(page: &Page, selector: &str) -> Result<Vec<Region>> {
+    let rects = frames::evaluate(
+        page,
+        selector,
+        js_templates::element_rects(&[selector.to_string()]),
+    )
+    .await?;
+    let rects: Vec<Region> = serde_json::from_value(rects)?;
+    let (dx, dy) = frames::offset(page, selector).await?;
+    if dx == 0.0 && dy == 0.0 {
+        return Ok(rects);
+    }
+    let dpr = page
+        .evaluate("window.devicePixelRatio||1")
+        .await
+        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?
+        .into_value::<f64>()
+        .unwrap_or(1.0);
+    Ok(rects
+        .into_iter()
+        .map(|r| Region {
+            x: r.x + dx * dpr,
+            y: r.y + dy * dpr,
+            ..r
+        })
+        .collect())
+}
+
+/// Captures a PNG screenshot and compares it with the baseline, or replaces the baseline with
+/// the capture when `update_baseline` is set. A missing baseline is an error otherwise.
+pub async fn handle_screenshot_compare(
+    provider: &impl PageProvider,
+    output_path: &str,
+    full_page: bool,
+    selector: Option<&str>,
+    options: &CompareOptions,
+) -> Result<VisualComparison> {
+    options.check_baseline()?;
+    let capture = super::screenshot::handle_screenshot(
+        provider,
+        output_path,
+        full_page,
+        selector,
+        Some("png"),
+        None,
+    )
+    .await?;
+
+    let mut result = VisualComparison {
+        screenshot: capture.file_path.clone(),
+        baseline: options.baseline.clone(),
+        diff: None,
+        width: capture.width,
+        height: capture.height,
+        baseline_width: capture.width,
+        baseline_height: capture.height,
+        mismatched_pixels: 0,
+        anti_aliased_pixels: 0,
+        total_pixels: capture.width as u64 * capture.height as u64,
+        mismatch_percentage: 0.0,
+        threshold: options.threshold,
+        ignored_regions: Vec::new(),
+        passed: true,
+        baseline_updated: false,
+    };
+
+    if options.update_baseline {
+        if let Some(parent) = options.baseline.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(&capture.file_path, &options.baseline)?;
+        result.baseline_updated = true;
+        return Ok(result);
+    }
+
+    let mut regions = options.ignore_regions.clone();
+    if !options.ignore_selectors.is_empty() {
+        let page = provider.get_or_create_page().await?;
+        let (ox, oy) = match selector {
+            Some(sel) => element_regions(&page, sel)
+                .await?
+                .first()
+                .map_or((0.0, 0.0), |r| (r.x, r.y)),
+            None if full_page => {
+                let scroll: [f64; 2] = page
+                    .evaluate("[-window.scrollX*(window.devicePixelRatio||1),-window.scrollY*(window.devicePixelRatio||1)]")
+                    .await
+                    .map_err(|e| ChromeError::EvaluationError(e.to_string()))?
+                    .into_value()
+                    .map_err(|e| ChromeError::EvaluationError(e.to_string()))?;
+                (scroll[0], scroll[1])
+            }
+            None => (0.0, 0.0),
+        };
+        for ignored in &options.ignore_selectors {
+            regions.extend(
+                element_regions(&page, ignored)
+                    .await?
+                    .into_iter()
+                    .map(|r| Region {
+                        x: r.x - ox,
+                        y: r.y - oy,
+                        ..r
+                    }),
+            );
+        }
+    }
+
+    let actual = load_image(&capture.file_path)?;
+    let baseline = load_image(&options.baseline)?;
+    let (stats, diff) = diff_images(&actual, &baseline, &regions);
+
+    if let Some(ref diff_out) = options.diff_out {
+        if let Some(parent) = diff_out.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        diff.save(diff_out)
+            .map_err(|e| ChromeError::ScreenshotFailed(format!("Failed to save diff: {}", e)))?;
+        result.diff = Some(diff_out.clone());
+    }
+
+    result.baseline_width = baseline.width();
+    result.baseline_height = baseline.height();
+    result.mismatched_pixels = stats.mismatched;
+    result.anti_aliased_pixels = stats.anti_aliased;
+    result.total_pixels = stats.total;
+    result.mismatch_percentage = stats.mismatch_percentage();
+    result.passed = result.mismatch_percentage <= options.threshold;
+    result.ignored_regions = regions;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    #[test]
+    fn test_missing_baseline_requires_update() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut options = CompareOptions {
+            baseline: dir.path().join("missing.png"),
+            threshold: 0.1,
+            diff_out: None,
+            ignore_selectors: Vec::new(),
+            ignore_regions: Vec::new(),
+            update_baseline: false,
+        };
+        let err = options.check_baseline().unwrap_err().to_string();
+        assert!(err.contains("Baseline not found"));
+        assert!(err.contains("--update-baseline"));
+
+        options.update_baseline = true;
+        assert!(options.check_baseline().is_ok());
+
+        options.update_baseline = false;
+        options.baseline = dir.path().to_path_buf();
+        assert!(options.check_baseline().is_ok());
+    }
+
+    #[test]
+    fn test_region_parse() {
+        assert_eq!(
+            Region::parse("10, 20,30,40.5"),
+            Some(Region {
+                x: 10.0,
+                y: 20.0,
+                width: 30.0,
+                height: 40.5
+            })
+        );
+        assert_eq!(Region::parse("#banner"), None);
+        assert_eq!(Region::parse("1,2,3"), None);
+    }
+
+    #[test]
+    fn test_diff_identical_and_changed() {
+        let baseline = RgbaImage::from_pixel(10, 10, WHITE);
+        let (stats, _) = diff_images(&baseline, &baseline, &[]);
+        assert_eq!(stats.mismatched, 0);
+        assert_eq!(stats.total, 100);
+
+        let mut actual = baseline.clone();
+        for x in 2..6 {
+            for y in 2..6 {
+                actual.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+            }
+        }
+        let (stats, diff) = diff_images(&actual, &baseline, &[]);
+        assert_eq!(stats.mismatched, 16);
+        assert_eq!(stats.mismatch_percentage(), 16.0);
+        assert_eq!(*diff.get_pixel(3, 3), DIFF_COLOR);
+
+        let ignore = Region::parse("2,2,4,4").unwrap();
+        let (stats, _) = diff_images(&actual, &baseline, &[ignore]);
+        assert_eq!(stats.mismatched, 0);
+        assert_eq!(stats.total, 84);
+    }
+
+    #[test]
+    fn test_diff_size_change_counts_as_mismatch() {
+        let baseline = RgbaImage::from_pixel(10, 10, WHITE);
+        let actual = RgbaImage::from_pixel(10, 12, WHITE);
+        let (stats, diff) = diff_images(&actual, &baseline, &[]);
+        assert_eq!(stats.total, 120);
+        assert_eq!(stats.mismatched, 20);
+        assert_eq!((diff.width(), diff.height()), (10, 12));
+        assert_eq!(*diff.get_pixel(0, 11), DIFF_COLOR);
+    }
+}
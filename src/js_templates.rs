@@ -1,7 +1,31 @@
+/// Attribute `describe` stamps on listed elements so refs keep pointing at the same node
+pub const REF_ATTRIBUTE: &str = "data-cdtcli-ref";
+
+/// Attribute marking the element a semantic locator resolved to
+pub const LOCATOR_ATTRIBUTE: &str = "data-cdtcli-loc";
+
+/// Attribute marking an iframe a `>>` chain continues into
+pub const FRAME_ATTRIBUTE: &str = "data-cdtcli-frame";
+
 pub fn escape_selector(selector: &str) -> String {
     selector.replace('\\', "\\\\").replace('\'', "\\'")
 }
 
+/// `(selector, all)` search over the document and its open shadow roots. Each root is queried
+/// natively; only shadow hosts are descended into. Frames have documents of their own and are
+/// searched by running the query in their execution context.
+const DEEP_QUERY: &str = r#"((s,all)=>{const out=[];const children=r=>{const found=[];const w=(r.ownerDocument||r).createTreeWalker(r,NodeFilter.SHOW_ELEMENT,n=>n.shadowRoot?NodeFilter.FILTER_ACCEPT:NodeFilter.FILTER_SKIP);for(let n=w.nextNode();n;n=w.nextNode())found.push(n.shadowRoot);return found};const visit=r=>{if(all){for(const el of r.querySelectorAll(s))out.push(el)}else{const el=r.querySelector(s);if(el)return el}for(const c of children(r)){const el=visit(c);if(el)return el}return null};const el=visit(document);return all?out:el})"#;
+
+/// JS expression for the first element matching `selector`, piercing shadow roots
+pub fn deep_query(selector: &str) -> String {
+    format!("{}('{}',false)", DEEP_QUERY, escape_selector(selector))
+}
+
+/// JS expression for an array of every element matching `selector`, piercing shadow roots
+pub fn deep_query_all(selector: &str) -> String {
+    format!("{}('{}',true)", DEEP_QUERY, escape_selector(selector))
+}
+
 const ELEMENT_UTILS: &str = r#"
 const INTERACTIVE_TAGS = new Set(['a','button','input','select','textarea','details','summary']);
 const FORM_TAGS = new Set(['input','select','textarea','form','label','fieldset','legend','datalist','output','option','optgroup']);
@@ -124,7 +148,6 @@ function getText(el, maxLen) {
 "#;
 
 pub fn visibility_check(selector: &str, check_visible: bool) -> String {
-    let escaped = escape_selector(selector);
     let (condition, default_return) = if check_visible {
         (
             "style.display!=='none'&&style.visibility!=='hidden'&&parseFloat(style.opacity||'1')>0&&rect.width>0&&rect.height>0",
@@ -138,50 +161,167 @@ pub fn visibility_check(selector: &str, check_visible: bool) -> String {
     };
 
     format!(
-        r#"(function(){{const el=document.querySelector('{}');if(!el)return {};const style=window.getComputedStyle(el);const rect=el.getBoundingClientRect();return {}}})()"#,
-        escaped, default_return, condition
+        r#"(function(){{const el={};if(!el)return {};const style=window.getComputedStyle(el);const rect=el.getBoundingClientRect();return {}}})()"#,
+        deep_query(selector),
+        default_return,
+        condition
     )
 }
 
 pub fn click_element(selector: &str) -> String {
-    let escaped = escape_selector(selector);
     format!(
-        r#"(function(){{const el=document.querySelector('{}');if(!el)return{{found:false}};el.scrollIntoView({{block:'center',behavior:'instant'}});el.click();return{{found:true}}}})()"#,
-        escaped
+        r#"(function(){{const el={};if(!el)return{{found:false}};el.scrollIntoView({{block:'center',behavior:'instant'}});el.click();return{{found:true}}}})()"#,
+        deep_query(selector)
     )
 }
 
 pub fn fill_element(selector: &str, text: &str) -> String {
-    let escaped = escape_selector(selector);
     let escaped_text = escape_selector(text);
     format!(
-        r#"(function(){{const el=document.querySelector('{}');if(!el)return{{found:false}};el.scrollIntoView({{block:'center',behavior:'instant'}});el.focus();el.value='{}';el.dispatchEvent(new Event('input',{{bubbles:true}}));el.dispatchEvent(new Event('change',{{bubbles:true}}));return{{found:true}}}})()"#,
-        escaped, escaped_text
+        r#"(function(){{const el={};if(!el)return{{found:false}};el.scrollIntoView({{block:'center',behavior:'instant'}});el.focus();el.value='{}';el.dispatchEvent(new Event('input',{{bubbles:true}}));el.dispatchEvent(new Event('change',{{bubbles:true}}));return{{found:true}}}})()"#,
+        deep_query(selector),
+        escaped_text
     )
 }
 
 pub fn type_element(selector: &str, text: &str, delay_ms: u64) -> String {
-    let escaped = escape_selector(selector);
     let escaped_text = escape_selector(text);
     format!(
-        r#"(async function(){{const el=document.querySelector('{}');if(!el)return{{found:false}};el.scrollIntoView({{block:'center',behavior:'instant'}});el.focus();const text='{}';for(const c of text){{el.value+=c;el.dispatchEvent(new Event('input',{{bubbles:true}}));await new Promise(r=>setTimeout(r,{}))}}el.dispatchEvent(new Event('change',{{bubbles:true}}));return{{found:true}}}})()"#,
-        escaped, escaped_text, delay_ms
+        r#"(async function(){{const el={};if(!el)return{{found:false}};el.scrollIntoView({{block:'center',behavior:'instant'}});el.focus();const text='{}';for(const c of text){{el.value+=c;el.dispatchEvent(new Event('input',{{bubbles:true}}));await new Promise(r=>setTimeout(r,{}))}}el.dispatchEvent(new Event('change',{{bubbles:true}}));return{{found:true}}}})()"#,
+        deep_query(selector),
+        escaped_text,
+        delay_ms
+    )
+}
+
+/// Runs the named actionability `checks` (visible, stable, enabled, editable, hit) against the
+/// first match, scrolling it into view first when `scroll` is among them. Returns `{ok:true,x,y}` with the element's center
+/// (or `position` from its top-left corner) in the viewport of the element's frame, or
+/// `{ok:false,check,reason}` for the first check that failed.
+pub fn actionability(selector: &str, checks: &[&str], position: Option<(f64, f64)>) -> String {
+    format!(
+        r#"(async function(){{
+const el = {target};
+if (!el || !el.isConnected) return {{ ok: false, check: 'attached', reason: 'element is not attached to the DOM' }};
+const want = new Set({checks});
+const win = el.ownerDocument.defaultView;
+const describe = n => n.tagName.toLowerCase() + (n.id ? '#' + n.id : '') + Array.from(n.classList).slice(0, 3).map(c => '.' + c).join('');
+const frame = () => new Promise(r => {{ win.requestAnimationFrame(() => r()); setTimeout(r, 100); }});
+if (want.has('scroll')) {{
+    if (el.scrollIntoViewIfNeeded) el.scrollIntoViewIfNeeded(true); else el.scrollIntoView({{ block: 'center', behavior: 'instant' }});
+}}
+if (want.has('visible')) {{
+    const style = win.getComputedStyle(el);
+    const rect = el.getBoundingClientRect();
+    if (style.display === 'none' || style.visibility === 'hidden' || parseFloat(style.opacity || '1') === 0 || rect.width === 0 || rect.height === 0) {{
+        return {{ ok: false, check: 'visible', reason: 'element is not visible' }};
+    }}
+}}
+if (want.has('stable')) {{
+    await frame();
+    const a = el.getBoundingClientRect();
+    await frame();
+    const b = el.getBoundingClientRect();
+    if (a.x !== b.x || a.y !== b.y || a.width !== b.width || a.height !== b.height) {{
+        return {{ ok: false, check: 'stable', reason: 'element is still moving' }};
+    }}
+}}
+if (want.has('enabled')) {{
+    if (el.matches(':disabled') || el.getAttribute('aria-disabled') === 'true') return {{ ok: false, check: 'enabled', reason: 'element is disabled' }};
+}}
+if (want.has('editable')) {{
+    const field = ['INPUT', 'TEXTAREA', 'SELECT'].includes(el.tagName);
+    if (!field && !el.isContentEditable) return {{ ok: false, check: 'editable', reason: 'element is not an input, textarea, select or contenteditable' }};
+    if (el.readOnly || el.getAttribute('aria-readonly') === 'true') return {{ ok: false, check: 'editable', reason: 'element is readonly' }};
+}}
+const rect = el.getBoundingClientRect();
+const position = {position};
+const x = rect.left + (position ? position[0] : rect.width / 2);
+const y = rect.top + (position ? position[1] : rect.height / 2);
+if (want.has('hit')) {{
+    const root = el.getRootNode();
+    const hit = (root.elementFromPoint ? root : el.ownerDocument).elementFromPoint(x, y);
+    if (!hit) return {{ ok: false, check: 'hit', reason: 'element center is outside the viewport' }};
+    if (hit !== el && !el.contains(hit)) {{
+        return {{ ok: false, check: 'hit', reason: '<' + describe(hit) + '> intercepts pointer events' }};
+    }}
+}}
+return {{ ok: true, x, y }};
+}})()"#,
+        target = deep_query(selector),
+        checks = serde_json::Value::from(checks.to_vec()),
+        position = serde_json::json!(position.map(|(x, y)| [x, y])),
+    )
+}
+
+/// Focuses the first match, optionally selecting its current contents so typing replaces them
+pub fn focus_element(selector: &str, select_all: bool) -> String {
+    let select = if select_all {
+        "if(typeof el.select==='function')el.select();else if(el.isContentEditable){const r=el.ownerDocument.createRange();r.selectNodeContents(el);const s=el.ownerDocument.defaultView.getSelection();s.removeAllRanges();s.addRange(r)}"
+    } else {
+        ""
+    };
+    format!(
+        r#"(function(){{const el={};if(!el)return false;el.focus();{}return true}})()"#,
+        deep_query(selector),
+        select
+    )
+}
+
+/// Whether the first match is a file input (and accepts several files), or null when missing
+pub fn file_input(selector: &str) -> String {
+    format!(
+        r#"(function(){{const el={};if(!el)return null;const file=el.tagName==='INPUT'&&el.type==='file';return{{file,multiple:file&&el.multiple}}}})()"#,
+        deep_query(selector)
+    )
+}
+
+/// Match count plus visibility, trimmed text and an optional attribute of the first match
+pub fn element_state(selector: &str, attribute: Option<&str>) -> String {
+    let attribute = match attribute {
+        Some(name) => format!("el.getAttribute('{}')", escape_selector(name)),
+        None => "null".to_string(),
+    };
+    format!(
+        r#"(function(){{const els={};const el=els[0];if(!el)return{{count:0,visible:false,text:null,attribute:null}};const style=window.getComputedStyle(el);const rect=el.getBoundingClientRect();return{{count:els.length,visible:style.display!=='none'&&style.visibility!=='hidden'&&parseFloat(style.opacity||'1')>0&&rect.width>0&&rect.height>0,text:(el.innerText??el.textContent??'').trim(),attribute:{}}}}})()"#,
+        deep_query_all(selector),
+        attribute
+    )
+}
+
+/// Device-pixel rects of every match, relative to the viewport of the document it runs in
+pub fn element_rects(selectors: &[String]) -> String {
+    let selectors = selectors
+        .iter()
+        .map(|s| format!("'{}'", escape_selector(s)))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        r#"(function(){{const dpr=window.devicePixelRatio||1;const out=[];for(const s of [{}]){{for(const el of {}(s,true)){{const r=el.getBoundingClientRect();if(r.width>0&&r.height>0)out.push({{x:r.left*dpr,y:r.top*dpr,width:r.width*dpr,height:r.height*dpr}})}}}}return out}})()"#,
+        selectors, DEEP_QUERY
     )
 }
 
 pub const MUTATION_OBSERVER: &str = r#"(function(){if(!window.__mutationCount){window.__mutationCount=0;const observer=new MutationObserver(()=>{window.__mutationCount++});observer.observe(document.body||document.documentElement,{childList:true,subtree:true,attributes:true})}return window.__mutationCount})()"#;
 
+/// URL, title and viewport of the top document, plus the epoch that tells refs stamped on
+/// this document apart from those of a previous one
+pub const PAGE_INFO: &str = r#"(function(){window.__cdtcliRefEpoch=window.__cdtcliRefEpoch||Math.random().toString(36).slice(2);return{refEpoch:window.__cdtcliRefEpoch,page:{url:window.location.href,title:document.title,viewport:{width:window.innerWidth,height:window.innerHeight}}}})()"#;
+
+/// Lists visible elements of one document and stamps them with refs numbered from `refs`,
+/// whose length caps how many are listed. Frames are described by running this in each of
+/// them, so bounds are relative to the document's own viewport.
 pub fn describe_visible_elements(
     selector: Option<&str>,
     filter_interactable: bool,
     filter_forms: bool,
     filter_navigation: bool,
-    limit: usize,
+    refs: std::ops::Range<usize>,
     include_bounds: bool,
     include_selectors: bool,
 ) -> String {
     let root_selector = selector
-        .map(|s| format!("document.querySelector('{}')", escape_selector(s)))
+        .map(deep_query)
         .unwrap_or_else(|| "document.body".to_string());
 
     format!(
@@ -205,9 +345,13 @@ function hasState(state) {{
 const root = {root};
 if (!root) return null;
 
+const REF_PREFIX = {{interactive:'i',form:'f',navigation:'n',media:'m',text:'t',container:'c'}};
+{deep}('[{ref_attr}]', true).forEach(el => el.removeAttribute('{ref_attr}'));
+
 const filterInteractable = {filter_interactable};
 const filterForms = {filter_forms};
 const filterNavigation = {filter_navigation};
+const start = {start};
 const limit = {limit};
 const includeBounds = {include_bounds};
 const includeSelectors = {include_selectors};
@@ -218,68 +362,64 @@ let interactive = 0;
 let forms = 0;
 let navigation = 0;
 
-const walker = document.createTreeWalker(root, NodeFilter.SHOW_ELEMENT, {{
-    acceptNode: function(node) {{
-        return isVisible(node) ? NodeFilter.FILTER_ACCEPT : NodeFilter.FILTER_REJECT;
-    }}
-}});
-
-let node = walker.currentNode;
-while (node) {{
-    if (node.nodeType === 1 && isVisible(node)) {{
-        const category = getCategory(node);
-        const isInteractive = category === 'interactive';
-        const isForm = category === 'form';
-        const isNav = category === 'navigation';
-
-        if (isInteractive) interactive++;
-        if (isForm) forms++;
-        if (isNav) navigation++;
-
-        const shouldInclude =
-            (!filterInteractable && !filterForms && !filterNavigation) ||
-            (filterInteractable && isInteractive) ||
-            (filterForms && isForm) ||
-            (filterNavigation && isNav);
-
-        if (shouldInclude && (category !== 'container' || node === root)) {{
-            totalVisible++;
-            if (elements.length < limit) {{
-                const state = getState(node);
-                const el = {{
-                    index: elements.length,
-                    tag: node.tagName.toLowerCase(),
-                    role: getRole(node),
-                    label: getLabel(node),
-                    text: getText(node, 200),
-                    category: category,
-                    state: hasState(state) ? state : null,
-                    selector: includeSelectors ? genSelector(node) : null,
-                    bounds: null
+// Depth-first like a TreeWalker, but also entering open shadow roots
+const nodes = [];
+(function visit(node) {{
+    if (!isVisible(node)) return;
+    nodes.push(node);
+    if (node.shadowRoot) for (const child of node.shadowRoot.children) visit(child);
+    for (const child of node.children) visit(child);
+}})(root);
+
+for (const node of nodes) {{
+    const category = getCategory(node);
+    const isInteractive = category === 'interactive';
+    const isForm = category === 'form';
+    const isNav = category === 'navigation';
+
+    if (isInteractive) interactive++;
+    if (isForm) forms++;
+    if (isNav) navigation++;
+
+    const shouldInclude =
+        (!filterInteractable && !filterForms && !filterNavigation) ||
+        (filterInteractable && isInteractive) ||
+        (filterForms && isForm) ||
+        (filterNavigation && isNav);
+
+    if (shouldInclude && (category !== 'container' || node === root)) {{
+        totalVisible++;
+        if (elements.length < limit) {{
+            const index = start + elements.length;
+            node.setAttribute('{ref_attr}', REF_PREFIX[category] + index);
+            const state = getState(node);
+            const el = {{
+                index: index,
+                tag: node.tagName.toLowerCase(),
+                role: getRole(node),
+                label: getLabel(node),
+                text: getText(node, 200),
+                category: category,
+                state: hasState(state) ? state : null,
+                selector: includeSelectors ? genSelector(node) : null,
+                bounds: null
+            }};
+            if (includeBounds) {{
+                const rect = node.getBoundingClientRect();
+                el.bounds = {{
+                    x: Math.round(rect.x),
+                    y: Math.round(rect.y),
+                    width: Math.round(rect.width),
+                    height: Math.round(rect.height),
+                    inViewport: rect.top >= 0 && rect.left >= 0 && rect.bottom <= window.innerHeight && rect.right <= window.innerWidth
                 }};
-                if (includeBounds) {{
-                    const rect = node.getBoundingClientRect();
-                    el.bounds = {{
-                        x: Math.round(rect.x),
-                        y: Math.round(rect.y),
-                        width: Math.round(rect.width),
-                        height: Math.round(rect.height),
-                        inViewport: rect.top >= 0 && rect.left >= 0 && rect.bottom <= window.innerHeight && rect.right <= window.innerWidth
-                    }};
-                }}
-                elements.push(el);
             }}
+            elements.push(el);
         }}
     }}
-    node = walker.nextNode();
 }}
 
 return {{
-    page: {{
-        url: window.location.href,
-        title: document.title,
-        viewport: {{ width: window.innerWidth, height: window.innerHeight }}
-    }},
     elements: elements,
     summary: {{
         totalVisible: totalVisible,
@@ -291,11 +431,14 @@ return {{
 }};
 }})()"#,
         utils = ELEMENT_UTILS,
+        ref_attr = REF_ATTRIBUTE,
+        deep = DEEP_QUERY,
         root = root_selector,
         filter_interactable = filter_interactable,
         filter_forms = filter_forms,
         filter_navigation = filter_navigation,
-        limit = limit,
+        start = refs.start,
+        limit = refs.len(),
         include_bounds = include_bounds,
         include_selectors = include_selectors
     )
@@ -303,7 +446,7 @@ return {{
 
 pub fn label_elements(selector: Option<&str>) -> String {
     let root_selector = selector
-        .map(|s| format!("document.querySelector('{}')", escape_selector(s)))
+        .map(deep_query)
         .unwrap_or_else(|| "document.body".to_string());
 
     format!(
@@ -379,45 +522,106 @@ if (window.__labelOverlays) {
 })()"#
 }
 
-pub fn resolve_ref(ref_id: &str) -> String {
-    let escaped = escape_selector(ref_id);
+/// Resolves a chain of `Locator::query()` objects joined by `>>`, each searched inside the
+/// previous match, and stamps every final match. Returns the stamp, null when nothing matches
+/// yet, or `{error}`. A step that lands on an iframe stamps it and returns `{frame, next}` so
+/// the caller continues from step `next` in that frame's execution context.
+pub fn locate_element(chain: &[serde_json::Value]) -> String {
     format!(
         r#"(function(){{{utils}
-const REF_CATEGORIES = {{'i':'interactive','f':'form','n':'navigation','m':'media','t':'text','c':'container'}};
-
-const ref = '{ref_id}';
-const match = ref.match(/^([ifnmtc])(\d+)$/);
-if (!match) return null;
-
-const [, prefix, indexStr] = match;
-const targetCategory = REF_CATEGORIES[prefix];
-const targetIndex = parseInt(indexStr, 10);
+const chain = {chain};
+const norm = s => (s || '').replace(/\s+/g, ' ').trim();
+const matches = (actual, q) => {{
+    const a = norm(actual);
+    if (!a) return false;
+    return q.exact ? a === norm(q.value) : a.toLowerCase().includes(norm(q.value).toLowerCase());
+}};
+const labelsOf = el => {{
+    const out = Array.from(el.labels || [], l => l.textContent);
+    const aria = el.getAttribute('aria-label');
+    if (aria) out.push(aria);
+    const by = el.getAttribute('aria-labelledby');
+    if (by) out.push(by.split(/\s+/).map(id => el.ownerDocument.getElementById(id)?.textContent || '').join(' '));
+    return out;
+}};
+const nameOf = el => {{
+    if (el.tagName === 'INPUT' && ['submit', 'button', 'reset'].includes(el.type)) return el.value;
+    return getLabel(el) || labelsOf(el)[0] || el.innerText || el.textContent;
+}};
+const ownText = el => el.tagName === 'INPUT' && ['submit', 'button'].includes(el.type) ? el.value : el.innerText;
+const TESTS = {{
+    role: (el, q) => getRole(el) === q.value && (q.name == null || norm(nameOf(el)).toLowerCase() === norm(q.name).toLowerCase()),
+    text: (el, q) => matches(ownText(el), q) && !Array.from(el.children).some(c => isVisible(c) && matches(c.innerText, q)),
+    label: (el, q) => labelsOf(el).some(l => matches(l, q)),
+    placeholder: (el, q) => matches(el.getAttribute('placeholder'), q),
+}};
 
-const walker = document.createTreeWalker(document.body, NodeFilter.SHOW_ELEMENT, {{
-    acceptNode: function(node) {{
-        return isVisible(node) ? NodeFilter.FILTER_ACCEPT : NodeFilter.FILTER_REJECT;
+// The scope plus every open shadow root below it; frames are only entered through `>>`
+function roots(scope) {{
+    const out = [scope];
+    if (scope.shadowRoot) out.push(...roots(scope.shadowRoot));
+    for (const el of scope.querySelectorAll('*')) {{
+        if (el.shadowRoot) out.push(...roots(el.shadowRoot));
     }}
-}});
+    return out;
+}}
 
-let index = 0;
-let node = walker.currentNode;
-while (node) {{
-    if (node.nodeType === 1 && isVisible(node)) {{
-        const category = getCategory(node);
-        if (category !== 'container' && category === targetCategory) {{
-            if (index === targetIndex) {{
-                return {{ selector: genSelector(node), tag: node.tagName.toLowerCase(), found: true }};
-            }}
-            index++;
+function query(scope, q) {{
+    if (q.engine === 'css') return roots(scope).flatMap(r => Array.from(r.querySelectorAll(q.value)));
+    if (q.engine === 'xpath') {{
+        const doc = scope.ownerDocument || scope;
+        const snap = doc.evaluate(q.value, scope, null, XPathResult.ORDERED_NODE_SNAPSHOT_TYPE, null);
+        const out = [];
+        for (let i = 0; i < snap.snapshotLength; i++) {{
+            if (snap.snapshotItem(i).nodeType === 1) out.push(snap.snapshotItem(i));
         }}
+        return out;
+    }}
+    const test = TESTS[q.engine];
+    return roots(scope)
+        .flatMap(r => Array.from(r.querySelectorAll('*')))
+        .filter(el => test(el, q) && isVisible(el));
+}}
+
+let scope = document;
+let found = [];
+for (let i = 0; i < chain.length; i++) {{
+    found = query(scope, chain[i]);
+    if (!found.length) return null;
+    scope = found[0];
+    if (i < chain.length - 1 && (scope.tagName === 'IFRAME' || scope.tagName === 'FRAME')) {{
+        const frame = 'f' + Math.random().toString(36).slice(2, 10);
+        scope.setAttribute('{frame_attr}', frame);
+        return {{ frame, next: i + 1 }};
     }}
-    node = walker.nextNode();
 }}
 
-return {{ found: false, error: `Element with ref '${{ref}}' not found` }};
+const stamp = 'l' + Math.random().toString(36).slice(2, 10);
+for (const el of found) el.setAttribute('{attr}', stamp);
+return stamp;
 }})()"#,
         utils = ELEMENT_UTILS,
-        ref_id = escaped
+        chain = serde_json::Value::from(chain),
+        attr = LOCATOR_ATTRIBUTE,
+        frame_attr = FRAME_ATTRIBUTE,
+    )
+}
+
+/// Where a stamped ref stands now: `ok`, `navigated` (new document) or `missing`. Without a
+/// ref only the document is checked, for refs stamped inside frames, which are looked up in
+/// their own execution context.
+pub fn ref_status(ref_id: Option<&str>, epoch: &str) -> String {
+    let element = match ref_id {
+        Some(ref_id) => format!(
+            "const el={};return el&&el.isConnected?'ok':'missing'",
+            deep_query(&format!("[{}=\"{}\"]", REF_ATTRIBUTE, ref_id))
+        ),
+        None => "return'ok'".to_string(),
+    };
+    format!(
+        r#"(function(){{if(window.__cdtcliRefEpoch!=='{}')return'navigated';{}}})()"#,
+        escape_selector(epoch),
+        element
     )
 }
 
@@ -435,35 +639,111 @@ mod tests {
     #[test]
     fn test_visibility_check_visible() {
         let script = visibility_check("#test", true);
-        assert!(script.contains("querySelector('#test')"));
+        assert!(script.contains("('#test',false)"));
         assert!(script.contains("return false")); // default return
     }
 
     #[test]
     fn test_visibility_check_hidden() {
         let script = visibility_check("#test", false);
-        assert!(script.contains("querySelector('#test')"));
+        assert!(script.contains("('#test',false)"));
         assert!(script.contains("return true")); // default return
     }
 
     #[test]
     fn test_click_element() {
         let script = click_element("#btn");
-        assert!(script.contains("querySelector('#btn')"));
+        assert!(script.contains("('#btn',false)"));
         assert!(script.contains("click()"));
     }
 
     #[test]
     fn test_fill_element() {
         let script = fill_element("#input", "hello");
-        assert!(script.contains("querySelector('#input')"));
+        assert!(script.contains("('#input',false)"));
         assert!(script.contains("value='hello'"));
     }
 
+    #[test]
+    fn test_element_state() {
+        let script = element_state("a.nav", Some("data-x'y"));
+        assert!(script.contains("('a.nav',true)"));
+        assert!(script.contains("getAttribute('data-x\\'y')"));
+        assert!(element_state("#a", None).contains("attribute:null"));
+    }
+
+    #[test]
+    fn test_element_rects() {
+        let script = element_rects(&["#ad".into(), ".clock".into()]);
+        assert!(script.contains("for(const s of ['#ad','.clock'])"));
+        assert!(script.contains("x:r.left*dpr"));
+    }
+
+    #[test]
+    fn test_locate_element() {
+        let script = locate_element(&[
+            serde_json::json!({"engine": "css", "value": "iframe"}),
+            serde_json::json!({"engine": "text", "value": "it's"}),
+        ]);
+        assert!(script.contains(
+            r#"const chain = [{"engine":"css","value":"iframe"},{"engine":"text","value":"it's"}];"#
+        ));
+        assert!(script.contains("el.setAttribute('data-cdtcli-loc', stamp)"));
+        assert!(script.contains("scope.setAttribute('data-cdtcli-frame', frame)"));
+        assert!(!script.contains("contentDocument"));
+    }
+
+    #[test]
+    fn test_deep_query() {
+        let script = deep_query("it's");
+        assert!(script.starts_with("((s,all)=>"));
+        assert!(script.ends_with("('it\\'s',false)"));
+        assert!(deep_query_all("a").ends_with("('a',true)"));
+        assert!(click_element("#btn").contains("const el=((s,all)=>"));
+        // Descends only into shadow hosts instead of walking every element or frame document
+        assert!(!script.contains("querySelectorAll('*')"));
+        assert!(!script.contains("contentDocument"));
+        assert!(script.contains("n.shadowRoot?NodeFilter.FILTER_ACCEPT"));
+    }
+
+    #[test]
+    fn test_ref_status() {
+        let script = ref_status(Some("i3"), "abc");
+        assert!(script.contains("__cdtcliRefEpoch!=='abc'"));
+        assert!(script.contains("('[data-cdtcli-ref=\"i3\"]',false)"));
+        assert!(ref_status(None, "abc").ends_with("return'navigated';return'ok'})()"));
+    }
+
+    #[test]
+    fn test_actionability() {
+        let script = actionability("#buy", &["visible", "hit"], None);
+        assert!(script.contains("('#buy',false)"));
+        assert!(script.contains(r#"const want = new Set(["visible","hit"]);"#));
+        assert!(script.contains("const position = null;"));
+        assert!(
+            actionability("canvas", &[], Some((4.0, 2.5))).contains("const position = [4.0,2.5];")
+        );
+        assert!(script.contains("intercepts pointer events"));
+        assert!(script.contains("if (want.has('scroll'))"));
+    }
+
+    #[test]
+    fn test_focus_element() {
+        assert!(focus_element("#q", true).contains("el.select()"));
+        assert!(!focus_element("#q", false).contains("select"));
+    }
+
+    #[test]
+    fn test_file_input() {
+        let script = file_input("#avatar");
+        assert!(script.contains("('#avatar',false)"));
+        assert!(script.contains("el.type==='file'"));
+    }
+
     #[test]
     fn test_type_element() {
         let script = type_element("#input", "hi", 50);
-        assert!(script.contains("querySelector('#input')"));
+        assert!(script.contains("('#input',false)"));
         assert!(script.contains("setTimeout(r,50)"));
     }
 }
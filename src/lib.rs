@@ -6,6 +6,7 @@ pub mod devices;
 pub mod error;
 pub mod handlers;
 pub mod js_templates;
+pub mod locator;
 pub mod output;
 pub mod server;
 pub mod timeouts;
@@ -0,0 +1,329 @@
+use crate::chrome::collectors::TargetInfo;
+use crate::{ChromeError, Result};
+use serde_json::{Value, json};
+use std::fmt;
+
+/// How an element is addressed: plain CSS or one of the `engine=value` locators
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Locator {
+    Css(String),
+    /// `role=button[name="Sign in"]`; the name must match the accessible name
+    Role {
+        role: String,
+        name: Option<String>,
+    },
+    Text(TextQuery),
+    Label(TextQuery),
+    Placeholder(TextQuery),
+    TestId(String),
+    XPath(String),
+}
+
+/// Quoted values match exactly, bare values as a case-insensitive substring
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextQuery {
+    pub value: String,
+    pub exact: bool,
+}
+
+impl TextQuery {
+    fn parse(s: &str) -> Self {
+        match unquote(s) {
+            Some(value) => Self { value, exact: true },
+            None => Self {
+                value: s.trim().to_string(),
+                exact: false,
+            },
+        }
+    }
+}
+
+impl fmt::Display for TextQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.exact {
+            write!(f, "\"{}\"", self.value.replace('"', "\\\""))
+        } else {
+            f.write_str(&self.value)
+        }
+    }
+}
+
+fn unquote(s: &str) -> Option<String> {
+    let s = s.trim();
+    let quote = s.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let inner = s.strip_prefix(quote)?.strip_suffix(quote)?;
+    Some(inner.replace(&format!("\\{quote}"), &quote.to_string()))
+}
+
+impl Locator {
+    /// Parses `--selector` input; anything without a known `engine=` prefix is CSS
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        if input.starts_with("//") || input.starts_with("(//") {
+            return Ok(Self::XPath(input.to_string()));
+        }
+
+        let Some((engine, value)) = input.split_once('=') else {
+            return Ok(Self::Css(input.to_string()));
+        };
+        let invalid = |reason: &str| ChromeError::InvalidLocator {
+            locator: input.to_string(),
+            reason: reason.to_string(),
+        };
+        if value.trim().is_empty() && is_engine(engine.trim()) {
+            return Err(invalid("missing value"));
+        }
+
+        Ok(match engine.trim() {
+            "css" => Self::Css(value.trim().to_string()),
+            "xpath" => Self::XPath(value.trim().to_string()),
+            "testid" => Self::TestId(unquote(value).unwrap_or_else(|| value.trim().to_string())),
+            "text" => Self::Text(TextQuery::parse(value)),
+            "label" => Self::Label(TextQuery::parse(value)),
+            "placeholder" => Self::Placeholder(TextQuery::parse(value)),
+            "role" => parse_role(value).map_err(invalid)?,
+            _ => Self::Css(input.to_string()),
+        })
+    }
+
+    /// Parses a `>>` chain such as `iframe#pay >> role=button[name="Pay"]`; each step
+    /// searches inside the previous match, entering the document of a matched frame
+    pub fn parse_chain(input: &str) -> Result<Vec<Self>> {
+        let mut steps = Vec::new();
+        let mut quote = None;
+        let mut start = 0;
+        let bytes = input.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            match (quote, bytes[i]) {
+                (None, b'"' | b'\'') => quote = Some(bytes[i]),
+                (Some(q), c) if c == q => quote = None,
+                (None, b'>') if bytes.get(i + 1) == Some(&b'>') => {
+                    steps.push(&input[start..i]);
+                    start = i + 2;
+                    i += 1;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        steps.push(&input[start..]);
+
+        steps
+            .into_iter()
+            .map(|step| {
+                if step.trim().is_empty() {
+                    return Err(ChromeError::InvalidLocator {
+                        locator: input.to_string(),
+                        reason: "empty step in >> chain".into(),
+                    });
+                }
+                Self::parse(step)
+            })
+            .collect()
+    }
+
+    /// The locator an exporter should use for a recorded element, in order of preference
+    pub fn from_target(target: &TargetInfo) -> Self {
+        if let Some(ref testid) = target.testid {
+            return Self::TestId(testid.clone());
+        }
+
+        if target.aria.len() >= 2 {
+            let (role, name) = (&target.aria[0], &target.aria[1]);
+            if !role.is_empty() && !name.is_empty() {
+                return Self::Role {
+                    role: role.clone(),
+                    name: Some(name.clone()),
+                };
+            }
+        }
+
+        if let Some(ref text) = target.text
+            && !text.is_empty()
+        {
+            return Self::Text(TextQuery {
+                value: text.clone(),
+                exact: false,
+            });
+        }
+
+        match (&target.css, &target.xpath) {
+            (Some(css), _) => Self::Css(css.clone()),
+            (None, Some(xpath)) => Self::XPath(xpath.clone()),
+            (None, None) => Self::Css("body".into()),
+        }
+    }
+
+    /// Equivalent CSS selector, when the locator needs no page-side matching
+    pub fn as_css(&self) -> Option<String> {
+        match self {
+            Self::Css(css) => Some(css.clone()),
+            Self::TestId(testid) => Some(format!(
+                "[data-testid=\"{}\"]",
+                testid.replace('\\', "\\\\").replace('"', "\\\"")
+            )),
+            _ => None,
+        }
+    }
+
+    /// Query object for `js_templates::locate_element`
+    pub fn query(&self) -> Value {
+        match self {
+            Self::Css(_) | Self::TestId(_) => json!({"engine": "css", "value": self.as_css()}),
+            Self::Role { role, name } => json!({"engine": "role", "value": role, "name": name}),
+            Self::Text(q) => json!({"engine": "text", "value": q.value, "exact": q.exact}),
+            Self::Label(q) => json!({"engine": "label", "value": q.value, "exact": q.exact}),
+            Self::Placeholder(q) => {
+                json!({"engine": "placeholder", "value": q.value, "exact": q.exact})
+            }
+            Self::XPath(xpath) => json!({"engine": "xpath", "value": xpath}),
+        }
+    }
+}
+
+fn is_engine(name: &str) -> bool {
+    matches!(
+        name,
+        "css" | "xpath" | "testid" | "text" | "label" | "placeholder" | "role"
+    )
+}
+
+fn parse_role(value: &str) -> std::result::Result<Locator, &'static str> {
+    let value = value.trim();
+    let (role, rest) = match value.find('[') {
+        Some(i) => (&value[..i], Some(&value[i..])),
+        None => (value, None),
+    };
+    let role = role.trim();
+    if role.is_empty() || !role.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err("role must be a plain ARIA role such as button");
+    }
+
+    let name = match rest {
+        None => None,
+        Some(rest) => {
+            let inner = rest
+                .strip_prefix('[')
+                .and_then(|r| r.strip_suffix(']'))
+                .ok_or("expected [name=\"...\"] after the role")?;
+            match inner.split_once('=') {
+                Some((key, name)) if key.trim() == "name" => {
+                    Some(unquote(name).unwrap_or_else(|| name.trim().to_string()))
+                }
+                _ => return Err("only the name attribute is supported"),
+            }
+        }
+    };
+
+    Ok(Locator::Role {
+        role: role.to_string(),
+        name,
+    })
+}
+
+impl fmt::Display for Locator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Css(css) => f.write_str(css),
+            Self::Role { role, name: None } => write!(f, "role={role}"),
+            Self::Role {
+                role,
+                name: Some(name),
+            } => write!(f, "role={role}[name=\"{}\"]", name.replace('"', "\\\"")),
+            Self::Text(q) => write!(f, "text={q}"),
+            Self::Label(q) => write!(f, "label={q}"),
+            Self::Placeholder(q) => write!(f, "placeholder={q}"),
+            Self::TestId(testid) => write!(f, "testid={testid}"),
+            Self::XPath(xpath) => write!(f, "xpath={xpath}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_locators() {
+        assert_eq!(
+            Locator::parse("role=button[name=\"Sign in\"]").unwrap(),
+            Locator::Role {
+                role: "button".into(),
+                name: Some("Sign in".into())
+            }
+        );
+        assert_eq!(
+            Locator::parse("text=Continue").unwrap(),
+            Locator::Text(TextQuery {
+                value: "Continue".into(),
+                exact: false
+            })
+        );
+        assert_eq!(
+            Locator::parse("label='Email'").unwrap(),
+            Locator::Label(TextQuery {
+                value: "Email".into(),
+                exact: true
+            })
+        );
+        assert_eq!(
+            Locator::parse("testid=submit").unwrap().as_css().unwrap(),
+            "[data-testid=\"submit\"]"
+        );
+        assert_eq!(
+            Locator::parse("//div[@id='a']").unwrap(),
+            Locator::XPath("//div[@id='a']".into())
+        );
+    }
+
+    #[test]
+    fn test_parse_chain() {
+        let chain = Locator::parse_chain("iframe#pay >> role=button[name=\"a >> b\"]").unwrap();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[0], Locator::Css("iframe#pay".into()));
+        assert_eq!(
+            chain[1],
+            Locator::Role {
+                role: "button".into(),
+                name: Some("a >> b".into())
+            }
+        );
+        assert_eq!(Locator::parse_chain("div > a").unwrap().len(), 1);
+        assert!(Locator::parse_chain("iframe >> ").is_err());
+    }
+
+    #[test]
+    fn test_css_passthrough() {
+        for css in ["#login", "input[name=q]", "a[href='/x=1']", "css=text"] {
+            let locator = Locator::parse(css).unwrap();
+            assert!(locator.as_css().is_some(), "{css}");
+        }
+        assert_eq!(
+            Locator::parse("css=text").unwrap().as_css().unwrap(),
+            "text"
+        );
+    }
+
+    #[test]
+    fn test_invalid_locators() {
+        assert!(Locator::parse("text=").is_err());
+        assert!(Locator::parse("role=button[label=\"x\"]").is_err());
+        assert!(Locator::parse("role=but ton").is_err());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for input in [
+            "role=button[name=\"Sign in\"]",
+            "role=link",
+            "text=\"Continue\"",
+            "placeholder=Search",
+            "xpath=//main",
+        ] {
+            let locator = Locator::parse(input).unwrap();
+            assert_eq!(locator.to_string(), input);
+            assert_eq!(Locator::parse(&locator.to_string()).unwrap(), locator);
+        }
+    }
+}
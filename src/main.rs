@@ -12,7 +12,12 @@ async fn main() {
     // Handle result
     if let Err(e) = result {
         eprintln!("Error: {}", e);
-        process::exit(1);
+        // Only failed assertions get their own status; everything else stays 1
+        let code = match e {
+            chrome_devtools_cli::ChromeError::AssertionFailed(_) => e.exit_code(),
+            _ => 1,
+        };
+        process::exit(code);
     }
 }
 
@@ -2,9 +2,11 @@ use crate::ChromeError;
 use crate::server::protocol::{Response, error_codes};
 use serde::Serialize;
 
-fn map_error_code(err: &ChromeError) -> i32 {
+pub(crate) fn map_error_code(err: &ChromeError) -> i32 {
     match err {
-        ChromeError::ElementNotFound { .. } => error_codes::ELEMENT_NOT_FOUND,
+        ChromeError::ElementNotFound { .. } | ChromeError::FrameNotFound(_) => {
+            error_codes::ELEMENT_NOT_FOUND
+        }
         ChromeError::NavigationTimeout(_) => error_codes::TIMEOUT,
         ChromeError::ConnectionLost | ChromeError::SessionNotFound => {
             error_codes::SESSION_NOT_FOUND
@@ -1,10 +1,16 @@
 use crate::Result;
-use crate::config::Config;
+use crate::config::{Config, NetworkConfig};
 use crate::handlers;
-use crate::handlers::input::InteractionMode;
-use crate::server::adapter::{ToResponse, opt_bool, opt_str, opt_u64};
-use crate::{ChromeError, chrome::PageProvider, js_templates};
-use serde::Deserialize;
+use crate::handlers::input::{ClickOptions, InteractionMode};
+use crate::handlers::mouse::MouseAction;
+use crate::server::adapter::{ToResponse, map_error_code, opt_bool, opt_str, opt_u64};
+use crate::{
+    ChromeError,
+    chrome::{ConsoleLevel, NetworkFilter, PageProvider, ReplayFallback, frames},
+    js_templates,
+};
+use chromiumoxide::cdp::browser_protocol::browser::PermissionSetting;
+use chromiumoxide::cdp::browser_protocol::input::MouseButton;
 use serde_json::{Value, json};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -13,39 +19,91 @@ use tokio::sync::broadcast;
 
 use super::http::{DEFAULT_HTTP_PORT, HttpServer};
 use super::ipc::{ClientId, IpcServer};
-use super::protocol::{Request, Response, error_codes};
-use super::session_pool::SessionPool;
-
-#[derive(Deserialize)]
-struct RefResolution {
-    found: bool,
-    selector: Option<String>,
-    #[allow(dead_code)]
-    error: Option<String>,
+use super::protocol::{Request, Response, SessionEvent, error_codes};
+use super::session_pool::{Session, SessionPool};
+use super::subscription::{EVENT_STREAMS, event_stream, forward_events};
+
+async fn resolve_ref_to_selector(session: &Session, ref_id: &str) -> Result<String> {
+    let refs = session.refs().await.ok_or_else(|| {
+        ChromeError::General(format!("Unknown ref '{}': run describe first", ref_id))
+    })?;
+    let target = refs.targets.get(ref_id).ok_or_else(|| {
+        ChromeError::General(format!(
+            "Unknown ref '{}': not issued by the last describe",
+            ref_id
+        ))
+    })?;
+
+    let page = session.get_or_create_page().await?;
+    let selector = handlers::describe::ref_selector(ref_id);
+    // Refs inside frames live in another execution context than the epoch
+    let frame = frames::scope(&page, &selector);
+    let mut status: String = page
+        .evaluate(js_templates::ref_status(
+            frame.is_none().then_some(ref_id),
+            &refs.epoch,
+        ))
+        .await
+        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?
+        .into_value()
+        .unwrap_or_default();
+    if status == "ok"
+        && let Some(frame) = frame
+    {
+        let attached = format!(
+            "{}?.isConnected===true",
+            js_templates::deep_query(&selector)
+        );
+        if frame.evaluate(attached).await.ok() != Some(Value::Bool(true)) {
+            status = "missing".to_string();
+        }
+    }
+
+    match status.as_str() {
+        "ok" => Ok(selector),
+        "navigated" => Err(ChromeError::StaleRef {
+            ref_id: ref_id.to_string(),
+            reason: format!("page navigated since {} was described", target),
+        }),
+        _ => Err(ChromeError::StaleRef {
+            ref_id: ref_id.to_string(),
+            reason: format!("{} is no longer in the document", target),
+        }),
+    }
 }
 
-async fn resolve_ref_to_selector(provider: &impl PageProvider, ref_id: &str) -> Result<String> {
-    let page = provider.get_or_create_page().await?;
-    let script = js_templates::resolve_ref(ref_id);
-    let result = page
-        .evaluate(script)
-        .await
-        .map_err(|e| ChromeError::EvaluationError(e.to_string()))?;
+fn click_options_from_params(params: &Value, click_count: i64) -> Result<ClickOptions> {
+    use handlers::mouse::{parse_button, parse_modifiers, parse_point};
+    Ok(ClickOptions {
+        button: opt_str!(params, "button")
+            .map(parse_button)
+            .transpose()?
+            .unwrap_or(MouseButton::Left),
+        click_count,
+        modifiers: opt_str!(params, "modifiers")
+            .map(parse_modifiers)
+            .transpose()?
+            .unwrap_or(0),
+        position: opt_str!(params, "position").map(parse_point).transpose()?,
+    })
+}
 
-    let resolution: Option<RefResolution> = result.into_value().unwrap_or(None);
-    match resolution {
-        Some(r) if r.found => r.selector.ok_or_else(|| {
-            ChromeError::General(format!("Ref '{}' found but no selector generated", ref_id))
+fn network_filter_from_params(params: &Value) -> NetworkFilter {
+    let owned = |name: &str| params.get(name).and_then(|v| v.as_str()).map(String::from);
+    NetworkFilter {
+        domain: owned("domain"),
+        // Older clients send the status as a number
+        status: params.get("status").and_then(|v| {
+            v.as_str()
+                .map(String::from)
+                .or_else(|| v.as_u64().map(|s| s.to_string()))
         }),
-        Some(r) => {
-            Err(ChromeError::General(r.error.unwrap_or_else(|| {
-                format!("Element with ref '{}' not found", ref_id)
-            })))
-        }
-        None => Err(ChromeError::General(format!(
-            "Invalid ref format: {}",
-            ref_id
-        ))),
+        method: owned("method"),
+        resource_type: owned("resource_type"),
+        mime_type: owned("mime_type"),
+        url_pattern: owned("url_pattern"),
+        min_duration_ms: params.get("min_duration_ms").and_then(|v| v.as_f64()),
+        min_size: params.get("min_size").and_then(|v| v.as_i64()),
     }
 }
 
@@ -116,8 +174,24 @@ impl Daemon {
                 let config = config.clone();
                 let ipc = ipc_server.clone();
                 async move {
-                    handle_request(client_id, request, &pool, ext_path.as_ref(), &config, &ipc)
-                        .await
+                    let id = request.id;
+                    let method = request.method.clone();
+                    let deadline = request.params.get("deadline_ms").and_then(|v| v.as_u64());
+                    let handled =
+                        handle_request(client_id, request, &pool, ext_path.as_ref(), &config, &ipc);
+                    // A deadline cancels the handler, so the work stops with the caller's wait
+                    match deadline {
+                        Some(ms) => tokio::time::timeout(Duration::from_millis(ms), handled)
+                            .await
+                            .unwrap_or_else(|_| {
+                                Response::error(
+                                    id,
+                                    error_codes::TIMEOUT,
+                                    format!("{} timed out after {}ms", method, ms),
+                                )
+                            }),
+                        None => handled.await,
+                    }
                 }
             })
             .await?;
@@ -221,12 +295,12 @@ impl Daemon {
 }
 
 async fn handle_request(
-    _client_id: ClientId,
+    client_id: ClientId,
     request: Request,
     pool: &SessionPool,
     extension_path: Option<&PathBuf>,
     _config: &Config,
-    ipc: &IpcServer,
+    ipc: &Arc<IpcServer>,
 ) -> Response {
     let id = request.id;
     let params = &request.params;
@@ -266,10 +340,20 @@ async fn handle_request(
         };
     }
 
+    macro_rules! locate {
+        ($session:expr, $selector:expr) => {{
+            let frame = opt_str!(params, "frame");
+            match handlers::input::resolve_selector($session.as_ref(), $selector, frame).await {
+                Ok(s) => s,
+                Err(e) => return Response::error(id, map_error_code(&e), e.to_string()),
+            }
+        }};
+    }
+
     macro_rules! resolve_selector {
         ($session:expr) => {{
             match (opt_str!(params, "selector"), opt_str!(params, "ref")) {
-                (Some(s), _) if !s.is_empty() => s.to_string(),
+                (Some(s), _) if !s.is_empty() => locate!($session, s),
                 (_, Some(r)) => match resolve_ref_to_selector($session.as_ref(), r).await {
                     Ok(s) => s,
                     Err(e) => {
@@ -287,17 +371,50 @@ async fn handle_request(
         }};
     }
 
+    // Like `resolve_selector!` but optional, and locators are left for handlers that poll
+    macro_rules! selector_or_ref {
+        ($session:expr) => {{
+            match (opt_str!(params, "selector"), opt_str!(params, "ref")) {
+                (Some(s), _) if !s.is_empty() => Some(s.to_string()),
+                (_, Some(r)) => match resolve_ref_to_selector($session.as_ref(), r).await {
+                    Ok(s) => Some(s),
+                    Err(e) => {
+                        return Response::error(id, error_codes::INVALID_PARAMS, e.to_string());
+                    }
+                },
+                _ => None,
+            }
+        }};
+    }
+
     match request.method.as_str() {
         // === Session Management ===
         "session.create" => {
             let headless = opt_bool!(params, "headless", true);
             let profile_directory = opt_str!(params, "profile_directory").map(|s| s.to_string());
+            let network = NetworkConfig {
+                proxy: opt_str!(params, "proxy").map(|s| s.to_string()),
+                proxy_bypass: opt_str!(params, "proxy_bypass").map(|s| s.to_string()),
+                user_agent: opt_str!(params, "user_agent").map(|s| s.to_string()),
+            };
 
             let result = if profile_directory.is_some() {
+                if !network.is_empty() {
+                    return Response::error(
+                        id,
+                        error_codes::INVALID_PARAMS,
+                        "--proxy and --user-agent cannot be combined with a user profile session",
+                    );
+                }
                 pool.get_or_create_user_profile_session(headless, extension_path)
                     .await
             } else {
-                pool.create_ephemeral(headless, extension_path).await
+                pool.create_ephemeral(
+                    headless,
+                    extension_path,
+                    (!network.is_empty()).then_some(network),
+                )
+                .await
             };
 
             match result {
@@ -335,8 +452,12 @@ async fn handle_request(
 
         "session.destroy" => {
             let session_id = require_str!("session_id");
+            let replay = match pool.get(session_id).await {
+                Some(session) => session.interceptor().stop_replay().await,
+                None => None,
+            };
             match pool.destroy(session_id).await {
-                Ok(_) => Response::success(id, json!({"success": true})),
+                Ok(_) => Response::success(id, json!({"success": true, "replay": replay})),
                 Err(e) => Response::error(id, error_codes::BROWSER_ERROR, e.to_string()),
             }
         }
@@ -350,7 +471,15 @@ async fn handle_request(
                     "session_id": info.id,
                     "cdp_port": info.cdp_port,
                     "page_count": info.page_count,
-                    "headless": info.headless
+                    "headless": info.headless,
+                    "network_throttle": session.network_throttle().await,
+                    "cpu_throttle": session.cpu_throttle().await,
+                    "navigation_multiplier": session.action_config().await.navigation_multiplier(),
+                    "environment": session.environment().await,
+                    "proxy": session.proxy(),
+                    "permissions": session.permissions().await,
+                    "extra_headers": session.extra_headers().await,
+                    "http_auth": session.interceptor().http_credentials().await
                 }),
             )
         }
@@ -389,13 +518,128 @@ async fn handle_request(
         }
 
         // === Input (delegated to handlers) ===
-        "click" => {
+        "click" | "dblclick" => {
             let session = get_session!();
             let selector = resolve_selector!(session);
             let mode = opt_str!(params, "mode")
                 .and_then(|m| m.parse().ok())
                 .unwrap_or(InteractionMode::Auto);
-            handlers::input::handle_click(session.as_ref(), &selector, mode)
+            let click_count = if request.method == "dblclick" { 2 } else { 1 };
+            let mut options = match click_options_from_params(params, click_count) {
+                Ok(o) => o,
+                Err(e) => return Response::error(id, error_codes::INVALID_PARAMS, e.to_string()),
+            };
+            options.modifiers |= session.held_modifiers().await;
+            handlers::input::handle_click(session.as_ref(), &selector, mode, options)
+                .await
+                .to_response(id)
+        }
+
+        "drag" => {
+            let session = get_session!();
+            let from = locate!(session, require_str!("from"));
+            let to = locate!(session, require_str!("to"));
+            let steps = opt_u64!(params, "steps", 10) as u32;
+            handlers::mouse::handle_drag(session.as_ref(), &from, &to, steps)
+                .await
+                .to_response(id)
+        }
+
+        "tap" => {
+            let session = get_session!();
+            let selector = resolve_selector!(session);
+            handlers::touch::handle_tap(session.as_ref(), &selector)
+                .await
+                .to_response(id)
+        }
+
+        "longpress" => {
+            let session = get_session!();
+            let selector = resolve_selector!(session);
+            let duration = opt_u64!(params, "duration", 800);
+            handlers::touch::handle_longpress(session.as_ref(), &selector, duration)
+                .await
+                .to_response(id)
+        }
+
+        "swipe" => {
+            let session = get_session!();
+            let from = require_str!("from");
+            if let Some(to) = opt_str!(params, "to") {
+                let from = locate!(session, from);
+                let to = locate!(session, to);
+                let steps = opt_u64!(params, "steps", 10) as u32;
+                return handlers::touch::handle_swipe_between(session.as_ref(), &from, &to, steps)
+                    .await
+                    .to_response(id);
+            }
+            let Some(direction) = handlers::touch::parse_direction(from) else {
+                return Response::error(
+                    id,
+                    error_codes::INVALID_PARAMS,
+                    "to required unless from is a direction (up, down, left, right)",
+                );
+            };
+            let selector = match opt_str!(params, "selector") {
+                Some(s) => Some(locate!(session, s)),
+                None => None,
+            };
+            let distance = params
+                .get("distance")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(300.0);
+            handlers::touch::handle_swipe(
+                session.as_ref(),
+                direction,
+                selector.as_deref(),
+                distance,
+            )
+            .await
+            .to_response(id)
+        }
+
+        "pinch" => {
+            let session = get_session!();
+            let Some(scale) = params.get("scale").and_then(|v| v.as_f64()) else {
+                return Response::error(id, error_codes::INVALID_PARAMS, "scale required");
+            };
+            let selector = match opt_str!(params, "selector") {
+                Some(s) => Some(locate!(session, s)),
+                None => None,
+            };
+            handlers::touch::handle_pinch(session.as_ref(), scale, selector.as_deref())
+                .await
+                .to_response(id)
+        }
+
+        "mouse.move" | "mouse.down" | "mouse.up" => {
+            let session = get_session!();
+            let action = match request.method.as_str() {
+                "mouse.move" => MouseAction::Move,
+                "mouse.down" => MouseAction::Down,
+                _ => MouseAction::Up,
+            };
+            let (Some(x), Some(y)) = (
+                params.get("x").and_then(|v| v.as_f64()),
+                params.get("y").and_then(|v| v.as_f64()),
+            ) else {
+                return Response::error(id, error_codes::INVALID_PARAMS, "x and y required");
+            };
+            let parsed = opt_str!(params, "button")
+                .map(handlers::mouse::parse_button)
+                .transpose()
+                .and_then(|button| {
+                    let modifiers = opt_str!(params, "modifiers")
+                        .map(handlers::mouse::parse_modifiers)
+                        .transpose()?;
+                    Ok((button, modifiers.unwrap_or(0)))
+                });
+            let (button, modifiers) = match parsed {
+                Ok(p) => p,
+                Err(e) => return Response::error(id, error_codes::INVALID_PARAMS, e.to_string()),
+            };
+            let modifiers = modifiers | session.held_modifiers().await;
+            handlers::mouse::handle_mouse(session.as_ref(), action, x, y, button, modifiers)
                 .await
                 .to_response(id)
         }
@@ -435,16 +679,40 @@ async fn handle_request(
 
         "press" => {
             let session = get_session!();
-            let key = require_str!("key");
-            handlers::input::handle_press(session.as_ref(), key)
+            // `keys` is a sequence of chords; `key` may hold several separated by spaces
+            let keys: Vec<String> = match params.get("keys").and_then(|v| v.as_array()) {
+                Some(keys) => keys
+                    .iter()
+                    .filter_map(|k| k.as_str().map(String::from))
+                    .collect(),
+                None => require_str!("key")
+                    .split_whitespace()
+                    .map(String::from)
+                    .collect(),
+            };
+            let held = session.held_modifiers().await;
+            handlers::keyboard::handle_press(session.as_ref(), &keys, held)
                 .await
                 .to_response(id)
         }
 
+        "keydown" | "keyup" => {
+            let session = get_session!();
+            let key = require_str!("key");
+            let held = session.held_modifiers().await;
+            let down = request.method == "keydown";
+            let result =
+                handlers::keyboard::handle_key_hold(session.as_ref(), key, down, held).await;
+            if let Ok(ref r) = result {
+                session.set_held_modifiers(r.modifiers).await;
+            }
+            result.to_response(id)
+        }
+
         // === Inspect (delegated to handlers) ===
         "inspect" => {
             let session = get_session!();
-            let selector = require_str!("selector");
+            let selector = &locate!(session, require_str!("selector"));
             let attributes = opt_bool!(params, "attributes", false);
             let styles = opt_bool!(params, "styles", false);
             let show_box = opt_bool!(params, "box", false);
@@ -463,7 +731,7 @@ async fn handle_request(
 
         "listeners" => {
             let session = get_session!();
-            let selector = require_str!("selector");
+            let selector = &locate!(session, require_str!("selector"));
             handlers::inspect::handle_listeners(session.as_ref(), selector)
                 .await
                 .to_response(id)
@@ -471,7 +739,7 @@ async fn handle_request(
 
         "query" => {
             let session = get_session!();
-            let selector = require_str!("selector");
+            let selector = &locate!(session, require_str!("selector"));
             let count_only = opt_bool!(params, "count", false);
             let limit = opt_u64!(params, "limit", 20) as usize;
             handlers::inspect::handle_query(session.as_ref(), selector, count_only, Some(limit))
@@ -481,7 +749,7 @@ async fn handle_request(
 
         "dom" => {
             let session = get_session!();
-            let selector = require_str!("selector");
+            let selector = &locate!(session, require_str!("selector"));
             let depth = opt_u64!(params, "depth", 3) as u32;
             handlers::inspect::handle_dom(session.as_ref(), selector, depth)
                 .await
@@ -501,8 +769,12 @@ async fn handle_request(
 
         "describe" => {
             let session = get_session!();
+            let root = match (opt_str!(params, "selector"), opt_str!(params, "frame")) {
+                (None, None) => None,
+                (selector, _) => Some(locate!(session, selector.unwrap_or("body"))),
+            };
             let options = handlers::describe::DescribeOptions {
-                selector: opt_str!(params, "selector"),
+                selector: root.as_deref(),
                 interactable: opt_bool!(params, "interactable", false),
                 forms: opt_bool!(params, "forms", false),
                 navigation: opt_bool!(params, "navigation", false),
@@ -510,9 +782,11 @@ async fn handle_request(
                 with_bounds: opt_bool!(params, "with_bounds", false),
                 with_selectors: opt_bool!(params, "with_selectors", false),
             };
-            handlers::describe::handle_describe(session.as_ref(), options)
-                .await
-                .to_response(id)
+            let result = handlers::describe::handle_describe(session.as_ref(), options).await;
+            if let Ok(ref r) = result {
+                session.set_refs(r.ref_map()).await;
+            }
+            result.to_response(id)
         }
 
         "label" => {
@@ -549,6 +823,24 @@ async fn handle_request(
                 .to_response(id)
         }
 
+        "upload" => {
+            let session = get_session!();
+            let selector = resolve_selector!(session);
+            let files: Vec<String> = params
+                .get("files")
+                .and_then(|v| v.as_array())
+                .map(|files| {
+                    files
+                        .iter()
+                        .filter_map(|f| f.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default();
+            handlers::upload::handle_upload(session.as_ref(), &selector, &files)
+                .await
+                .to_response(id)
+        }
+
         "html" => {
             let session = get_session!();
             let selector = opt_str!(params, "selector");
@@ -682,12 +974,15 @@ async fn handle_request(
                 .get("quality")
                 .and_then(|v| v.as_u64())
                 .map(|q| q as u8);
-            let selector = opt_str!(params, "selector");
+            let selector = match selector_or_ref!(session) {
+                Some(s) => Some(locate!(session, &s)),
+                None => None,
+            };
             handlers::screenshot::handle_screenshot(
                 session.as_ref(),
                 output,
                 full_page,
-                selector,
+                selector.as_deref(),
                 format,
                 quality,
             )
@@ -695,14 +990,237 @@ async fn handle_request(
             .to_response(id)
         }
 
+        "screenshot.compare" => {
+            let session = get_session!();
+            let output = opt_str!(params, "output").unwrap_or("screenshot.png");
+            let full_page = opt_bool!(params, "full_page", false);
+            let selector = match selector_or_ref!(session) {
+                Some(s) => Some(locate!(session, &s)),
+                None => None,
+            };
+            let list = |key: &str| params.get(key).cloned().unwrap_or(Value::Null);
+            let mut ignore_selectors = Vec::new();
+            for s in
+                serde_json::from_value::<Vec<String>>(list("ignore_selectors")).unwrap_or_default()
+            {
+                // Ignore selectors that match nothing mask nothing rather than fail the check
+                match handlers::input::match_selector(
+                    session.as_ref(),
+                    &s,
+                    opt_str!(params, "frame"),
+                )
+                .await
+                {
+                    Ok(css) => ignore_selectors.extend(css),
+                    Err(e) => return Response::error(id, map_error_code(&e), e.to_string()),
+                }
+            }
+            let options = handlers::visual::CompareOptions {
+                baseline: PathBuf::from(require_str!("baseline")),
+                threshold: params
+                    .get("threshold")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.1),
+                diff_out: opt_str!(params, "diff_out").map(PathBuf::from),
+                ignore_selectors,
+                ignore_regions: serde_json::from_value(list("ignore_regions")).unwrap_or_default(),
+                update_baseline: opt_bool!(params, "update_baseline", false),
+            };
+            handlers::visual::handle_screenshot_compare(
+                session.as_ref(),
+                output,
+                full_page,
+                selector.as_deref(),
+                &options,
+            )
+            .await
+            .to_response(id)
+        }
+
         "emulate" => {
             let session = get_session!();
             let device = require_str!("device");
-            handlers::emulation::handle_emulate(session.as_ref(), device)
+            let environment = session.environment().await;
+            let result =
+                handlers::emulation::handle_emulate(session.as_ref(), device, &environment).await;
+            if let Ok(ref emulation) = result {
+                session.set_environment(emulation.env.clone()).await;
+            }
+            result.to_response(id)
+        }
+
+        "env.set" => {
+            let session = get_session!();
+            let update: crate::devices::EnvironmentOverrides =
+                match serde_json::from_value(params.clone()) {
+                    Ok(env) => env,
+                    Err(e) => {
+                        return Response::error(id, error_codes::INVALID_PARAMS, e.to_string());
+                    }
+                };
+            if update.is_empty() {
+                return Response::error(
+                    id,
+                    error_codes::INVALID_PARAMS,
+                    "at least one environment override required",
+                );
+            }
+            let mut environment = session.environment().await;
+            environment.merge(&update);
+            let result =
+                handlers::emulation::handle_env_set(session.as_ref(), environment.clone()).await;
+            if result.is_ok() {
+                session.set_environment(environment).await;
+            }
+            result.to_response(id)
+        }
+
+        "env.show" => {
+            let session = get_session!();
+            Response::success(id, json!({"env": session.environment().await}))
+        }
+
+        "env.reset" => {
+            let session = get_session!();
+            let result = handlers::emulation::handle_env_reset(session.as_ref()).await;
+            if result.is_ok() {
+                session.set_environment(Default::default()).await;
+            }
+            result.to_response(id)
+        }
+
+        "headers.set" => {
+            let session = get_session!();
+            let update: std::collections::BTreeMap<String, String> = match params
+                .get("headers")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+            {
+                Ok(Some(headers)) => headers,
+                Ok(None) => {
+                    return Response::error(id, error_codes::INVALID_PARAMS, "headers required");
+                }
+                Err(e) => return Response::error(id, error_codes::INVALID_PARAMS, e.to_string()),
+            };
+            let mut headers = session.extra_headers().await;
+            headers.extend(update);
+            let result = handlers::headers::handle_headers(session.as_ref(), headers).await;
+            if let Ok(ref r) = result {
+                session.set_extra_headers(r.headers.clone()).await;
+            }
+            result.to_response(id)
+        }
+
+        "headers.clear" => {
+            let session = get_session!();
+            let result =
+                handlers::headers::handle_headers(session.as_ref(), Default::default()).await;
+            if result.is_ok() {
+                session.set_extra_headers(Default::default()).await;
+            }
+            result.to_response(id)
+        }
+
+        "http_auth.set" => {
+            let session = get_session!();
+            let username = require_str!("username");
+            let password = opt_str!(params, "password").unwrap_or("");
+            handlers::headers::handle_http_auth(
+                session.interceptor(),
+                username,
+                password,
+                opt_str!(params, "origin"),
+            )
+            .await
+            .to_response(id)
+        }
+
+        "http_auth.clear" => {
+            let session = get_session!();
+            handlers::headers::handle_http_auth_clear(session.interceptor())
                 .await
                 .to_response(id)
         }
 
+        "permissions.set" => {
+            let session = get_session!();
+            let names: Vec<String> = params
+                .get("permissions")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default();
+            let setting = match require_str!("setting").parse() {
+                Ok(setting) => setting,
+                Err(s) => {
+                    return Response::error(
+                        id,
+                        error_codes::INVALID_PARAMS,
+                        format!("Invalid permission setting: {}", s),
+                    );
+                }
+            };
+            let changes = match handlers::permissions::build_overrides(
+                &names,
+                setting,
+                opt_str!(params, "origin"),
+            ) {
+                Ok(changes) => changes,
+                Err(e) => return Response::error(id, error_codes::INVALID_PARAMS, e.to_string()),
+            };
+            let result = handlers::permissions::handle_permissions(session.as_ref(), changes).await;
+            if let Ok(ref r) = result {
+                session.update_permissions(&r.changed).await;
+            }
+            result.to_response(id)
+        }
+
+        "permissions.reset" => {
+            let session = get_session!();
+            let result = match opt_str!(params, "origin") {
+                // Only this origin's overrides go back to prompting
+                Some(origin) => {
+                    let origin = match handlers::permissions::normalize_origin(origin) {
+                        Ok(origin) => origin,
+                        Err(e) => {
+                            return Response::error(id, error_codes::INVALID_PARAMS, e.to_string());
+                        }
+                    };
+                    let names: Vec<String> = session
+                        .permissions()
+                        .await
+                        .into_iter()
+                        .filter(|p| p.origin.as_deref() == Some(origin.as_str()))
+                        .map(|p| p.name)
+                        .collect();
+                    if names.is_empty() {
+                        return Response::error(
+                            id,
+                            error_codes::INVALID_PARAMS,
+                            format!("No permission overrides for {}", origin),
+                        );
+                    }
+                    let changes = match handlers::permissions::build_overrides(
+                        &names,
+                        PermissionSetting::Prompt,
+                        Some(&origin),
+                    ) {
+                        Ok(changes) => changes,
+                        Err(e) => {
+                            return Response::error(id, error_codes::INVALID_PARAMS, e.to_string());
+                        }
+                    };
+                    handlers::permissions::handle_permissions(session.as_ref(), changes).await
+                }
+                None => handlers::permissions::handle_permissions_reset(session.as_ref()).await,
+            };
+            match result {
+                Ok(ref r) if r.changed.is_empty() => session.clear_permissions().await,
+                Ok(ref r) => session.update_permissions(&r.changed).await,
+                Err(_) => {}
+            }
+            result.to_response(id)
+        }
+
         "viewport" => {
             let session = get_session!();
             let width = match params
@@ -727,6 +1245,38 @@ async fn handle_request(
                 .to_response(id)
         }
 
+        "throttle" => {
+            let session = get_session!();
+            let f64_param = |name: &str| params.get(name).and_then(|v| v.as_f64());
+            let profile = match handlers::throttle::resolve_profile(
+                opt_str!(params, "profile"),
+                f64_param("latency"),
+                f64_param("download"),
+                f64_param("upload"),
+            ) {
+                Ok(p) => p,
+                Err(e) => return Response::error(id, error_codes::INVALID_PARAMS, e.to_string()),
+            };
+            let result =
+                handlers::throttle::handle_throttle(session.as_ref(), profile.clone()).await;
+            if result.is_ok() {
+                session.set_network_throttle(profile).await;
+            }
+            result.to_response(id)
+        }
+
+        "throttle.cpu" => {
+            let session = get_session!();
+            let Some(rate) = params.get("rate").and_then(|v| v.as_f64()) else {
+                return Response::error(id, error_codes::INVALID_PARAMS, "rate required");
+            };
+            let result = handlers::throttle::handle_cpu_throttle(session.as_ref(), rate).await;
+            if result.is_ok() {
+                session.set_cpu_throttle(rate).await;
+            }
+            result.to_response(id)
+        }
+
         "dialog" => {
             let session = get_session!();
             let accept = opt_bool!(params, "accept", false);
@@ -747,13 +1297,80 @@ async fn handle_request(
         "wait" => {
             let session = get_session!();
             let condition = require_str!("condition");
-            let selector = opt_str!(params, "selector");
+            let selector = selector_or_ref!(session);
             let timeout = opt_u64!(params, "timeout", 30000);
-            handlers::script::handle_wait(session.as_ref(), condition, selector, timeout)
+            handlers::script::handle_wait(
+                session.as_ref(),
+                condition,
+                selector.as_deref(),
+                opt_str!(params, "frame"),
+                timeout,
+            )
+            .await
+            .to_response(id)
+        }
+
+        "assert" => {
+            let session = get_session!();
+            let mut assertion: handlers::assert::Assertion = match params
+                .get("assertion")
+                .cloned()
+                .map(serde_json::from_value)
+            {
+                Some(Ok(a)) => a,
+                Some(Err(e)) => {
+                    return Response::error(
+                        id,
+                        error_codes::INVALID_PARAMS,
+                        format!("Invalid assertion: {}", e),
+                    );
+                }
+                None => {
+                    return Response::error(id, error_codes::INVALID_PARAMS, "assertion required");
+                }
+            };
+            if let Some(selector) = assertion.selector_mut() {
+                if let Some(s) = selector_or_ref!(session) {
+                    *selector = s;
+                }
+                if selector.is_empty() {
+                    return Response::error(
+                        id,
+                        error_codes::INVALID_PARAMS,
+                        "selector or ref required",
+                    );
+                }
+            }
+            let frame = opt_str!(params, "frame");
+            let since = match assertion.since() {
+                Some(since) => match session.mark(since).await.or_else(|| {
+                    chrono::DateTime::parse_from_rfc3339(since)
+                        .ok()
+                        .map(|t| t.with_timezone(&chrono::Utc))
+                }) {
+                    Some(t) => Some(t),
+                    None => {
+                        return Response::error(
+                            id,
+                            error_codes::INVALID_PARAMS,
+                            format!("Unknown mark or timestamp: {}", since),
+                        );
+                    }
+                },
+                None => None,
+            };
+            handlers::assert::handle_assert(session.as_ref(), assertion, since, frame)
                 .await
                 .to_response(id)
         }
 
+        "assert.mark" => {
+            let session = get_session!();
+            let name = opt_str!(params, "name").unwrap_or("default");
+            let at = session.set_mark(name).await;
+            Response::success(id, json!({"name": name, "at": at}))
+        }
+
         "console" => {
             let session = get_session!();
             let filter = opt_str!(params, "filter");
@@ -768,12 +1385,180 @@ async fn handle_request(
 
         "network" => {
             let session = get_session!();
-            let domain = opt_str!(params, "domain");
-            let status = params
-                .get("status")
-                .and_then(|v| v.as_u64())
-                .map(|s| s as u16);
-            handlers::network::handle_list(session.as_ref(), domain, status)
+            let filter = network_filter_from_params(params);
+            handlers::network::handle_list(session.as_ref(), &filter)
+                .await
+                .to_response(id)
+        }
+
+        // === Live event subscriptions ===
+        "subscribe" => {
+            let session = get_session!();
+            let streams: Vec<String> = match params.get("events").and_then(|v| v.as_array()) {
+                Some(names) => names
+                    .iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect(),
+                None => EVENT_STREAMS.iter().map(|s| s.to_string()).collect(),
+            };
+            if let Some(unknown) = streams
+                .iter()
+                .find(|s| !EVENT_STREAMS.contains(&s.as_str()))
+            {
+                return Response::error(
+                    id,
+                    error_codes::INVALID_PARAMS,
+                    format!(
+                        "Unknown event stream: {} (expected {})",
+                        unknown,
+                        EVENT_STREAMS.join(", ")
+                    ),
+                );
+            }
+
+            let network_match = match network_filter_from_params(params).matcher() {
+                Ok(m) => m,
+                Err(e) => return Response::error(id, error_codes::INVALID_PARAMS, e.to_string()),
+            };
+            let level = opt_str!(params, "filter").and_then(|f| f.parse::<ConsoleLevel>().ok());
+
+            // Receivers are created before responding so no event is missed
+            let collectors = session.collectors();
+            let wants = |name: &str| streams.iter().any(|s| s == name);
+            let mut sources = Vec::new();
+            if wants("network") {
+                sources.push(event_stream(collectors.network.subscribe(), move |r| {
+                    network_match(&r).then(|| SessionEvent::NetworkRequest(Box::new(r)))
+                }));
+            }
+            if wants("console") {
+                sources.push(event_stream(collectors.console.subscribe(), move |m| {
+                    level
+                        .is_none_or(|l| m.level == l)
+                        .then_some(SessionEvent::ConsoleMessage(m))
+                }));
+            }
+            if wants("pageerror") {
+                sources.push(event_stream(collectors.pageerror.subscribe(), |e| {
+                    Some(SessionEvent::PageError(e))
+                }));
+            }
+
+            let task = tokio::spawn(forward_events(
+                ipc.clone(),
+                client_id,
+                session.id.clone(),
+                sources,
+            ));
+            ipc.add_subscription(client_id, &session.id, task.abort_handle())
+                .await;
+            Response::success(id, json!({"session_id": session.id, "events": streams}))
+        }
+
+        "unsubscribe" => {
+            let session_id = require_str!("session_id");
+            let removed = ipc.remove_subscription(client_id, session_id).await;
+            Response::success(id, json!({"unsubscribed": removed}))
+        }
+
+        // === Routes (request interception) ===
+        "route.add" => {
+            let session = get_session!();
+            let pattern = require_str!("pattern");
+            let kind = opt_str!(params, "action").unwrap_or("continue");
+            let status = opt_u64!(params, "status", 200) as u16;
+            let headers = params
+                .get("headers")
+                .and_then(|h| h.as_object())
+                .map(|obj| {
+                    obj.iter()
+                        .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let body_file = opt_str!(params, "body_file").map(Path::new);
+            let reason = opt_str!(params, "reason");
+            let url = opt_str!(params, "url");
+            let action = match handlers::route::build_action(
+                kind, status, headers, body_file, reason, url,
+            ) {
+                Ok(a) => a,
+                Err(e) => return Response::error(id, error_codes::INVALID_PARAMS, e.to_string()),
+            };
+            handlers::route::handle_route_add(session.interceptor(), pattern, action)
+                .await
+                .to_response(id)
+        }
+
+        "route.list" => {
+            let session = get_session!();
+            handlers::route::handle_route_list(session.interceptor())
+                .await
+                .to_response(id)
+        }
+
+        "route.remove" => {
+            let session = get_session!();
+            let route_id = opt_str!(params, "id");
+            handlers::route::handle_route_remove(session.interceptor(), route_id)
+                .await
+                .to_response(id)
+        }
+
+        "downloads.list" => {
+            let session = get_session!();
+            handlers::downloads::handle_downloads_list(&session.collectors().downloads)
+                .await
+                .to_response(id)
+        }
+
+        "downloads.wait" => {
+            let session = get_session!();
+            let timeout = opt_u64!(params, "timeout", 30);
+            handlers::downloads::handle_downloads_wait(&session.collectors().downloads, timeout)
+                .await
+                .to_response(id)
+        }
+
+        "downloads.save" => {
+            let session = get_session!();
+            let download = require_str!("id");
+            let path = require_str!("path");
+            handlers::downloads::handle_downloads_save(
+                &session.collectors().downloads,
+                download,
+                Path::new(path),
+            )
+            .await
+            .to_response(id)
+        }
+
+        // === HAR replay ===
+        "replay.start" => {
+            let session = get_session!();
+            let har = require_str!("har");
+            let fallback = match opt_str!(params, "fallback")
+                .unwrap_or("abort")
+                .parse::<ReplayFallback>()
+            {
+                Ok(f) => f,
+                Err(e) => return Response::error(id, error_codes::INVALID_PARAMS, e.to_string()),
+            };
+            handlers::har::handle_replay_start(session.interceptor(), Path::new(har), fallback)
+                .await
+                .to_response(id)
+        }
+
+        "replay.status" => {
+            let session = get_session!();
+            handlers::har::handle_replay_status(session.interceptor())
+                .await
+                .to_response(id)
+        }
+
+        "replay.stop" => {
+            let session = get_session!();
+            handlers::har::handle_replay_stop(session.interceptor())
                 .await
                 .to_response(id)
         }
@@ -799,7 +1584,8 @@ async fn handle_request(
         "page.new" => {
             let session = get_session!();
             let url = opt_str!(params, "url");
-            let timeout_ms = opt_u64!(params, "timeout", 30000);
+            let multiplier = session.action_config().await.navigation_multiplier();
+            let timeout_ms = (opt_u64!(params, "timeout", 30000) as f64 * multiplier) as u64;
 
             match session.new_page(None).await {
                 Ok(page) => {
@@ -995,7 +1781,12 @@ async fn handle_request(
         "auth.export" => {
             let session = get_session!();
             let output = opt_str!(params, "output").map(std::path::Path::new);
-            handlers::auth::handle_auth_export(session.as_ref(), output)
+            let permissions = if opt_bool!(params, "permissions", false) {
+                session.permissions().await
+            } else {
+                Vec::new()
+            };
+            handlers::auth::handle_auth_export(session.as_ref(), output, permissions)
                 .await
                 .to_response(id)
         }
@@ -1003,9 +1794,13 @@ async fn handle_request(
         "auth.import" => {
             let session = get_session!();
             let input = require_str!("input");
-            handlers::auth::handle_auth_import(session.as_ref(), std::path::Path::new(input))
-                .await
-                .to_response(id)
+            let result =
+                handlers::auth::handle_auth_import(session.as_ref(), std::path::Path::new(input))
+                    .await;
+            if let Ok(ref imported) = result {
+                session.update_permissions(&imported.permissions).await;
+            }
+            result.to_response(id)
         }
 
         _ => Response::error(
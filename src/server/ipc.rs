@@ -6,6 +6,7 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::{UnixListener, UnixStream};
 use tokio::sync::{RwLock, broadcast, mpsc};
+use tokio::task::AbortHandle;
 
 use super::protocol::{Notification, Request, Response};
 
@@ -13,6 +14,8 @@ static CLIENT_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
 
 pub type ClientId = u64;
 
+type Subscriptions = Arc<RwLock<HashMap<ClientId, HashMap<String, AbortHandle>>>>;
+
 pub struct ClientConnection {
     pub id: ClientId,
     tx: mpsc::Sender<String>,
@@ -40,6 +43,7 @@ impl ClientConnection {
 pub struct IpcServer {
     socket_path: PathBuf,
     clients: Arc<RwLock<HashMap<ClientId, ClientConnection>>>,
+    subscriptions: Subscriptions,
     shutdown_tx: broadcast::Sender<()>,
 }
 
@@ -49,6 +53,7 @@ impl IpcServer {
         Self {
             socket_path,
             clients: Arc::new(RwLock::new(HashMap::new())),
+            subscriptions: Arc::new(RwLock::new(HashMap::new())),
             shutdown_tx,
         }
     }
@@ -84,10 +89,15 @@ impl IpcServer {
                         Ok((stream, _)) => {
                             let client_id = CLIENT_ID_COUNTER.fetch_add(1, Ordering::SeqCst);
                             let clients = self.clients.clone();
+                            let subscriptions = self.subscriptions.clone();
                             let on_request = on_request.clone();
 
                             tokio::spawn(async move {
                                 Self::handle_client(stream, client_id, clients, on_request).await;
+                                // Push tasks die with the connection
+                                if let Some(tasks) = subscriptions.write().await.remove(&client_id) {
+                                    tasks.values().for_each(AbortHandle::abort);
+                                }
                             });
                         }
                         Err(e) => {
@@ -176,10 +186,41 @@ impl IpcServer {
 
     pub async fn send_to(&self, client_id: ClientId, notification: &Notification) -> Result<()> {
         let clients = self.clients.read().await;
-        if let Some(client) = clients.get(&client_id) {
-            client.send_notification(notification).await?;
+        match clients.get(&client_id) {
+            Some(client) => client.send_notification(notification).await,
+            None => Err(ChromeError::General("Client disconnected".to_string())),
+        }
+    }
+
+    /// Tracks a task pushing notifications to `client_id` so it can be aborted
+    /// on unsubscribe or disconnect. Replaces any task under the same key.
+    pub async fn add_subscription(&self, client_id: ClientId, key: &str, task: AbortHandle) {
+        let previous = self
+            .subscriptions
+            .write()
+            .await
+            .entry(client_id)
+            .or_default()
+            .insert(key.to_string(), task);
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+    }
+
+    pub async fn remove_subscription(&self, client_id: ClientId, key: &str) -> bool {
+        let removed = self
+            .subscriptions
+            .write()
+            .await
+            .get_mut(&client_id)
+            .and_then(|tasks| tasks.remove(key));
+        match removed {
+            Some(task) => {
+                task.abort();
+                true
+            }
+            None => false,
         }
-        Ok(())
     }
 
     pub fn shutdown(&self) {
@@ -208,4 +249,21 @@ mod tests {
         let server = IpcServer::new(PathBuf::from("/tmp/test-cdtcli.sock"));
         assert_eq!(server.socket_path(), Path::new("/tmp/test-cdtcli.sock"));
     }
+
+    #[tokio::test]
+    async fn test_subscription_replace_and_remove() {
+        let server = IpcServer::new(PathBuf::from("/tmp/test-cdtcli-sub.sock"));
+        let first = tokio::spawn(std::future::pending::<()>());
+        let second = tokio::spawn(std::future::pending::<()>());
+
+        server.add_subscription(1, "s1", first.abort_handle()).await;
+        server
+            .add_subscription(1, "s1", second.abort_handle())
+            .await;
+        assert!(first.await.unwrap_err().is_cancelled());
+
+        assert!(server.remove_subscription(1, "s1").await);
+        assert!(second.await.unwrap_err().is_cancelled());
+        assert!(!server.remove_subscription(1, "s1").await);
+    }
 }
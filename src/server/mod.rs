@@ -4,6 +4,7 @@ pub mod http;
 pub mod ipc;
 pub mod protocol;
 pub mod session_pool;
+pub mod subscription;
 
 pub use daemon::{Daemon, DaemonConfig, default_socket_path};
 pub use http::{DEFAULT_HTTP_PORT, HttpServer};
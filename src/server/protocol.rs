@@ -1,3 +1,4 @@
+use crate::chrome::{ConsoleMessage, NetworkRequest, PageError};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -92,6 +93,11 @@ pub mod error_codes {
     pub const ELEMENT_NOT_FOUND: i32 = -32003;
 }
 
+/// Notification carrying a `SessionEvent` to subscribed clients
+pub const SESSION_EVENT: &str = "session.event";
+/// Notification sent when a subscribed session's event streams end
+pub const SESSION_CLOSED: &str = "session.closed";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum SessionEvent {
@@ -106,19 +112,9 @@ pub enum SessionEvent {
         uid: String,
         data: Value,
     },
-    NetworkRequest {
-        url: String,
-        method: String,
-        status: Option<u16>,
-    },
-    ConsoleMessage {
-        level: String,
-        text: String,
-    },
-    PageError {
-        message: String,
-        url: Option<String>,
-    },
+    NetworkRequest(Box<NetworkRequest>),
+    ConsoleMessage(ConsoleMessage),
+    PageError(PageError),
     DialogOpened {
         dialog_type: String,
         message: String,
@@ -152,4 +148,22 @@ mod tests {
         assert!(resp.error.is_some());
         assert_eq!(resp.error.unwrap().code, error_codes::SESSION_NOT_FOUND);
     }
+
+    #[test]
+    fn test_session_event_is_tagged() {
+        let event = SessionEvent::PageError(PageError {
+            message: "boom".to_string(),
+            url: None,
+            line: 1,
+            column: 2,
+            stack_trace: None,
+            timestamp: chrono::Utc::now(),
+        });
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["type"], "page_error");
+        assert_eq!(value["message"], "boom");
+
+        let parsed: SessionEvent = serde_json::from_value(value).unwrap();
+        assert!(matches!(parsed, SessionEvent::PageError(_)));
+    }
 }
@@ -1,11 +1,17 @@
-use crate::chrome::CollectorSet;
 use crate::chrome::storage::SessionStorage;
-use crate::config::Config;
+use crate::chrome::{ActionConfig, CollectorSet, Credentials, RequestInterceptor};
+use crate::config::{Config, NetworkConfig, ProxySettings};
+use crate::devices::EnvironmentOverrides;
+use crate::handlers::describe::RefMap;
+use crate::handlers::permissions::{self, PermissionOverride};
+use crate::handlers::throttle::{self, NetworkProfile};
+use crate::handlers::{emulation, headers};
 use crate::utils::find_chrome_executable;
 use crate::{ChromeError, Result, timeouts::secs};
 use chromiumoxide::{Browser, BrowserConfig, Page};
+use chrono::{DateTime, Utc};
 use futures::StreamExt;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::sync::Arc;
@@ -58,10 +64,20 @@ pub struct Session {
     pages: RwLock<Vec<Arc<Page>>>,
     selected_page: RwLock<usize>,
     collectors: Arc<CollectorSet>,
+    interceptor: Arc<RequestInterceptor>,
     storage: Arc<SessionStorage>,
     event_tx: broadcast::Sender<SessionEvent>,
     created_at: Instant,
     last_activity: RwLock<Instant>,
+    marks: RwLock<HashMap<String, DateTime<Utc>>>,
+    refs: RwLock<Option<RefMap>>,
+    held_modifiers: RwLock<i64>,
+    network_throttle: RwLock<Option<NetworkProfile>>,
+    cpu_throttle: RwLock<f64>,
+    environment: RwLock<EnvironmentOverrides>,
+    permissions: RwLock<Vec<PermissionOverride>>,
+    extra_headers: RwLock<BTreeMap<String, String>>,
+    proxy: Option<String>,
     headless: bool,
     uses_user_profile: bool,
 }
@@ -100,6 +116,8 @@ impl Session {
             .and_then(|src| storage.setup_extension(src).ok());
 
         let has_extension = resolved_ext.is_some();
+        let network_args = config.network.browser_args()?;
+        let proxy = config.network.proxy_settings()?;
 
         let (browser, mut handler) = if has_extension {
             let mut cmd = Command::new(&chrome_path);
@@ -138,6 +156,8 @@ impl Session {
                 cmd.arg("--profile-directory=Default");
             }
 
+            cmd.args(&network_args);
+
             cmd.spawn()
                 .map_err(|e| ChromeError::LaunchFailed(e.to_string()))?;
 
@@ -180,7 +200,8 @@ impl Session {
                 .arg("--no-first-run")
                 .arg("--no-default-browser-check")
                 .arg("--disable-features=ProfilePickerOnStartup")
-                .arg(profile_arg);
+                .arg(profile_arg)
+                .args(network_args);
 
             let browser_config = builder
                 .build()
@@ -201,6 +222,22 @@ impl Session {
             config.filters.clone(),
         ));
 
+        let interceptor = Arc::new(RequestInterceptor::new());
+        if let Some(ProxySettings {
+            username: Some(ref username),
+            ref password,
+            ..
+        }) = proxy
+        {
+            interceptor
+                .set_proxy_credentials(Some(Credentials {
+                    username: username.clone(),
+                    password: password.clone().unwrap_or_default(),
+                    origin: None,
+                }))
+                .await;
+        }
+
         let now = Instant::now();
 
         Ok(Self {
@@ -210,10 +247,20 @@ impl Session {
             pages: RwLock::new(Vec::new()),
             selected_page: RwLock::new(0),
             collectors,
+            interceptor,
             storage,
             event_tx,
             created_at: now,
             last_activity: RwLock::new(now),
+            marks: RwLock::new(HashMap::new()),
+            refs: RwLock::new(None),
+            held_modifiers: RwLock::new(0),
+            network_throttle: RwLock::new(None),
+            cpu_throttle: RwLock::new(1.0),
+            environment: RwLock::new(EnvironmentOverrides::default()),
+            permissions: RwLock::new(Vec::new()),
+            extra_headers: RwLock::new(BTreeMap::new()),
+            proxy: proxy.map(|p| p.server),
             headless,
             uses_user_profile,
         })
@@ -263,6 +310,22 @@ impl Session {
 
         let page = Arc::new(page);
         self.collectors.attach(&page).await?;
+        self.interceptor.attach(&page).await?;
+        if let Some(profile) = self.network_throttle().await {
+            throttle::apply_network(&page, Some(&profile)).await?;
+        }
+        let cpu_rate = self.cpu_throttle().await;
+        if cpu_rate > 1.0 {
+            throttle::apply_cpu(&page, cpu_rate).await?;
+        }
+        let environment = self.environment().await;
+        if !environment.is_empty() {
+            emulation::apply_environment(&page, &environment).await?;
+        }
+        let extra_headers = self.extra_headers().await;
+        if !extra_headers.is_empty() {
+            headers::apply_headers(&page, &extra_headers).await?;
+        }
 
         let mut pages = self.pages.write().await;
         pages.push(page.clone());
@@ -292,6 +355,94 @@ impl Session {
         &self.collectors
     }
 
+    pub fn interceptor(&self) -> &Arc<RequestInterceptor> {
+        &self.interceptor
+    }
+
+    /// Proxy server the browser was launched with, without credentials
+    pub fn proxy(&self) -> Option<&str> {
+        self.proxy.as_deref()
+    }
+
+    /// Records the current time under `name` for `--since` windows
+    pub async fn set_mark(&self, name: &str) -> DateTime<Utc> {
+        let now = Utc::now();
+        self.marks.write().await.insert(name.to_string(), now);
+        now
+    }
+
+    pub async fn mark(&self, name: &str) -> Option<DateTime<Utc>> {
+        self.marks.read().await.get(name).copied()
+    }
+
+    /// Replaces the refs issued by the previous `describe`
+    pub async fn set_refs(&self, refs: Option<RefMap>) {
+        *self.refs.write().await = refs;
+    }
+
+    pub async fn refs(&self) -> Option<RefMap> {
+        self.refs.read().await.clone()
+    }
+
+    /// Modifier bitmask left down by `keydown`, applied to later key and mouse input
+    pub async fn held_modifiers(&self) -> i64 {
+        *self.held_modifiers.read().await
+    }
+
+    pub async fn set_held_modifiers(&self, modifiers: i64) {
+        *self.held_modifiers.write().await = modifiers;
+    }
+
+    /// Network conditions applied by `throttle`, reapplied to every new page
+    pub async fn network_throttle(&self) -> Option<NetworkProfile> {
+        self.network_throttle.read().await.clone()
+    }
+
+    pub async fn set_network_throttle(&self, profile: Option<NetworkProfile>) {
+        *self.network_throttle.write().await = profile;
+    }
+
+    /// CPU slowdown set by `cpu-throttle`, 1.0 when unthrottled
+    pub async fn cpu_throttle(&self) -> f64 {
+        *self.cpu_throttle.read().await
+    }
+
+    pub async fn set_cpu_throttle(&self, rate: f64) {
+        *self.cpu_throttle.write().await = rate;
+    }
+
+    /// Environment overrides from `env` and device defaults, reapplied to every new page
+    pub async fn environment(&self) -> EnvironmentOverrides {
+        self.environment.read().await.clone()
+    }
+
+    pub async fn set_environment(&self, environment: EnvironmentOverrides) {
+        *self.environment.write().await = environment;
+    }
+
+    /// Permission overrides set through `permissions` or `auth import`. They live in the
+    /// browser context, so pages opened later share them.
+    pub async fn permissions(&self) -> Vec<PermissionOverride> {
+        self.permissions.read().await.clone()
+    }
+
+    pub async fn update_permissions(&self, changes: &[PermissionOverride]) {
+        permissions::merge_overrides(&mut *self.permissions.write().await, changes);
+    }
+
+    pub async fn clear_permissions(&self) {
+        self.permissions.write().await.clear();
+    }
+
+    /// Headers from `headers set`, sent by every new page
+    pub async fn extra_headers(&self) -> BTreeMap<String, String> {
+        self.extra_headers.read().await.clone()
+    }
+
+    pub async fn set_extra_headers(&self, headers: BTreeMap<String, String>) {
+        *self.extra_headers.write().await = headers;
+    }
+
     pub async fn list_pages(&self) -> Vec<PageInfo> {
         let pages = self.pages.read().await;
         let selected = *self.selected_page.read().await;
@@ -368,6 +519,26 @@ impl crate::chrome::PageProvider for Session {
     fn collectors(&self) -> &Arc<CollectorSet> {
         &self.collectors
     }
+
+    async fn all_pages(&self) -> Result<Vec<Arc<Page>>> {
+        self.get_or_create_page().await?;
+        Ok(self.pages.read().await.clone())
+    }
+
+    async fn browser(&self) -> Option<Arc<Browser>> {
+        Some(self.browser.clone())
+    }
+
+    async fn action_config(&self) -> ActionConfig {
+        ActionConfig {
+            cpu_timeout_multiplier: throttle::cpu_timeout_multiplier(self.cpu_throttle().await),
+            network_timeout_multiplier: self
+                .network_throttle()
+                .await
+                .map_or(1.0, |p| p.timeout_multiplier()),
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -402,14 +573,24 @@ impl SessionPool {
         headless: bool,
         profile_directory: Option<String>,
         extension_path: Option<&PathBuf>,
+        network: Option<NetworkConfig>,
     ) -> Result<Arc<Session>> {
+        let config = match network {
+            Some(network) => {
+                let mut config = (*self.config).clone();
+                config.network.merge(network);
+                config.network.proxy_settings()?;
+                Arc::new(config)
+            }
+            None => self.config.clone(),
+        };
         let port = self.allocate_port().await?;
         let id = uuid::Uuid::new_v4().to_string();
 
         let session = Session::new(
             id.clone(),
             port,
-            &self.config,
+            &config,
             headless,
             profile_directory,
             extension_path,
@@ -429,6 +610,16 @@ impl SessionPool {
         let session = self.sessions.write().await.remove(id);
 
         if let Some(session) = session {
+            if let Some(summary) = session.interceptor().stop_replay().await
+                && !summary.unmatched.is_empty()
+            {
+                tracing::info!(
+                    "Session {} replay of {} left {} unmatched request(s)",
+                    id,
+                    summary.source,
+                    summary.unmatched.len()
+                );
+            }
             self.release_port(session.cdp_port).await;
             // Don't cleanup storage on destroy - preserve data for history/export
             // Use `history clean` or `history delete` to remove session data
@@ -513,7 +704,7 @@ impl SessionPool {
         self.ensure_capacity(false).await?;
 
         tracing::info!("Creating user-profile session");
-        self.create_session_internal(headless, Some(String::new()), extension_path)
+        self.create_session_internal(headless, Some(String::new()), extension_path, None)
             .await
     }
 
@@ -565,9 +756,19 @@ impl SessionPool {
             selected_page: RwLock::new(0),
             storage,
             collectors,
+            interceptor: Arc::new(RequestInterceptor::new()),
             event_tx,
             created_at: now,
             last_activity: RwLock::new(now),
+            marks: RwLock::new(HashMap::new()),
+            refs: RwLock::new(None),
+            held_modifiers: RwLock::new(0),
+            network_throttle: RwLock::new(None),
+            cpu_throttle: RwLock::new(1.0),
+            environment: RwLock::new(EnvironmentOverrides::default()),
+            permissions: RwLock::new(Vec::new()),
+            extra_headers: RwLock::new(BTreeMap::new()),
+            proxy: None,
             headless: false,
             uses_user_profile: true,
         });
@@ -579,17 +780,19 @@ impl SessionPool {
         Ok(session)
     }
 
+    /// `network` overrides the configured proxy and user agent for this session only
     pub async fn create_ephemeral(
         &self,
         headless: bool,
         extension_path: Option<&PathBuf>,
+        network: Option<NetworkConfig>,
     ) -> Result<Arc<Session>> {
         self.cleanup_dead_browsers().await;
         self.cleanup_expired_ephemeral().await;
         self.ensure_capacity(true).await?;
 
         let session = self
-            .create_session_internal(headless, None, extension_path)
+            .create_session_internal(headless, None, extension_path, network)
             .await?;
         tracing::info!("Created ephemeral session: {}", session.id);
         Ok(session)
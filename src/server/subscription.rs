@@ -0,0 +1,57 @@
+use futures::StreamExt;
+use futures::stream::{BoxStream, select_all};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+
+use super::ipc::{ClientId, IpcServer};
+use super::protocol::{Notification, SESSION_CLOSED, SESSION_EVENT, SessionEvent};
+
+pub const EVENT_STREAMS: [&str; 3] = ["network", "console", "pageerror"];
+
+/// Adapts a collector feed into `SessionEvent`s, dropping items `map` rejects
+pub fn event_stream<T, F>(rx: Receiver<T>, map: F) -> BoxStream<'static, SessionEvent>
+where
+    T: Clone + Send + 'static,
+    F: Fn(T) -> Option<SessionEvent> + Send + 'static,
+{
+    futures::stream::unfold((rx, map), |(mut rx, map)| async move {
+        loop {
+            match rx.recv().await {
+                Ok(item) => {
+                    if let Some(event) = map(item) {
+                        return Some((event, (rx, map)));
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Subscriber lagged, dropped {} events", skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Pushes events to the client until it disconnects or every stream closes
+pub async fn forward_events(
+    ipc: Arc<IpcServer>,
+    client_id: ClientId,
+    session_id: String,
+    streams: Vec<BoxStream<'static, SessionEvent>>,
+) {
+    let mut events = select_all(streams);
+
+    while let Some(event) = events.next().await {
+        let notification = Notification::new(
+            SESSION_EVENT,
+            json!({"session_id": session_id, "event": event}),
+        );
+        if ipc.send_to(client_id, &notification).await.is_err() {
+            return;
+        }
+    }
+
+    let closed = Notification::new(SESSION_CLOSED, json!({"session_id": session_id}));
+    ipc.send_to(client_id, &closed).await.ok();
+}